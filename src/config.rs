@@ -5,9 +5,14 @@ use config_crate::{Config as RawConfig, ConfigError, Environment, File};
 use stq_http;
 use stq_logging::GrayLogConfig;
 use stq_routes::service::Service as StqService;
+use stq_static_resources::{OrderState, Project};
+use stq_types::CompanyPackageId;
 
 use sentry_integration::SentryConfig;
 
+/// Environment variable holding the secret billing signs state callbacks with
+const BILLING_CALLBACK_SECRET_VAR: &str = "STQ_SAGA_BILLING_CALLBACK_SECRET";
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Config {
     pub server: Server,
@@ -24,6 +29,15 @@ pub struct Config {
     pub client: Client,
     pub sentry: Option<SentryConfig>,
     pub service: Service,
+    pub billing_callback: BillingCallback,
+    pub scheduler: Scheduler,
+    pub currencies: Currencies,
+    pub store_deactivation: StoreDeactivation,
+    pub moderation: Moderation,
+    #[serde(default)]
+    pub carriers: Vec<Carrier>,
+    #[serde(default)]
+    pub order_notifications: OrderNotifications,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
@@ -72,6 +86,178 @@ pub struct ProjectUrls {
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Service {
     pub processing_timeout_ms: u64,
+    /// How many items of bulk request are processed at the same time
+    pub bulk_concurrency: usize,
+    /// How many items bulk request may contain
+    pub bulk_max_items: usize,
+}
+
+/// Settings for verifying signed state callbacks coming from billing
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BillingCallback {
+    /// Shared secret used by billing to sign the callback body
+    pub secret: String,
+    /// Maximum allowed difference between the signature timestamp and local time
+    pub timestamp_tolerance_s: i64,
+}
+
+/// Settings of periodic jobs run by saga coordinator itself
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Scheduler {
+    pub tick_interval_s: u64,
+    /// How long `Delivered` and `Received` orders are held before they are completed automatically
+    pub delivered_hold_period_h: i64,
+    /// How long the seller has to confirm a `Paid` order before it is declined automatically
+    pub confirmation_sla_h: i64,
+    /// How long before the confirmation deadline the seller is reminded about the order
+    pub confirmation_reminder_before_h: i64,
+    /// How long before the promised ship date the seller is reminded about the pre-order
+    pub pre_order_reminder_before_h: i64,
+    /// How often sellers are paid for completed orders
+    pub payout_interval_h: i64,
+}
+
+/// Currencies sent to stores microservice when the request does not specify them
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Currencies {
+    pub currency: String,
+    pub fiat_currency: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct StoreDeactivation {
+    pub open_orders: OpenOrdersPolicy,
+}
+
+/// What store deactivation does when the store has orders that are not completed or cancelled yet
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OpenOrdersPolicy {
+    /// Store is not deactivated until its open orders are finished
+    Block,
+    /// Store is deactivated and buyers of its open orders are notified
+    NotifyBuyers,
+}
+
+/// Settings of the moderation queue of stores and base products
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Moderation {
+    /// How long the assigned moderator has before the subject is reassigned to another one
+    pub assignment_timeout_h: i64,
+    /// How long the subject waits for its moderator before they are reminded about it
+    pub reminder_after_h: i64,
+    /// How long the subject waits since it was sent to moderation before all moderators are told about it
+    pub escalation_after_h: i64,
+    /// Changes of these fields of published base product have to be approved by moderator
+    pub sensitive_fields: Vec<BaseProductField>,
+}
+
+/// Field of base product that can be changed by seller
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BaseProductField {
+    Name,
+    ShortDescription,
+    LongDescription,
+    SeoTitle,
+    SeoDescription,
+    Currency,
+    CategoryId,
+    Slug,
+    LengthCm,
+    WidthCm,
+    HeightCm,
+    WeightG,
+}
+
+/// Delivery carrier used for validating tracking numbers and building tracking links
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Carrier {
+    pub name: String,
+    /// Company packages delivered by this carrier
+    pub company_package_ids: Vec<CompanyPackageId>,
+    /// Regular expression the whole tracking number must match
+    pub tracking_number_pattern: String,
+    /// Tracking page url, `{track_id}` is replaced with tracking number
+    pub tracking_url: String,
+}
+
+/// Which order state changes are emailed to whom, per project
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct OrderNotifications {
+    pub marketplace: OrderNotificationRules,
+    pub wallet: OrderNotificationRules,
+}
+
+impl OrderNotifications {
+    pub fn for_project(&self, project: &Project) -> &OrderNotificationRules {
+        match project {
+            Project::MarketPlace => &self.marketplace,
+            Project::Wallet => &self.wallet,
+        }
+    }
+}
+
+impl Default for OrderNotifications {
+    fn default() -> Self {
+        Self {
+            marketplace: OrderNotificationRules::default(),
+            wallet: OrderNotificationRules::default(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct OrderNotificationRules {
+    pub customer: RecipientNotificationRules,
+    pub store: RecipientNotificationRules,
+    /// Order states moderators are notified about, moderators can not opt out
+    pub moderators: Vec<OrderState>,
+}
+
+impl Default for OrderNotificationRules {
+    fn default() -> Self {
+        let states = vec![
+            OrderState::Paid,
+            OrderState::InProcessing,
+            OrderState::Cancelled,
+            OrderState::Sent,
+            OrderState::Delivered,
+            OrderState::Received,
+            OrderState::Dispute,
+            OrderState::Complete,
+        ];
+        Self {
+            customer: RecipientNotificationRules {
+                states: states.clone(),
+                opt_out_allowed: vec![
+                    OrderState::InProcessing,
+                    OrderState::Delivered,
+                    OrderState::Received,
+                    OrderState::Complete,
+                ],
+            },
+            store: RecipientNotificationRules {
+                states,
+                opt_out_allowed: vec![OrderState::Delivered, OrderState::Received, OrderState::Complete],
+            },
+            moderators: vec![OrderState::Dispute],
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RecipientNotificationRules {
+    /// Order states the recipient is notified about
+    pub states: Vec<OrderState>,
+    /// Order states the recipient may opt out of
+    pub opt_out_allowed: Vec<OrderState>,
+}
+
+impl RecipientNotificationRules {
+    pub fn should_notify(&self, state: OrderState, opt_outs: &[OrderState]) -> bool {
+        self.states.contains(&state) && !(self.opt_out_allowed.contains(&state) && opt_outs.contains(&state))
+    }
 }
 
 impl Config {
@@ -82,6 +268,33 @@ impl Config {
         let mut s = RawConfig::new();
 
         s.set_default("service.processing_timeout_ms", 1000 as i64).unwrap();
+        s.set_default("service.bulk_concurrency", 4 as i64).unwrap();
+        s.set_default("service.bulk_max_items", 100 as i64).unwrap();
+        s.set_default("billing_callback.timestamp_tolerance_s", 300 as i64).unwrap();
+        s.set_default("scheduler.tick_interval_s", 600 as i64).unwrap();
+        s.set_default("scheduler.delivered_hold_period_h", 14 * 24 as i64).unwrap();
+        s.set_default("scheduler.confirmation_sla_h", 72 as i64).unwrap();
+        s.set_default("scheduler.confirmation_reminder_before_h", 24 as i64).unwrap();
+        s.set_default("scheduler.pre_order_reminder_before_h", 48 as i64).unwrap();
+        s.set_default("scheduler.payout_interval_h", 24 as i64).unwrap();
+        s.set_default("currencies.currency", "STQ").unwrap();
+        s.set_default("currencies.fiat_currency", "USD").unwrap();
+        s.set_default("store_deactivation.open_orders", "block").unwrap();
+        s.set_default("moderation.assignment_timeout_h", 24 as i64).unwrap();
+        s.set_default("moderation.reminder_after_h", 12 as i64).unwrap();
+        s.set_default("moderation.escalation_after_h", 48 as i64).unwrap();
+        s.set_default(
+            "moderation.sensitive_fields",
+            vec![
+                "name",
+                "short_description",
+                "long_description",
+                "seo_title",
+                "seo_description",
+                "category_id",
+            ],
+        )
+        .unwrap();
 
         s.merge(File::with_name("config/base"))?;
 
@@ -92,6 +305,13 @@ impl Config {
         // Add in settings from the environment (with a prefix of STQ_SAGA)
         s.merge(Environment::with_prefix("STQ_SAGA"))?;
 
+        // Billing callback secret is not kept in config files, service does not start without it
+        let billing_callback_secret = env::var(BILLING_CALLBACK_SECRET_VAR)
+            .ok()
+            .filter(|secret| !secret.is_empty())
+            .ok_or_else(|| ConfigError::NotFound(BILLING_CALLBACK_SECRET_VAR.to_string()))?;
+        s.set("billing_callback.secret", billing_callback_secret)?;
+
         s.try_into()
     }
 
@@ -0,0 +1,130 @@
+//! Verification of signed billing callbacks. Billing signs every state callback
+//! with HMAC-SHA256 over `<timestamp>.<body>` using the shared secret from config.
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use chrono::Utc;
+use failure::Error as FailureError;
+use failure::Fail;
+use hex;
+use hmac::{Hmac, Mac};
+use hyper::header::Headers;
+use sha2::Sha256;
+
+use config::BillingCallback;
+use errors::Error;
+
+header! { (BillingSignature, "X-Billing-Signature") => [String] }
+header! { (BillingTimestamp, "X-Billing-Timestamp") => [String] }
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Signatures of callbacks that were already accepted, kept while they are
+/// still inside the timestamp tolerance window.
+///
+/// Signatures are kept in memory of this process only: a callback replayed to
+/// another replica or after a restart passes this check. Such replays are still
+/// skipped by order state deduplication using billing event sequence numbers.
+#[derive(Debug, Default)]
+pub struct ReplayGuard {
+    seen: HashMap<String, i64>,
+}
+
+impl ReplayGuard {
+    /// Returns `false` if this signature was already accepted
+    pub fn remember(&mut self, signature: &str, timestamp: i64, now: i64, tolerance_s: i64) -> bool {
+        self.seen.retain(|_, seen_timestamp| (now - *seen_timestamp).abs() <= tolerance_s);
+        if self.seen.contains_key(signature) {
+            return false;
+        }
+        self.seen.insert(signature.to_string(), timestamp);
+        true
+    }
+}
+
+pub fn verify_billing_callback(
+    config: &BillingCallback,
+    replay_guard: &Arc<Mutex<ReplayGuard>>,
+    headers: &Headers,
+    body: &str,
+) -> Result<(), FailureError> {
+    let signature = headers
+        .get::<BillingSignature>()
+        .map(|header| header.0.clone())
+        .ok_or_else(|| format_err!("Billing callback signature header is missing").context(Error::Unauthorized))?;
+    let timestamp = headers
+        .get::<BillingTimestamp>()
+        .map(|header| header.0.clone())
+        .ok_or_else(|| format_err!("Billing callback timestamp header is missing").context(Error::Unauthorized))?;
+
+    verify_signature(&config.secret, &timestamp, body, &signature)?;
+
+    let timestamp = timestamp
+        .parse::<i64>()
+        .map_err(|e| e.context("Billing callback timestamp is not a number").context(Error::Unauthorized))?;
+    let now = Utc::now().timestamp();
+    if (now - timestamp).abs() > config.timestamp_tolerance_s {
+        return Err(
+            format_err!("Billing callback timestamp {} is outside of the allowed window", timestamp)
+                .context(Error::Unauthorized)
+                .into(),
+        );
+    }
+
+    if !replay_guard
+        .lock()
+        .unwrap()
+        .remember(&signature, timestamp, now, config.timestamp_tolerance_s)
+    {
+        return Err(format_err!("Billing callback with signature {} was already processed", signature)
+            .context(Error::Unauthorized)
+            .into());
+    }
+
+    Ok(())
+}
+
+fn verify_signature(secret: &str, timestamp: &str, body: &str, signature: &str) -> Result<(), FailureError> {
+    let signature = hex::decode(signature).map_err(|e| {
+        e.context("Billing callback signature is not hex encoded")
+            .context(Error::Unauthorized)
+    })?;
+
+    let mut mac = HmacSha256::new_varkey(secret.as_bytes()).map_err(|_| format_err!("Invalid billing callback secret"))?;
+    mac.input(timestamp.as_bytes());
+    mac.input(b".");
+    mac.input(body.as_bytes());
+    mac.verify(&signature).map_err(|_| {
+        format_err!("Billing callback signature does not match")
+            .context(Error::Unauthorized)
+            .into()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BODY: &str = r#"[{"order_id":1}]"#;
+    const SIGNATURE: &str = "beb54b86f5ff564e39499953b162deede93ffdf59a9b5ec961892ef2b435bdc2";
+
+    #[test]
+    fn valid_signature() {
+        assert!(verify_signature("secret", "1540000000", BODY, SIGNATURE).is_ok());
+    }
+
+    #[test]
+    fn tampered_body() {
+        assert!(verify_signature("secret", "1540000000", r#"[{"order_id":2}]"#, SIGNATURE).is_err());
+        assert!(verify_signature("secret", "1540000001", BODY, SIGNATURE).is_err());
+        assert!(verify_signature("other", "1540000000", BODY, SIGNATURE).is_err());
+    }
+
+    #[test]
+    fn replayed_signature() {
+        let mut guard = ReplayGuard::default();
+        assert!(guard.remember(SIGNATURE, 1540000000, 1540000010, 300));
+        assert!(!guard.remember(SIGNATURE, 1540000000, 1540000020, 300));
+        assert!(guard.remember(SIGNATURE, 1540000000, 1540001000, 300));
+    }
+}
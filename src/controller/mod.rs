@@ -2,10 +2,11 @@
 //! stuff like reading bodies, parsing params, forming a response.
 //! Basically it provides inputs to `Service` layer and converts outputs
 //! of `Service` layer to http responses
+pub mod billing_callback;
 pub mod requests;
 pub mod routes;
 
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
 
 use failure::Error as FailureError;
@@ -16,21 +17,27 @@ use hyper::header::Authorization;
 use hyper::header::Headers;
 use hyper::server::Request;
 use hyper::Method;
+use serde_json;
 
 use stq_http::client::{ClientHandle as HttpClientHandle, HttpClientWithDefaultHeaders, TimeLimitedHttpClient};
 use stq_http::controller::Controller;
 use stq_http::controller::ControllerFuture;
 use stq_http::errors::ErrorMessageWrapper;
 use stq_http::request_util::parse_body;
+use stq_http::request_util::read_body;
 use stq_http::request_util::serialize_future;
 use stq_http::request_util::CorrelationToken as CorrelationTokenHeader;
 use stq_http::request_util::RequestTimeout as RequestTimeoutHeader;
 use stq_http::request_util::{Currency as CurrencyHeader, FiatCurrency as FiatCurrencyHeader};
 use stq_router::RouteParser;
+use stq_static_resources::Project;
+use stq_types::UserId;
 
+use self::billing_callback::{verify_billing_callback, ReplayGuard};
 use self::routes::Route;
-use config::Config;
+use config::{Config, Currencies};
 use errors::Error;
+use metrics;
 use microservice::{
     BillingMicroserviceImpl, DeliveryMicroserviceImpl, NotificationsMicroserviceImpl, OrdersMicroserviceImpl, StoresMicroserviceImpl,
     UsersMicroserviceImpl, WarehousesMicroserviceImpl,
@@ -42,10 +49,14 @@ use services::delivery::{DeliveryService, DeliveryServiceImpl};
 use services::order::{OrderService, OrderServiceImpl};
 use services::store::{StoreService, StoreServiceImpl};
 
+header! { (ProjectHeader, "X-Project") => [String] }
+
 pub struct ControllerImpl {
     pub config: Config,
     pub http_client: HttpClientHandle,
     pub route_parser: Arc<RouteParser<Route>>,
+    pub billing_replay_guard: Arc<Mutex<ReplayGuard>>,
+    pub carriers: Arc<CarrierRegistry>,
 }
 
 impl Controller for ControllerImpl {
@@ -68,7 +79,7 @@ impl Controller for ControllerImpl {
         ));
 
         let stores_microservice = Arc::new(StoresMicroserviceImpl::new(
-            HttpClientWithDefaultHeaders::new(http_client.clone(), stores_headers(&headers)),
+            HttpClientWithDefaultHeaders::new(http_client.clone(), stores_headers(&headers, &self.config.currencies)),
             self.config.clone(),
         ));
 
@@ -126,6 +137,9 @@ impl Controller for ControllerImpl {
             users_microservice.clone(),
             billing_microservice.clone(),
             warehouses_microservice.clone(),
+            self.carriers.clone(),
+            user_id(&headers),
+            project(&headers),
         );
 
         let delivery_service = DeliveryServiceImpl::new(
@@ -181,6 +195,16 @@ impl Controller for ControllerImpl {
                             .request_email_verification_apply(profile)
                             .map(|(_, user)| user)
                             .map_err(|(_, e)| FailureError::from(e.context("Error during email verification apply occurred.")))
+                    })
+                    .and_then(move |token| {
+                        // staff invitations are sent to the email, so they are accepted once it is verified
+                        let user = token.user.clone();
+                        store_service.accept_staff_invitations(user.id, user.email).then(move |res| {
+                            if let Err((_, e)) = res {
+                                log_and_capture_error(&FailureError::from(e.context("Accepting store staff invitations failed.")));
+                            }
+                            Ok(token)
+                        })
                     }),
             ),
             (&Method::Post, Some(Route::ResetPassword)) => serialize_future(
@@ -241,6 +265,17 @@ impl Controller for ControllerImpl {
                     }),
             ),
 
+            (&Method::Post, Some(Route::OrdersQuote)) => serialize_future(
+                parse_body::<ConvertCart>(req.body())
+                    .map_err(|e| FailureError::from(e.context("Parsing body failed, target: ConvertCart").context(Error::Parse)))
+                    .and_then(move |cart| {
+                        order_service
+                            .quote(cart)
+                            .map(|(_, quote)| quote)
+                            .map_err(|(_, e)| FailureError::from(e.context("Error during order quote occurred.")))
+                    }),
+            ),
+
             (&Method::Post, Some(Route::BuyNow)) => serialize_future(
                 parse_body::<BuyNow>(req.body())
                     .map_err(|e| FailureError::from(e.context("Parsing body // POST /buy_now in BuyNow failed!").context(Error::Parse)))
@@ -252,21 +287,28 @@ impl Controller for ControllerImpl {
                     }),
             ),
 
-            (&Method::Post, Some(Route::OrdersUpdateStateByBilling)) => serialize_future(
-                parse_body::<BillingOrdersVec>(req.body())
-                    .map_err(|e| {
-                        FailureError::from(
-                            e.context("Parsing body // POST /orders/update_state in BillingOrdersVec failed!")
-                                .context(Error::Parse),
-                        )
+            (&Method::Post, Some(Route::OrdersUpdateStateByBilling)) => serialize_future({
+                let billing_callback = self.config.billing_callback.clone();
+                let billing_replay_guard = self.billing_replay_guard.clone();
+                let headers = headers.clone();
+                read_body(req.body())
+                    .map_err(|e| FailureError::from(e.context("Reading body // POST /orders/update_state failed!").context(Error::Parse)))
+                    .and_then(move |body| verify_billing_callback(&billing_callback, &billing_replay_guard, &headers, &body).map(|_| body))
+                    .and_then(|body| {
+                        serde_json::from_str::<BillingOrdersVec>(&body).map_err(|e| {
+                            FailureError::from(
+                                e.context("Parsing body // POST /orders/update_state in BillingOrdersVec failed!")
+                                    .context(Error::Parse),
+                            )
+                        })
                     })
                     .and_then(move |orders_info| {
                         order_service
                             .update_state_by_billing(orders_info)
                             .map(|(_, _)| ())
                             .map_err(|(_, e)| FailureError::from(e.context("Error during orders update by external billing occurred.")))
-                    }),
-            ),
+                    })
+            }),
 
             (&Method::Post, Some(Route::OrdersManualSetState { order_slug })) => serialize_future(
                 parse_body::<UpdateStatePayload>(req.body())
@@ -287,6 +329,22 @@ impl Controller for ControllerImpl {
                     }),
             ),
 
+            (&Method::Post, Some(Route::OrdersBulkSetState)) => serialize_future(
+                parse_body::<BulkUpdateStatePayload>(req.body())
+                    .map_err(|e| {
+                        FailureError::from(
+                            e.context("Parsing body failed, target: BulkUpdateStatePayload")
+                                .context(Error::Parse),
+                        )
+                    })
+                    .and_then(move |payload| {
+                        order_service
+                            .bulk_set_state(payload)
+                            .map(|(_, results)| results)
+                            .map_err(|(_, e)| FailureError::from(e.context("Error during orders bulk update occurred.")))
+                    }),
+            ),
+
             (&Method::Post, Some(Route::OrdersSetPaymentState { order_id })) => serialize_future({
                 parse_body::<OrderPaymentStateRequest>(req.body())
                     .map_err(move |e| {
@@ -303,6 +361,51 @@ impl Controller for ControllerImpl {
                     })
             }),
 
+            (&Method::Post, Some(Route::OrdersResolveDispute(order_slug, resolution))) => serialize_future(
+                parse_body::<ResolveDisputePayload>(req.body())
+                    .map_err(|e| {
+                        FailureError::from(
+                            e.context("Parsing body failed, target: ResolveDisputePayload")
+                                .context(Error::Parse),
+                        )
+                    })
+                    .and_then(move |payload| {
+                        order_service
+                            .resolve_dispute(order_slug, resolution, payload)
+                            .map(|(_, order)| order)
+                            .map_err(|(_, e)| FailureError::from(e.context("Error during order dispute resolution occurred.")))
+                    }),
+            ),
+
+            (&Method::Get, Some(Route::OrderHistory { order_slug })) => serialize_future(
+                order_service
+                    .get_order_history(order_slug)
+                    .map(|(_, history)| history)
+                    .map_err(|(_, e)| FailureError::from(e.context("Error during getting order history occurred."))),
+            ),
+
+            (&Method::Post, Some(Route::UserNotificationOptOuts(user_id))) => serialize_future(
+                parse_body::<NotificationOptOuts>(req.body())
+                    .map_err(|e| FailureError::from(e.context("Parsing body failed, target: NotificationOptOuts").context(Error::Parse)))
+                    .and_then(move |opt_outs| {
+                        order_service
+                            .set_user_notification_opt_outs(user_id, opt_outs)
+                            .map(|(_, opt_outs)| opt_outs)
+                            .map_err(|(_, e)| FailureError::from(e.context("Error during setting user notification opt-outs occurred.")))
+                    }),
+            ),
+
+            (&Method::Post, Some(Route::StoreNotificationOptOuts(store_id))) => serialize_future(
+                parse_body::<NotificationOptOuts>(req.body())
+                    .map_err(|e| FailureError::from(e.context("Parsing body failed, target: NotificationOptOuts").context(Error::Parse)))
+                    .and_then(move |opt_outs| {
+                        order_service
+                            .set_store_notification_opt_outs(store_id, opt_outs)
+                            .map(|(_, opt_outs)| opt_outs)
+                            .map_err(|(_, e)| FailureError::from(e.context("Error during setting store notification opt-outs occurred.")))
+                    }),
+            ),
+
             // POST /stores/moderate
             (&Method::Post, Some(Route::StoreModerate)) => serialize_future(
                 parse_body::<StoreModerate>(req.body())
@@ -323,6 +426,14 @@ impl Controller for ControllerImpl {
                     .map_err(|(_, e)| FailureError::from(e.context("Error sending store to moderation occurred."))),
             ),
 
+            // DELETE /stores/<store_id>
+            (&Method::Delete, Some(Route::Store(store_id))) => serialize_future(
+                store_service
+                    .delete_store(store_id)
+                    .map(|(_, store)| store)
+                    .map_err(|(_, e)| FailureError::from(e.context("Error deleting store occurred."))),
+            ),
+
             // POST /stores/<store_id>/deactivate
             (&Method::Post, Some(Route::StoreDeactivate(store_id))) => serialize_future(
                 store_service
@@ -331,6 +442,71 @@ impl Controller for ControllerImpl {
                     .map_err(|(_, e)| FailureError::from(e.context("Error deactivating store occurred."))),
             ),
 
+            // POST /stores/<store_id>/reactivate
+            (&Method::Post, Some(Route::StoreReactivate(store_id))) => serialize_future(
+                store_service
+                    .reactivate_store(store_id)
+                    .map(|(_, store)| store)
+                    .map_err(|(_, e)| FailureError::from(e.context("Error reactivating store occurred."))),
+            ),
+
+            // POST /stores/<store_id>/transfer_ownership
+            (&Method::Post, Some(Route::StoreTransferOwnership(store_id))) => serialize_future(
+                parse_body::<TransferStoreOwnership>(req.body())
+                    .map_err(|e| {
+                        FailureError::from(
+                            e.context("Parsing body failed, target: TransferStoreOwnership")
+                                .context(Error::Parse),
+                        )
+                    })
+                    .and_then(move |payload| {
+                        store_service
+                            .transfer_ownership(store_id, payload)
+                            .map(|(_, store)| store)
+                            .map_err(|(_, e)| FailureError::from(e.context("Error transferring store ownership occurred.")))
+                    }),
+            ),
+
+            // POST /stores/<store_id>/staff
+            (&Method::Post, Some(Route::StoreStaff(store_id))) => serialize_future(
+                parse_body::<NewStoreStaff>(req.body())
+                    .map_err(|e| FailureError::from(e.context("Parsing body failed, target: NewStoreStaff").context(Error::Parse)))
+                    .and_then(move |payload| {
+                        store_service
+                            .add_staff(store_id, payload)
+                            .map(|(_, staff_member)| staff_member)
+                            .map_err(|(_, e)| FailureError::from(e.context("Error adding store staff member occurred.")))
+                    }),
+            ),
+
+            // GET /stores/<store_id>/staff
+            (&Method::Get, Some(Route::StoreStaff(store_id))) => serialize_future(
+                store_service
+                    .get_staff(store_id)
+                    .map(|(_, staff)| staff)
+                    .map_err(|(_, e)| FailureError::from(e.context("Error getting store staff occurred."))),
+            ),
+
+            // POST /stores/<store_id>/staff/invite
+            (&Method::Post, Some(Route::StoreStaffInvite(store_id))) => serialize_future(
+                parse_body::<StoreStaffInvite>(req.body())
+                    .map_err(|e| FailureError::from(e.context("Parsing body failed, target: StoreStaffInvite").context(Error::Parse)))
+                    .and_then(move |payload| {
+                        store_service
+                            .invite_staff(store_id, payload)
+                            .map(|(_, result)| result)
+                            .map_err(|(_, e)| FailureError::from(e.context("Error inviting store staff member occurred.")))
+                    }),
+            ),
+
+            // DELETE /stores/<store_id>/staff/<user_id>
+            (&Method::Delete, Some(Route::StoreStaffMember(store_id, user_id))) => serialize_future(
+                store_service
+                    .remove_staff(store_id, user_id)
+                    .map(|(_, _)| ())
+                    .map_err(|(_, e)| FailureError::from(e.context("Error removing store staff member occurred."))),
+            ),
+
             // POST /base_products/moderate
             (&Method::Post, Some(Route::BaseProductModerate)) => serialize_future(
                 parse_body::<BaseProductModerate>(req.body())
@@ -343,6 +519,18 @@ impl Controller for ControllerImpl {
                     }),
             ),
 
+            // POST /moderate/bulk
+            (&Method::Post, Some(Route::BulkModerate)) => serialize_future(
+                parse_body::<BulkModeratePayload>(req.body())
+                    .map_err(|e| FailureError::from(e.context("Parsing body failed, target: BulkModeratePayload").context(Error::Parse)))
+                    .and_then(move |payload| {
+                        store_service
+                            .bulk_moderate(payload)
+                            .map(|(_, results)| results)
+                            .map_err(|(_, e)| FailureError::from(e.context("Error during bulk moderation occurred.")))
+                    }),
+            ),
+
             // POST /base_products/moderation
             (&Method::Post, Some(Route::BaseProductModeration(base_product_id))) => serialize_future(
                 store_service
@@ -359,6 +547,14 @@ impl Controller for ControllerImpl {
                     .map_err(|(_, e)| FailureError::from(e.context("Error deactivating base product occurred."))),
             ),
 
+            // POST /base_products/<base_product_id>/reactivate
+            (&Method::Post, Some(Route::BaseProductReactivate(base_product_id))) => serialize_future(
+                store_service
+                    .reactivate_base_product(base_product_id)
+                    .map(|(_, base_product)| base_product)
+                    .map_err(|(_, e)| FailureError::from(e.context("Error reactivating base product occurred."))),
+            ),
+
             // POST /base_products/<base_product_id>/update
             (&Method::Post, Some(Route::BaseProductUpdate(base_product_id))) => serialize_future(
                 parse_body::<UpdateBaseProduct>(req.body())
@@ -400,6 +596,14 @@ impl Controller for ControllerImpl {
                     }),
             ),
 
+            // GET /base_products/<base_product_id>/pending_changes
+            (&Method::Get, Some(Route::BaseProductPendingChanges(base_product_id))) => serialize_future(
+                store_service
+                    .get_base_product_pending_changes(base_product_id)
+                    .map(|(_, changes)| changes)
+                    .map_err(|(_, e)| FailureError::from(e.context("Error getting base product pending changes occurred."))),
+            ),
+
             // POST /products/<product_id>/deactivate
             (&Method::Post, Some(Route::ProductDeactivate(product_id))) => serialize_future(
                 store_service
@@ -408,6 +612,17 @@ impl Controller for ControllerImpl {
                     .map_err(|(_, e)| FailureError::from(e.context("Error deactivating product occurred."))),
             ),
 
+            // GET /moderators/<moderator_id>/assignments
+            (&Method::Get, Some(Route::ModeratorAssignments(moderator_id))) => serialize_future(
+                store_service
+                    .get_moderator_assignments(moderator_id)
+                    .map(|(_, assignments)| assignments)
+                    .map_err(|(_, e)| FailureError::from(e.context("Error getting moderator assignments occurred."))),
+            ),
+
+            // GET /metrics
+            (&Method::Get, Some(Route::Metrics)) => Box::new(future::result(metrics::render())),
+
             // Fallback
             (m, _) => Box::new(future::err(
                 format_err!(
@@ -442,9 +657,31 @@ fn default_headers(request_headers: &Headers) -> Headers {
     headers
 }
 
-fn stores_headers(request_headers: &Headers) -> Headers {
+fn user_id(request_headers: &Headers) -> Option<UserId> {
+    request_headers
+        .get::<Authorization<String>>()
+        .and_then(|auth| auth.0.parse::<UserId>().ok())
+}
+
+/// Project is spelled the same way as in request bodies, unknown projects are ignored
+fn project(request_headers: &Headers) -> Option<Project> {
+    request_headers
+        .get::<ProjectHeader>()
+        .and_then(|project| serde_json::from_value(serde_json::Value::String(project.0.clone())).ok())
+}
+
+/// Currency headers of the request are passed to stores microservice as is, missing ones are taken from config
+fn stores_headers(request_headers: &Headers, currencies: &Currencies) -> Headers {
     let mut stores_headers = default_headers(request_headers);
-    stores_headers.set(CurrencyHeader("STQ".to_string()));
-    stores_headers.set(FiatCurrencyHeader("USD".to_string()));
+    let currency = request_headers
+        .get::<CurrencyHeader>()
+        .cloned()
+        .unwrap_or_else(|| CurrencyHeader(currencies.currency.clone()));
+    let fiat_currency = request_headers
+        .get::<FiatCurrencyHeader>()
+        .cloned()
+        .unwrap_or_else(|| FiatCurrencyHeader(currencies.fiat_currency.clone()));
+    stores_headers.set(currency);
+    stores_headers.set(fiat_currency);
     stores_headers
 }
@@ -1,5 +1,7 @@
 use stq_router::RouteParser;
-use stq_types::{BaseProductId, OrderId, OrderSlug, ProductId, StoreId};
+use stq_types::{BaseProductId, OrderId, OrderSlug, ProductId, StoreId, UserId};
+
+use models::DisputeResolution;
 
 #[derive(Clone, Debug, PartialEq)]
 pub enum Route {
@@ -10,20 +12,37 @@ pub enum Route {
     ResetPasswordApply,
     CreateStore,
     CreateOrder,
+    OrdersQuote,
     BuyNow,
     OrdersUpdateStateByBilling,
     OrdersManualSetState { order_slug: OrderSlug },
+    OrdersBulkSetState,
+    Store(StoreId),
     StoreModerate,
     StoreModeration(StoreId),
     StoreDeactivate(StoreId),
+    StoreReactivate(StoreId),
+    StoreTransferOwnership(StoreId),
+    StoreStaff(StoreId),
+    StoreStaffInvite(StoreId),
+    StoreStaffMember(StoreId, UserId),
     BaseProductUpdate(BaseProductId),
     BaseProductCreateWithVariants,
     BaseProductModerate,
+    BulkModerate,
     BaseProductDeactivate(BaseProductId),
+    BaseProductReactivate(BaseProductId),
     BaseProductUpsertShipping(BaseProductId),
+    BaseProductPendingChanges(BaseProductId),
     BaseProductModeration(BaseProductId),
     ProductDeactivate(ProductId),
     OrdersSetPaymentState { order_id: OrderId },
+    OrdersResolveDispute(OrderSlug, DisputeResolution),
+    OrderHistory { order_slug: OrderSlug },
+    UserNotificationOptOuts(UserId),
+    StoreNotificationOptOuts(StoreId),
+    ModeratorAssignments(UserId),
+    Metrics,
 }
 
 pub fn create_route_parser() -> RouteParser<Route> {
@@ -45,6 +64,15 @@ pub fn create_route_parser() -> RouteParser<Route> {
 
     router.add_route(r"^/buy_now$", || Route::BuyNow);
 
+    router.add_route(r"^/orders/quote$", || Route::OrdersQuote);
+
+    router.add_route_with_params(r"^/stores/(\d+)$", |params| {
+        params
+            .get(0)
+            .and_then(|string_id| string_id.parse::<StoreId>().ok())
+            .map(Route::Store)
+    });
+
     router.add_route(r"^/stores/moderate$", || Route::StoreModerate);
 
     router.add_route_with_params(r"^/stores/(\d+)/moderation$", |params| {
@@ -61,8 +89,44 @@ pub fn create_route_parser() -> RouteParser<Route> {
             .map(Route::StoreDeactivate)
     });
 
+    router.add_route_with_params(r"^/stores/(\d+)/reactivate$", |params| {
+        params
+            .get(0)
+            .and_then(|string_id| string_id.parse::<StoreId>().ok())
+            .map(Route::StoreReactivate)
+    });
+
+    router.add_route_with_params(r"^/stores/(\d+)/transfer_ownership$", |params| {
+        params
+            .get(0)
+            .and_then(|string_id| string_id.parse::<StoreId>().ok())
+            .map(Route::StoreTransferOwnership)
+    });
+
+    router.add_route_with_params(r"^/stores/(\d+)/staff$", |params| {
+        params
+            .get(0)
+            .and_then(|string_id| string_id.parse::<StoreId>().ok())
+            .map(Route::StoreStaff)
+    });
+
+    router.add_route_with_params(r"^/stores/(\d+)/staff/invite$", |params| {
+        params
+            .get(0)
+            .and_then(|string_id| string_id.parse::<StoreId>().ok())
+            .map(Route::StoreStaffInvite)
+    });
+
+    router.add_route_with_params(r"^/stores/(\d+)/staff/(\d+)$", |params| {
+        let store_id = params.get(0).and_then(|string_id| string_id.parse::<StoreId>().ok())?;
+        let user_id = params.get(1).and_then(|string_id| string_id.parse::<UserId>().ok())?;
+        Some(Route::StoreStaffMember(store_id, user_id))
+    });
+
     router.add_route(r"^/base_products/moderate$", || Route::BaseProductModerate);
 
+    router.add_route(r"^/moderate/bulk$", || Route::BulkModerate);
+
     router.add_route_with_params(r"^/base_products/(\d+)/moderation$", |params| {
         params
             .get(0)
@@ -77,6 +141,13 @@ pub fn create_route_parser() -> RouteParser<Route> {
             .map(Route::BaseProductDeactivate)
     });
 
+    router.add_route_with_params(r"^/base_products/(\d+)/reactivate$", |params| {
+        params
+            .get(0)
+            .and_then(|string_id| string_id.parse::<BaseProductId>().ok())
+            .map(Route::BaseProductReactivate)
+    });
+
     router.add_route_with_params(r"^/base_products/(\d+)/update$", |params| {
         params
             .get(0)
@@ -93,6 +164,13 @@ pub fn create_route_parser() -> RouteParser<Route> {
             .map(Route::BaseProductUpsertShipping)
     });
 
+    router.add_route_with_params(r"^/base_products/(\d+)/pending_changes$", |params| {
+        params
+            .get(0)
+            .and_then(|string_id| string_id.parse::<BaseProductId>().ok())
+            .map(Route::BaseProductPendingChanges)
+    });
+
     router.add_route_with_params(r"^/products/(\d+)/deactivate$", |params| {
         params
             .get(0)
@@ -102,6 +180,8 @@ pub fn create_route_parser() -> RouteParser<Route> {
 
     router.add_route(r"^/orders/update_state$", || Route::OrdersUpdateStateByBilling);
 
+    router.add_route(r"^/orders/bulk_set_state$", || Route::OrdersBulkSetState);
+
     router.add_route_with_params(r"^/orders/(\d+)/set_state$", |params| {
         params
             .get(0)
@@ -116,5 +196,49 @@ pub fn create_route_parser() -> RouteParser<Route> {
             .map(|order_id| Route::OrdersSetPaymentState { order_id })
     });
 
+    router.add_route_with_params(r"^/orders/(\d+)/dispute/refund$", |params| {
+        params
+            .get(0)
+            .and_then(|string_id| string_id.parse().ok())
+            .map(|order_slug| Route::OrdersResolveDispute(order_slug, DisputeResolution::Buyer))
+    });
+
+    router.add_route_with_params(r"^/orders/(\d+)/dispute/complete$", |params| {
+        params
+            .get(0)
+            .and_then(|string_id| string_id.parse().ok())
+            .map(|order_slug| Route::OrdersResolveDispute(order_slug, DisputeResolution::Seller))
+    });
+
+    router.add_route_with_params(r"^/orders/(\d+)/history$", |params| {
+        params
+            .get(0)
+            .and_then(|string_id| string_id.parse().ok())
+            .map(|order_slug| Route::OrderHistory { order_slug })
+    });
+
+    router.add_route_with_params(r"^/users/(\d+)/notification_opt_outs$", |params| {
+        params
+            .get(0)
+            .and_then(|string_id| string_id.parse::<UserId>().ok())
+            .map(Route::UserNotificationOptOuts)
+    });
+
+    router.add_route_with_params(r"^/stores/(\d+)/notification_opt_outs$", |params| {
+        params
+            .get(0)
+            .and_then(|string_id| string_id.parse::<StoreId>().ok())
+            .map(Route::StoreNotificationOptOuts)
+    });
+
+    router.add_route_with_params(r"^/moderators/(\d+)/assignments$", |params| {
+        params
+            .get(0)
+            .and_then(|string_id| string_id.parse::<UserId>().ok())
+            .map(Route::ModeratorAssignments)
+    });
+
+    router.add_route(r"^/metrics$", || Route::Metrics);
+
     router
 }
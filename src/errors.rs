@@ -14,6 +14,8 @@ pub enum Error {
     Validate(ValidationErrors),
     #[fail(display = "Http client error")]
     HttpClient,
+    #[fail(display = "Request is not authorized")]
+    Unauthorized,
     #[fail(display = "Server is refusing to fullfil the reqeust")]
     Forbidden,
     #[fail(display = "Unknown server error")]
@@ -27,6 +29,7 @@ impl Codeable for Error {
             Error::Validate(_) => StatusCode::BadRequest,
             Error::Parse => StatusCode::UnprocessableEntity,
             Error::HttpClient | Error::Unknown => StatusCode::InternalServerError,
+            Error::Unauthorized => StatusCode::Unauthorized,
             Error::Forbidden => StatusCode::Forbidden,
         }
     }
@@ -5,13 +5,22 @@ extern crate env_logger;
 extern crate failure;
 extern crate futures;
 extern crate futures_cpupool;
+extern crate hex;
+extern crate hmac;
+#[macro_use]
 extern crate hyper;
 #[macro_use]
+extern crate lazy_static;
+#[macro_use]
 extern crate log;
+#[macro_use]
+extern crate prometheus;
+extern crate regex;
 extern crate serde;
 #[macro_use]
 extern crate serde_derive;
 extern crate serde_json;
+extern crate sha2;
 extern crate tokio_core;
 extern crate tokio_signal;
 extern crate tokio_timer;
@@ -34,13 +43,15 @@ mod macros;
 pub mod config;
 mod controller;
 mod errors;
+mod metrics;
 mod microservice;
 mod models;
+mod scheduler;
 pub mod sentry_integration;
 mod services;
 
 use std::process;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 
 use stq_http::controller::Application;
 
@@ -48,8 +59,11 @@ use futures::prelude::*;
 use hyper::server::Http;
 use tokio_core::reactor::Core;
 
+use controller::billing_callback::ReplayGuard;
 use controller::ControllerImpl;
 use errors::Error;
+use models::CarrierRegistry;
+use scheduler::Scheduler;
 
 /// Starts new web service from provided `Config`
 pub fn start_server(config: config::Config) {
@@ -68,6 +82,16 @@ pub fn start_server(config: config::Config) {
     let client_stream = client.stream();
     handle.spawn(client_stream.for_each(|_| Ok(())));
 
+    let billing_replay_guard = Arc::new(Mutex::new(ReplayGuard::default()));
+    let carriers = Arc::new(CarrierRegistry::new(&config.carriers).expect("Could not create carrier registry"));
+
+    Scheduler {
+        config: config.clone(),
+        http_client: client_handle.clone(),
+        carriers: carriers.clone(),
+    }
+    .start(&handle);
+
     let serve = Http::new()
         .serve_addr_handle(&address, &*handle, {
             move || {
@@ -76,6 +100,8 @@ pub fn start_server(config: config::Config) {
                     config: config.clone(),
                     http_client: client_handle.clone(),
                     route_parser: Arc::new(controller::routes::create_route_parser()),
+                    billing_replay_guard: billing_replay_guard.clone(),
+                    carriers: carriers.clone(),
                 });
 
                 Ok(app)
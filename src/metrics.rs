@@ -0,0 +1,48 @@
+//! Prometheus metrics exposed by saga coordinator on `GET /metrics`
+use failure::Error as FailureError;
+use prometheus::{self, CounterVec, Encoder, GaugeVec, HistogramVec, TextEncoder};
+
+lazy_static! {
+    /// Billing state callbacks that were not applied to orders
+    pub static ref BILLING_EVENTS_SKIPPED: CounterVec = register_counter_vec!(
+        "saga_billing_events_skipped_total",
+        "Billing state callbacks skipped as duplicate or stale",
+        &["reason"]
+    )
+    .unwrap();
+    /// Billing state callbacks trying to move a paid order back to a pre-payment state
+    pub static ref BILLING_EVENTS_OUT_OF_ORDER: CounterVec = register_counter_vec!(
+        "saga_billing_events_out_of_order_total",
+        "Billing state callbacks received out of order",
+        &["current_state", "incoming_state"]
+    )
+    .unwrap();
+    /// Time stores and base products waited since they were sent to moderation until moderator handled them
+    pub static ref MODERATION_WAIT_SECONDS: HistogramVec = register_histogram_vec!(
+        "saga_moderation_wait_seconds",
+        "Time subjects waited for moderation",
+        &["subject"],
+        vec![3600.0, 4.0 * 3600.0, 12.0 * 3600.0, 24.0 * 3600.0, 48.0 * 3600.0, 72.0 * 3600.0, 168.0 * 3600.0]
+    )
+    .unwrap();
+    /// Stores and base products currently waiting for moderation
+    pub static ref MODERATION_WAITING: GaugeVec = register_gauge_vec!(
+        "saga_moderation_waiting",
+        "Subjects waiting for moderation",
+        &["subject"]
+    )
+    .unwrap();
+    /// Wait time of the subject waiting for moderation the longest
+    pub static ref MODERATION_OLDEST_WAIT_SECONDS: GaugeVec = register_gauge_vec!(
+        "saga_moderation_oldest_wait_seconds",
+        "Time the oldest subject waits for moderation",
+        &["subject"]
+    )
+    .unwrap();
+}
+
+pub fn render() -> Result<String, FailureError> {
+    let mut buffer = vec![];
+    TextEncoder::new().encode(&prometheus::gather(), &mut buffer)?;
+    Ok(String::from_utf8(buffer)?)
+}
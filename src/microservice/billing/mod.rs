@@ -18,13 +18,21 @@ pub trait BillingMicroservice {
     fn create_user_merchant(&self, initiator: Option<Initiator>, payload: CreateUserMerchantPayload) -> ApiFuture<Merchant>;
     fn delete_store_merchant(&self, initiator: Option<Initiator>, store_id: StoreId) -> ApiFuture<MerchantId>;
     fn delete_role(&self, initiator: Option<Initiator>, role_id: RoleId) -> ApiFuture<NewRole<BillingRole>>;
+    fn get_roles_for_user(&self, initiator: Option<Initiator>, user_id: UserId) -> ApiFuture<Vec<NewRole<BillingRole>>>;
     fn create_store_merchant(&self, initiator: Option<Initiator>, payload: CreateStoreMerchantPayload) -> ApiFuture<Merchant>;
     fn create_role(&self, initiator: Option<Initiator>, payload: NewRole<BillingRole>) -> ApiFuture<NewRole<BillingRole>>;
     fn create_invoice(&self, initiator: Initiator, payload: CreateInvoice) -> ApiFuture<Invoice>;
     fn revert_create_invoice(&self, initiator: Initiator, saga_id: SagaId) -> ApiFuture<SagaId>;
+    fn get_invoice_by_order_id(&self, initiator: Initiator, order_id: OrderId) -> ApiFuture<Option<Invoice>>;
     fn decline_order(&self, initiator: Initiator, order_id: OrderId) -> ApiFuture<()>;
     fn capture_order(&self, initiator: Initiator, order_id: OrderId) -> ApiFuture<()>;
     fn set_payment_state(&self, initiator: Option<Initiator>, order_id: OrderId, payload: OrderPaymentStateRequest) -> ApiFuture<()>;
+    fn get_order(&self, initiator: Option<Initiator>, order_id: OrderId) -> ApiFuture<Option<BillingOrder>>;
+    fn search_orders(&self, initiator: Option<Initiator>, terms: BillingOrderSearchTerms) -> ApiFuture<Vec<BillingOrder>>;
+    fn get_store_merchant(&self, initiator: Option<Initiator>, store_id: StoreId) -> ApiFuture<Merchant>;
+    fn create_payout(&self, initiator: Option<Initiator>, payload: CreatePayout) -> ApiFuture<Payout>;
+    /// Returns `false` if payout run of the period was already reserved
+    fn reserve_payout_run(&self, initiator: Option<Initiator>, payload: NewPayoutRun) -> ApiFuture<bool>;
 }
 
 pub struct BillingMicroserviceImpl<T: HttpClient + Clone> {
@@ -84,6 +92,17 @@ impl<T: 'static + HttpClient + Clone> BillingMicroservice for BillingMicroservic
         )
     }
 
+    fn get_roles_for_user(&self, initiator: Option<Initiator>, user_id: UserId) -> ApiFuture<Vec<NewRole<BillingRole>>> {
+        let url = format!("{}/roles/by-user-id/{}", self.billing_url(), user_id);
+        Box::new(
+            super::request::<_, (), _>(self.http_client.clone(), Method::Get, url, None, initiator.map(Into::into)).map_err(|e| {
+                e.context("Getting user roles in billing microservice failed.")
+                    .context(Error::HttpClient)
+                    .into()
+            }),
+        )
+    }
+
     fn create_store_merchant(&self, initiator: Option<Initiator>, payload: CreateStoreMerchantPayload) -> ApiFuture<Merchant> {
         let url = format!("{}/merchants/store", self.billing_url());
         Box::new(
@@ -131,6 +150,19 @@ impl<T: 'static + HttpClient + Clone> BillingMicroservice for BillingMicroservic
         )
     }
 
+    fn get_invoice_by_order_id(&self, initiator: Initiator, order_id: OrderId) -> ApiFuture<Option<Invoice>> {
+        let url = format!("{}/invoices/by-order-id/{}", self.billing_url(), order_id);
+        Box::new(
+            super::request::<_, (), Option<Invoice>>(self.http_client.clone(), Method::Get, url, None, Some(initiator.into())).map_err(
+                move |e| {
+                    e.context(format!("Getting invoice of order {} in billing microservice failed", order_id))
+                        .context(Error::HttpClient)
+                        .into()
+                },
+            ),
+        )
+    }
+
     fn create_invoice(&self, initiator: Initiator, payload: CreateInvoice) -> ApiFuture<Invoice> {
         let url = format!("{}/invoices", self.billing_url());
         Box::new(
@@ -180,6 +212,89 @@ impl<T: 'static + HttpClient + Clone> BillingMicroservice for BillingMicroservic
             }),
         )
     }
+
+    fn get_order(&self, initiator: Option<Initiator>, order_id: OrderId) -> ApiFuture<Option<BillingOrder>> {
+        let url = format!("{}/orders/{}", self.billing_url(), order_id);
+        Box::new(
+            super::request::<_, (), Option<BillingOrder>>(self.http_client.clone(), Method::Get, url, None, initiator.map(Into::into))
+                .map_err(move |e| {
+                    e.context(format!("Getting order {} in billing microservice failed", order_id))
+                        .context(Error::HttpClient)
+                        .into()
+                }),
+        )
+    }
+
+    fn search_orders(&self, initiator: Option<Initiator>, terms: BillingOrderSearchTerms) -> ApiFuture<Vec<BillingOrder>> {
+        let url = format!("{}/orders/search", self.billing_url());
+        Box::new(
+            super::request::<_, BillingOrderSearchTerms, Vec<BillingOrder>>(
+                self.http_client.clone(),
+                Method::Post,
+                url,
+                Some(terms),
+                initiator.map(Into::into),
+            )
+            .map_err(|e| {
+                e.context("Searching orders in billing microservice failed.")
+                    .context(Error::HttpClient)
+                    .into()
+            }),
+        )
+    }
+
+    fn get_store_merchant(&self, initiator: Option<Initiator>, store_id: StoreId) -> ApiFuture<Merchant> {
+        let url = format!("{}/merchants/store/{}", self.billing_url(), store_id);
+        Box::new(
+            super::request::<_, (), Merchant>(self.http_client.clone(), Method::Get, url, None, initiator.map(Into::into)).map_err(
+                move |e| {
+                    e.context(format!("Getting merchant of store {} in billing microservice failed", store_id))
+                        .context(Error::HttpClient)
+                        .into()
+                },
+            ),
+        )
+    }
+
+    fn create_payout(&self, initiator: Option<Initiator>, payload: CreatePayout) -> ApiFuture<Payout> {
+        let url = format!("{}/payouts", self.billing_url());
+        Box::new(
+            super::request::<_, CreatePayout, Payout>(
+                self.http_client.clone(),
+                Method::Post,
+                url,
+                Some(payload),
+                initiator.map(Into::into),
+            )
+            .map_err(|e| {
+                e.context("Creating payout in billing microservice failed.")
+                    .context(Error::HttpClient)
+                    .into()
+            }),
+        )
+    }
+
+    fn reserve_payout_run(&self, initiator: Option<Initiator>, payload: NewPayoutRun) -> ApiFuture<bool> {
+        let url = format!("{}/payouts/runs", self.billing_url());
+        let period_start = payload.period_start;
+        Box::new(
+            super::request::<_, NewPayoutRun, bool>(
+                self.http_client.clone(),
+                Method::Post,
+                url,
+                Some(payload),
+                initiator.map(Into::into),
+            )
+            .map_err(move |e| {
+                e.context(format!(
+                    "Reserving payout run of period starting {} in billing microservice failed.",
+                    period_start
+                ))
+                .context(Error::HttpClient)
+                .into()
+            }),
+        )
+    }
 }
 
 impl<T: HttpClient + Clone> BillingMicroserviceImpl<T> {
@@ -16,8 +16,10 @@ use models::*;
 pub trait DeliveryMicroservice {
     fn delete_shipping_by_base_product(&self, initiator: Option<Initiator>, base_product_id: BaseProductId) -> ApiFuture<()>;
     fn delete_delivery_role(&self, initiator: Option<Initiator>, role_id: RoleId) -> ApiFuture<NewRole<DeliveryRole>>;
+    fn get_delivery_roles_for_user(&self, initiator: Option<Initiator>, user_id: UserId) -> ApiFuture<Vec<NewRole<DeliveryRole>>>;
     fn create_delivery_role(&self, initiator: Option<Initiator>, payload: NewRole<DeliveryRole>) -> ApiFuture<NewRole<DeliveryRole>>;
     fn upsert_shipping(&self, initiator: Option<Initiator>, base_product_id: BaseProductId, payload: NewShipping) -> ApiFuture<Shipping>;
+    fn get_shipping(&self, initiator: Option<Initiator>, base_product_id: BaseProductId) -> ApiFuture<Shipping>;
 }
 
 pub struct DeliveryMicroserviceImpl<T: 'static + HttpClient + Clone> {
@@ -48,6 +50,17 @@ impl<T: 'static + HttpClient + Clone> DeliveryMicroservice for DeliveryMicroserv
         )
     }
 
+    fn get_delivery_roles_for_user(&self, initiator: Option<Initiator>, user_id: UserId) -> ApiFuture<Vec<NewRole<DeliveryRole>>> {
+        let url = format!("{}/roles/by-user-id/{}", self.delivery_url(), user_id);
+        Box::new(
+            super::request::<_, (), _>(self.http_client.clone(), Method::Get, url, None, initiator.map(Into::into)).map_err(|e| {
+                e.context("Getting user roles in delivery microservice failed.")
+                    .context(Error::HttpClient)
+                    .into()
+            }),
+        )
+    }
+
     fn create_delivery_role(&self, initiator: Option<Initiator>, payload: NewRole<DeliveryRole>) -> ApiFuture<NewRole<DeliveryRole>> {
         let url = format!("{}/{}", self.delivery_url(), StqModel::Role.to_url());
         Box::new(
@@ -83,6 +96,17 @@ impl<T: 'static + HttpClient + Clone> DeliveryMicroservice for DeliveryMicroserv
             }),
         )
     }
+
+    fn get_shipping(&self, initiator: Option<Initiator>, base_product_id: BaseProductId) -> ApiFuture<Shipping> {
+        let url = format!("{}/products/{}", self.delivery_url(), base_product_id);
+        Box::new(
+            super::request::<_, (), _>(self.http_client.clone(), Method::Get, url, None, initiator.map(Into::into)).map_err(|e| {
+                e.context("Getting shipping in delivery microservice failed.")
+                    .context(Error::HttpClient)
+                    .into()
+            }),
+        )
+    }
 }
 
 impl<T: 'static + HttpClient + Clone> DeliveryMicroserviceImpl<T> {
@@ -6,15 +6,20 @@ use stq_http::client::HttpClient;
 use stq_routes::model::Model as StqModel;
 use stq_routes::service::Service as StqService;
 use stq_static_resources::{
-    ApplyEmailVerificationForUser, ApplyPasswordResetForUser, BaseProductModerationStatusForModerator, BaseProductModerationStatusForUser,
-    EmailVerificationForUser, OrderCreateForStore, OrderCreateForUser, OrderUpdateStateForStore, OrderUpdateStateForUser,
-    PasswordResetForUser, Project, StoreModerationStatusForModerator, StoreModerationStatusForUser,
+    ApplyEmailVerificationForUser, ApplyPasswordResetForUser, BaseProductModerationStatusForModerator, EmailVerificationForUser,
+    OrderCreateForStore, OrderCreateForUser, OrderUpdateStateForStore, PasswordResetForUser, Project, StoreModerationStatusForModerator,
 };
 
 use super::{ApiFuture, Initiator};
 use config;
 use errors::Error;
-use models::{CreateEmarsysContactPayload, CreatedEmarsysContact};
+use models::{
+    BaseProductModerationStatusWithReasonForUser, CreateEmarsysContactPayload, CreatedEmarsysContact, InvoiceRequotedForUser,
+    ModerationOverdueForModerator, ModerationStatusDigestForUser, OrderConfirmationReminderForStore, OrderDisputeForModerator,
+    OrderPaymentStateForStore, OrderPaymentStateForUser, OrderUpdateStateForModerator, OrderUpdateStateWithTrackingForUser,
+    OrdersUpdateStateForUser, PayoutStatementForStore, PreOrderExpectedDateForUser, PreOrderShipReminderForStore, StoreDeactivatedForUser,
+    StoreModerationStatusWithReasonForUser, StoreStaffInvitationForUser,
+};
 
 pub trait NotificationsMicroservice {
     fn apply_email_verification(
@@ -28,11 +33,29 @@ pub trait NotificationsMicroservice {
     fn email_verification(&self, initiator: Option<Initiator>, payload: EmailVerificationForUser, project: Project) -> ApiFuture<()>;
     fn order_create_for_user(&self, initiator: Initiator, payload: OrderCreateForUser) -> ApiFuture<()>;
     fn order_create_for_store(&self, initiator: Initiator, payload: OrderCreateForStore) -> ApiFuture<()>;
-    fn order_update_state_for_user(&self, initiator: Initiator, payload: OrderUpdateStateForUser) -> ApiFuture<()>;
+    fn order_update_state_for_user(&self, initiator: Initiator, payload: OrderUpdateStateWithTrackingForUser) -> ApiFuture<()>;
+    fn orders_update_state_for_user(&self, initiator: Initiator, payload: OrdersUpdateStateForUser) -> ApiFuture<()>;
     fn order_update_state_for_store(&self, initiator: Initiator, payload: OrderUpdateStateForStore) -> ApiFuture<()>;
-    fn store_moderation_status_for_user(&self, initiator: Initiator, payload: StoreModerationStatusForUser) -> ApiFuture<()>;
-    fn base_product_moderation_status_for_user(&self, initiator: Initiator, payload: BaseProductModerationStatusForUser) -> ApiFuture<()>;
+    fn order_confirmation_reminder_for_store(&self, initiator: Initiator, payload: OrderConfirmationReminderForStore) -> ApiFuture<()>;
+    fn order_dispute_for_moderator(&self, initiator: Initiator, payload: OrderDisputeForModerator) -> ApiFuture<()>;
+    fn order_update_state_for_moderator(&self, initiator: Initiator, payload: OrderUpdateStateForModerator) -> ApiFuture<()>;
+    fn pre_order_ship_reminder_for_store(&self, initiator: Initiator, payload: PreOrderShipReminderForStore) -> ApiFuture<()>;
+    fn pre_order_expected_date_for_user(&self, initiator: Initiator, payload: PreOrderExpectedDateForUser) -> ApiFuture<()>;
+    fn order_payment_state_for_user(&self, initiator: Initiator, payload: OrderPaymentStateForUser) -> ApiFuture<()>;
+    fn order_payment_state_for_store(&self, initiator: Initiator, payload: OrderPaymentStateForStore) -> ApiFuture<()>;
+    fn payout_statement_for_store(&self, initiator: Initiator, payload: PayoutStatementForStore) -> ApiFuture<()>;
+    fn invoice_requoted_for_user(&self, initiator: Initiator, payload: InvoiceRequotedForUser) -> ApiFuture<()>;
+    fn store_staff_invitation_for_user(&self, initiator: Initiator, payload: StoreStaffInvitationForUser) -> ApiFuture<()>;
+    fn store_deactivated_for_user(&self, initiator: Initiator, payload: StoreDeactivatedForUser) -> ApiFuture<()>;
+    fn store_moderation_status_for_user(&self, initiator: Initiator, payload: StoreModerationStatusWithReasonForUser) -> ApiFuture<()>;
+    fn base_product_moderation_status_for_user(
+        &self,
+        initiator: Initiator,
+        payload: BaseProductModerationStatusWithReasonForUser,
+    ) -> ApiFuture<()>;
+    fn moderation_status_digest_for_user(&self, initiator: Initiator, payload: ModerationStatusDigestForUser) -> ApiFuture<()>;
     fn store_moderation_status_for_moderator(&self, initiator: Initiator, payload: StoreModerationStatusForModerator) -> ApiFuture<()>;
+    fn moderation_overdue_for_moderator(&self, initiator: Initiator, payload: ModerationOverdueForModerator) -> ApiFuture<()>;
     fn base_product_moderation_status_for_moderator(
         &self,
         initiator: Initiator,
@@ -150,10 +173,172 @@ impl<T: 'static + HttpClient + Clone> NotificationsMicroservice for Notification
         )
     }
 
-    fn order_update_state_for_user(&self, initiator: Initiator, payload: OrderUpdateStateForUser) -> ApiFuture<()> {
+    fn order_confirmation_reminder_for_store(&self, initiator: Initiator, payload: OrderConfirmationReminderForStore) -> ApiFuture<()> {
+        let url = format!("{}/stores/order-confirmation-reminder", self.notifications_url());
+        Box::new(
+            super::request::<_, OrderConfirmationReminderForStore, ()>(
+                self.http_client.clone(),
+                Method::Post,
+                url,
+                Some(payload),
+                Some(initiator.into()),
+            )
+            .map_err(|e| {
+                e.context("Sending order confirmation reminder for store in notifications microservice failed.")
+                    .context(Error::HttpClient)
+                    .into()
+            }),
+        )
+    }
+
+    fn pre_order_ship_reminder_for_store(&self, initiator: Initiator, payload: PreOrderShipReminderForStore) -> ApiFuture<()> {
+        let url = format!("{}/stores/pre-order-ship-reminder", self.notifications_url());
+        Box::new(
+            super::request::<_, PreOrderShipReminderForStore, ()>(
+                self.http_client.clone(),
+                Method::Post,
+                url,
+                Some(payload),
+                Some(initiator.into()),
+            )
+            .map_err(|e| {
+                e.context("Sending pre-order ship reminder for store in notifications microservice failed.")
+                    .context(Error::HttpClient)
+                    .into()
+            }),
+        )
+    }
+
+    fn pre_order_expected_date_for_user(&self, initiator: Initiator, payload: PreOrderExpectedDateForUser) -> ApiFuture<()> {
+        let url = format!("{}/users/pre-order-expected-date", self.notifications_url());
+        Box::new(
+            super::request::<_, PreOrderExpectedDateForUser, ()>(
+                self.http_client.clone(),
+                Method::Post,
+                url,
+                Some(payload),
+                Some(initiator.into()),
+            )
+            .map_err(|e| {
+                e.context("Sending pre-order expected date for user in notifications microservice failed.")
+                    .context(Error::HttpClient)
+                    .into()
+            }),
+        )
+    }
+
+    fn order_payment_state_for_user(&self, initiator: Initiator, payload: OrderPaymentStateForUser) -> ApiFuture<()> {
+        let url = format!("{}/users/order-payment-state", self.notifications_url());
+        Box::new(
+            super::request::<_, OrderPaymentStateForUser, ()>(
+                self.http_client.clone(),
+                Method::Post,
+                url,
+                Some(payload),
+                Some(initiator.into()),
+            )
+            .map_err(|e| {
+                e.context("Sending order payment state for user in notifications microservice failed.")
+                    .context(Error::HttpClient)
+                    .into()
+            }),
+        )
+    }
+
+    fn order_payment_state_for_store(&self, initiator: Initiator, payload: OrderPaymentStateForStore) -> ApiFuture<()> {
+        let url = format!("{}/stores/order-payment-state", self.notifications_url());
+        Box::new(
+            super::request::<_, OrderPaymentStateForStore, ()>(
+                self.http_client.clone(),
+                Method::Post,
+                url,
+                Some(payload),
+                Some(initiator.into()),
+            )
+            .map_err(|e| {
+                e.context("Sending order payment state for store in notifications microservice failed.")
+                    .context(Error::HttpClient)
+                    .into()
+            }),
+        )
+    }
+
+    fn payout_statement_for_store(&self, initiator: Initiator, payload: PayoutStatementForStore) -> ApiFuture<()> {
+        let url = format!("{}/stores/payout-statement", self.notifications_url());
+        Box::new(
+            super::request::<_, PayoutStatementForStore, ()>(
+                self.http_client.clone(),
+                Method::Post,
+                url,
+                Some(payload),
+                Some(initiator.into()),
+            )
+            .map_err(|e| {
+                e.context("Sending payout statement for store in notifications microservice failed.")
+                    .context(Error::HttpClient)
+                    .into()
+            }),
+        )
+    }
+
+    fn invoice_requoted_for_user(&self, initiator: Initiator, payload: InvoiceRequotedForUser) -> ApiFuture<()> {
+        let url = format!("{}/users/invoice-requoted", self.notifications_url());
+        Box::new(
+            super::request::<_, InvoiceRequotedForUser, ()>(
+                self.http_client.clone(),
+                Method::Post,
+                url,
+                Some(payload),
+                Some(initiator.into()),
+            )
+            .map_err(|e| {
+                e.context("Sending invoice requoted for user in notifications microservice failed.")
+                    .context(Error::HttpClient)
+                    .into()
+            }),
+        )
+    }
+
+    fn store_staff_invitation_for_user(&self, initiator: Initiator, payload: StoreStaffInvitationForUser) -> ApiFuture<()> {
+        let url = format!("{}/users/store-staff-invitation", self.notifications_url());
+        Box::new(
+            super::request::<_, StoreStaffInvitationForUser, ()>(
+                self.http_client.clone(),
+                Method::Post,
+                url,
+                Some(payload),
+                Some(initiator.into()),
+            )
+            .map_err(|e| {
+                e.context("Sending store staff invitation for user in notifications microservice failed.")
+                    .context(Error::HttpClient)
+                    .into()
+            }),
+        )
+    }
+
+    fn store_deactivated_for_user(&self, initiator: Initiator, payload: StoreDeactivatedForUser) -> ApiFuture<()> {
+        let url = format!("{}/users/store-deactivated", self.notifications_url());
+        Box::new(
+            super::request::<_, StoreDeactivatedForUser, ()>(
+                self.http_client.clone(),
+                Method::Post,
+                url,
+                Some(payload),
+                Some(initiator.into()),
+            )
+            .map_err(|e| {
+                e.context("Sending store deactivated for user in notifications microservice failed.")
+                    .context(Error::HttpClient)
+                    .into()
+            }),
+        )
+    }
+
+    fn order_update_state_for_user(&self, initiator: Initiator, payload: OrderUpdateStateWithTrackingForUser) -> ApiFuture<()> {
         let url = format!("{}/users/order-update-state", self.notifications_url());
         Box::new(
-            super::request::<_, OrderUpdateStateForUser, ()>(
+            super::request::<_, OrderUpdateStateWithTrackingForUser, ()>(
                 self.http_client.clone(),
                 Method::Post,
                 url,
@@ -168,6 +353,24 @@ impl<T: 'static + HttpClient + Clone> NotificationsMicroservice for Notification
         )
     }
 
+    fn orders_update_state_for_user(&self, initiator: Initiator, payload: OrdersUpdateStateForUser) -> ApiFuture<()> {
+        let url = format!("{}/users/orders-update-state", self.notifications_url());
+        Box::new(
+            super::request::<_, OrdersUpdateStateForUser, ()>(
+                self.http_client.clone(),
+                Method::Post,
+                url,
+                Some(payload),
+                Some(initiator.into()),
+            )
+            .map_err(|e| {
+                e.context("Sending orders update for user in notifications microservice failed.")
+                    .context(Error::HttpClient)
+                    .into()
+            }),
+        )
+    }
+
     fn order_create_for_store(&self, initiator: Initiator, payload: OrderCreateForStore) -> ApiFuture<()> {
         let url = format!("{}/stores/order-create", self.notifications_url());
         Box::new(
@@ -198,10 +401,10 @@ impl<T: 'static + HttpClient + Clone> NotificationsMicroservice for Notification
         )
     }
 
-    fn store_moderation_status_for_user(&self, initiator: Initiator, payload: StoreModerationStatusForUser) -> ApiFuture<()> {
+    fn store_moderation_status_for_user(&self, initiator: Initiator, payload: StoreModerationStatusWithReasonForUser) -> ApiFuture<()> {
         let url = format!("{}/users/stores/update-moderation-status", self.notifications_url());
         Box::new(
-            super::request::<_, StoreModerationStatusForUser, ()>(
+            super::request::<_, StoreModerationStatusWithReasonForUser, ()>(
                 self.http_client.clone(),
                 Method::Post,
                 url,
@@ -216,10 +419,14 @@ impl<T: 'static + HttpClient + Clone> NotificationsMicroservice for Notification
         )
     }
 
-    fn base_product_moderation_status_for_user(&self, initiator: Initiator, payload: BaseProductModerationStatusForUser) -> ApiFuture<()> {
+    fn base_product_moderation_status_for_user(
+        &self,
+        initiator: Initiator,
+        payload: BaseProductModerationStatusWithReasonForUser,
+    ) -> ApiFuture<()> {
         let url = format!("{}/users/base_products/update-moderation-status", self.notifications_url());
         Box::new(
-            super::request::<_, BaseProductModerationStatusForUser, ()>(
+            super::request::<_, BaseProductModerationStatusWithReasonForUser, ()>(
                 self.http_client.clone(),
                 Method::Post,
                 url,
@@ -234,6 +441,24 @@ impl<T: 'static + HttpClient + Clone> NotificationsMicroservice for Notification
         )
     }
 
+    fn moderation_status_digest_for_user(&self, initiator: Initiator, payload: ModerationStatusDigestForUser) -> ApiFuture<()> {
+        let url = format!("{}/users/moderation-status-digest", self.notifications_url());
+        Box::new(
+            super::request::<_, ModerationStatusDigestForUser, ()>(
+                self.http_client.clone(),
+                Method::Post,
+                url,
+                Some(payload),
+                Some(initiator.into()),
+            )
+            .map_err(|e| {
+                e.context("Sending moderation status digest for user in notifications microservice failed.")
+                    .context(Error::HttpClient)
+                    .into()
+            }),
+        )
+    }
+
     fn store_moderation_status_for_moderator(&self, initiator: Initiator, payload: StoreModerationStatusForModerator) -> ApiFuture<()> {
         let url = format!("{}/moderators/stores/update-moderation-status", self.notifications_url());
         Box::new(
@@ -251,6 +476,25 @@ impl<T: 'static + HttpClient + Clone> NotificationsMicroservice for Notification
             }),
         )
     }
+
+    fn moderation_overdue_for_moderator(&self, initiator: Initiator, payload: ModerationOverdueForModerator) -> ApiFuture<()> {
+        let url = format!("{}/moderators/moderation-overdue", self.notifications_url());
+        Box::new(
+            super::request::<_, ModerationOverdueForModerator, ()>(
+                self.http_client.clone(),
+                Method::Post,
+                url,
+                Some(payload),
+                Some(initiator.into()),
+            )
+            .map_err(|e| {
+                e.context("Sending moderation overdue for moderator in notifications microservice failed.")
+                    .context(Error::HttpClient)
+                    .into()
+            }),
+        )
+    }
+
     fn base_product_moderation_status_for_moderator(
         &self,
         initiator: Initiator,
@@ -273,6 +517,42 @@ impl<T: 'static + HttpClient + Clone> NotificationsMicroservice for Notification
         )
     }
 
+    fn order_dispute_for_moderator(&self, initiator: Initiator, payload: OrderDisputeForModerator) -> ApiFuture<()> {
+        let url = format!("{}/moderators/orders/dispute", self.notifications_url());
+        Box::new(
+            super::request::<_, OrderDisputeForModerator, ()>(
+                self.http_client.clone(),
+                Method::Post,
+                url,
+                Some(payload),
+                Some(initiator.into()),
+            )
+            .map_err(|e| {
+                e.context("Sending order dispute for moderator in notifications microservice failed.")
+                    .context(Error::HttpClient)
+                    .into()
+            }),
+        )
+    }
+
+    fn order_update_state_for_moderator(&self, initiator: Initiator, payload: OrderUpdateStateForModerator) -> ApiFuture<()> {
+        let url = format!("{}/moderators/orders/update-state", self.notifications_url());
+        Box::new(
+            super::request::<_, OrderUpdateStateForModerator, ()>(
+                self.http_client.clone(),
+                Method::Post,
+                url,
+                Some(payload),
+                Some(initiator.into()),
+            )
+            .map_err(|e| {
+                e.context("Sending order update state for moderator in notifications microservice failed.")
+                    .context(Error::HttpClient)
+                    .into()
+            }),
+        )
+    }
+
     fn emarsys_create_contact(&self, payload: CreateEmarsysContactPayload) -> ApiFuture<CreatedEmarsysContact> {
         let url = format!("{}/emarsys/contact", self.notifications_url());
         Box::new(
@@ -17,17 +17,40 @@ use services::parse_validation_errors;
 
 pub trait OrdersMicroservice {
     fn convert_cart(&self, payload: ConvertCartPayload) -> ApiFuture<Vec<Order>>;
+    /// Orders that converting the cart would create, nothing is stored and cart stays intact
+    fn preview_convert_cart(&self, payload: ConvertCartPayload) -> ApiFuture<Vec<Order>>;
     fn get_order(&self, initiator: Option<Initiator>, order_id: OrderIdentifier) -> ApiFuture<Option<Order>>;
+    fn search_orders(&self, initiator: Option<Initiator>, terms: OrderSearchTerms) -> ApiFuture<Vec<Order>>;
+    fn add_order_history_entry(&self, initiator: Option<Initiator>, payload: NewOrderHistoryEntry) -> ApiFuture<OrderHistoryEntry>;
+    fn get_order_history(&self, initiator: Option<Initiator>, order_id: OrderIdentifier) -> ApiFuture<Vec<OrderHistoryEntry>>;
+    /// Stores billing callback sequence number as the last one of the order unless it is a duplicate or stale
+    fn reserve_billing_event(&self, initiator: Option<Initiator>, order_id: OrderId, sequence_number: u64)
+        -> ApiFuture<BillingEventStatus>;
+    /// Drops reservation of billing callback which could not be applied, so its retry is not skipped
+    fn release_billing_event(&self, initiator: Option<Initiator>, order_id: OrderId, sequence_number: u64) -> ApiFuture<()>;
+    /// Records reminder unless one of the same kind was recorded after `since`, that earlier reminder is returned then
+    fn reserve_order_reminder(
+        &self,
+        initiator: Option<Initiator>,
+        order_id: OrderId,
+        payload: NewOrderReminder,
+    ) -> ApiFuture<Option<OrderReminder>>;
+    /// Drops reminder which could not be sent, so it is sent again
+    fn release_order_reminder(&self, initiator: Option<Initiator>, order_id: OrderId, kind: OrderReminderKind) -> ApiFuture<()>;
+    /// Returns `true` if the run was reserved by this request, `false` if some instance reserved it earlier
+    fn reserve_scheduler_run(&self, initiator: Option<Initiator>, payload: NewSchedulerRun) -> ApiFuture<bool>;
     fn set_order_state(
         &self,
         initiator: Option<Initiator>,
         order_id: OrderIdentifier,
-        payload: UpdateStatePayload,
+        payload: SetOrderStatePayload,
     ) -> ApiFuture<Option<Order>>;
+    fn update_order_price(&self, initiator: Option<Initiator>, order_id: OrderId, payload: UpdateOrderPrice) -> ApiFuture<Order>;
     fn create_buy_now(&self, buy_now: BuyNow, conversion_id: Option<ConversionId>) -> ApiFuture<Vec<Order>>;
     fn revert_convert_cart(&self, initiator: Initiator, payload: ConvertCartRevert) -> ApiFuture<CartHash>;
     fn create_role(&self, initiator: Option<Initiator>, role: RoleEntry<NewOrdersRole>) -> ApiFuture<RoleEntry<NewOrdersRole>>;
     fn delete_role(&self, initiator: Option<Initiator>, role_id: RoleEntryId) -> ApiFuture<RoleEntry<NewOrdersRole>>;
+    fn get_roles_for_user(&self, initiator: Option<Initiator>, user_id: UserId) -> ApiFuture<Vec<RoleEntry<NewOrdersRole>>>;
     fn delete_products_from_all_carts(&self, initiator: Option<Initiator>, payload: DeleteProductsFromCartsPayload) -> ApiFuture<()>;
     fn delete_delivery_method_from_all_carts(
         &self,
@@ -97,6 +120,17 @@ impl<T: 'static + HttpClient + Clone> OrdersMicroservice for OrdersMicroserviceI
         )
     }
 
+    fn get_roles_for_user(&self, initiator: Option<Initiator>, user_id: UserId) -> ApiFuture<Vec<RoleEntry<NewOrdersRole>>> {
+        let url = format!("{}/roles/by-user-id/{}", self.orders_url(), user_id);
+        Box::new(
+            super::request::<_, (), _>(self.http_client.clone(), Method::Get, url, None, initiator.map(Into::into)).map_err(|e| {
+                e.context("Getting user roles in orders microservice failed.")
+                    .context(Error::HttpClient)
+                    .into()
+            }),
+        )
+    }
+
     fn create_role(&self, initiator: Option<Initiator>, payload: RoleEntry<NewOrdersRole>) -> ApiFuture<RoleEntry<NewOrdersRole>> {
         let url = format!("{}/{}", self.orders_url(), StqModel::Role.to_url());
         Box::new(
@@ -129,6 +163,20 @@ impl<T: 'static + HttpClient + Clone> OrdersMicroservice for OrdersMicroserviceI
         )
     }
 
+    fn preview_convert_cart(&self, payload: ConvertCartPayload) -> ApiFuture<Vec<Order>> {
+        let url = format!("{}/{}/create_from_cart/preview", self.orders_url(), StqModel::Order.to_url());
+        Box::new(
+            super::request::<_, ConvertCartPayload, Vec<Order>>(self.http_client.clone(), Method::Post, url, Some(payload), None).map_err(
+                |e| {
+                    parse_validation_errors(e.into(), &["order"])
+                        .context("Previewing cart conversion in orders microservice failed.")
+                        .context(Error::HttpClient)
+                        .into()
+                },
+            ),
+        )
+    }
+
     fn get_order(&self, initiator: Option<Initiator>, order_id: OrderIdentifier) -> ApiFuture<Option<Order>> {
         let url = format!(
             "{}/{}/{}",
@@ -149,11 +197,237 @@ impl<T: 'static + HttpClient + Clone> OrdersMicroservice for OrdersMicroserviceI
         )
     }
 
+    fn search_orders(&self, initiator: Option<Initiator>, terms: OrderSearchTerms) -> ApiFuture<Vec<Order>> {
+        let url = format!("{}/{}/search", self.orders_url(), StqModel::Order.to_url());
+
+        Box::new(
+            super::request::<_, OrderSearchTerms, Vec<Order>>(
+                self.http_client.clone(),
+                Method::Post,
+                url,
+                Some(terms),
+                initiator.map(Into::into),
+            )
+            .map_err(|e| {
+                e.context("Searching orders in orders microservice failed.")
+                    .context(Error::HttpClient)
+                    .into()
+            }),
+        )
+    }
+
+    fn update_order_price(&self, initiator: Option<Initiator>, order_id: OrderId, payload: UpdateOrderPrice) -> ApiFuture<Order> {
+        let url = format!(
+            "{}/{}/{}/price",
+            self.orders_url(),
+            StqModel::Order.to_url(),
+            order_identifier_route(&OrderIdentifier::Id(order_id)),
+        );
+
+        Box::new(
+            super::request::<_, UpdateOrderPrice, Order>(
+                self.http_client.clone(),
+                Method::Put,
+                url,
+                Some(payload),
+                initiator.map(Into::into),
+            )
+            .map_err(move |e| {
+                parse_validation_errors(e.into(), &["order"])
+                    .context(format!(
+                        "Updating price of order with id {} in orders microservice failed.",
+                        order_id
+                    ))
+                    .context(Error::HttpClient)
+                    .into()
+            }),
+        )
+    }
+
+    fn add_order_history_entry(&self, initiator: Option<Initiator>, payload: NewOrderHistoryEntry) -> ApiFuture<OrderHistoryEntry> {
+        let url = format!(
+            "{}/{}/{}/history",
+            self.orders_url(),
+            StqModel::Order.to_url(),
+            order_identifier_route(&OrderIdentifier::Id(payload.order_id)),
+        );
+        let order_id = payload.order_id;
+
+        Box::new(
+            super::request::<_, NewOrderHistoryEntry, OrderHistoryEntry>(
+                self.http_client.clone(),
+                Method::Post,
+                url,
+                Some(payload),
+                initiator.map(Into::into),
+            )
+            .map_err(move |e| {
+                e.context(format!(
+                    "Adding history entry for order with id {} in orders microservice failed.",
+                    order_id
+                ))
+                .context(Error::HttpClient)
+                .into()
+            }),
+        )
+    }
+
+    fn get_order_history(&self, initiator: Option<Initiator>, order_id: OrderIdentifier) -> ApiFuture<Vec<OrderHistoryEntry>> {
+        let url = format!(
+            "{}/{}/{}/history",
+            self.orders_url(),
+            StqModel::Order.to_url(),
+            order_identifier_route(&order_id),
+        );
+
+        Box::new(
+            super::request::<_, (), Vec<OrderHistoryEntry>>(self.http_client.clone(), Method::Get, url, None, initiator.map(Into::into))
+                .map_err(move |e| {
+                    parse_validation_errors(e.into(), &["order"])
+                        .context(format!(
+                            "Getting history of order with id {:?} in orders microservice failed.",
+                            order_id
+                        ))
+                        .context(Error::HttpClient)
+                        .into()
+                }),
+        )
+    }
+
+    fn reserve_billing_event(
+        &self,
+        initiator: Option<Initiator>,
+        order_id: OrderId,
+        sequence_number: u64,
+    ) -> ApiFuture<BillingEventStatus> {
+        let url = format!(
+            "{}/{}/{}/billing_events",
+            self.orders_url(),
+            StqModel::Order.to_url(),
+            order_identifier_route(&OrderIdentifier::Id(order_id)),
+        );
+
+        Box::new(
+            super::request::<_, BillingEventReservation, BillingEventStatus>(
+                self.http_client.clone(),
+                Method::Post,
+                url,
+                Some(BillingEventReservation { sequence_number }),
+                initiator.map(Into::into),
+            )
+            .map_err(move |e| {
+                e.context(format!(
+                    "Reserving billing event {} for order with id {} in orders microservice failed.",
+                    sequence_number, order_id
+                ))
+                .context(Error::HttpClient)
+                .into()
+            }),
+        )
+    }
+
+    fn release_billing_event(&self, initiator: Option<Initiator>, order_id: OrderId, sequence_number: u64) -> ApiFuture<()> {
+        let url = format!(
+            "{}/{}/{}/billing_events/{}",
+            self.orders_url(),
+            StqModel::Order.to_url(),
+            order_identifier_route(&OrderIdentifier::Id(order_id)),
+            sequence_number,
+        );
+
+        Box::new(
+            super::request::<_, (), ()>(self.http_client.clone(), Method::Delete, url, None, initiator.map(Into::into)).map_err(move |e| {
+                e.context(format!(
+                    "Releasing billing event {} for order with id {} in orders microservice failed.",
+                    sequence_number, order_id
+                ))
+                .context(Error::HttpClient)
+                .into()
+            }),
+        )
+    }
+
+    fn reserve_order_reminder(
+        &self,
+        initiator: Option<Initiator>,
+        order_id: OrderId,
+        payload: NewOrderReminder,
+    ) -> ApiFuture<Option<OrderReminder>> {
+        let url = format!(
+            "{}/{}/{}/reminders",
+            self.orders_url(),
+            StqModel::Order.to_url(),
+            order_identifier_route(&OrderIdentifier::Id(order_id)),
+        );
+
+        Box::new(
+            super::request::<_, NewOrderReminder, Option<OrderReminder>>(
+                self.http_client.clone(),
+                Method::Post,
+                url,
+                Some(payload),
+                initiator.map(Into::into),
+            )
+            .map_err(move |e| {
+                e.context(format!(
+                    "Reserving reminder for order with id {} in orders microservice failed.",
+                    order_id
+                ))
+                .context(Error::HttpClient)
+                .into()
+            }),
+        )
+    }
+
+    fn release_order_reminder(&self, initiator: Option<Initiator>, order_id: OrderId, kind: OrderReminderKind) -> ApiFuture<()> {
+        let url = format!(
+            "{}/{}/{}/reminders/{}",
+            self.orders_url(),
+            StqModel::Order.to_url(),
+            order_identifier_route(&OrderIdentifier::Id(order_id)),
+            kind,
+        );
+
+        Box::new(
+            super::request::<_, (), ()>(self.http_client.clone(), Method::Delete, url, None, initiator.map(Into::into)).map_err(move |e| {
+                e.context(format!(
+                    "Releasing {} reminder for order with id {} in orders microservice failed.",
+                    kind, order_id
+                ))
+                .context(Error::HttpClient)
+                .into()
+            }),
+        )
+    }
+
+    fn reserve_scheduler_run(&self, initiator: Option<Initiator>, payload: NewSchedulerRun) -> ApiFuture<bool> {
+        let url = format!("{}/scheduler_runs", self.orders_url());
+        let job = payload.job;
+        let period_start = payload.period_start;
+        Box::new(
+            super::request::<_, NewSchedulerRun, bool>(
+                self.http_client.clone(),
+                Method::Post,
+                url,
+                Some(payload),
+                initiator.map(Into::into),
+            )
+            .map_err(move |e| {
+                e.context(format!(
+                    "Reserving {:?} scheduler run of period starting {} in orders microservice failed.",
+                    job, period_start
+                ))
+                .context(Error::HttpClient)
+                .into()
+            }),
+        )
+    }
+
     fn set_order_state(
         &self,
         initiator: Option<Initiator>,
         order_id: OrderIdentifier,
-        payload: UpdateStatePayload,
+        payload: SetOrderStatePayload,
     ) -> ApiFuture<Option<Order>> {
         let url = format!(
             "{}/{}/{}/status",
@@ -163,7 +437,7 @@ impl<T: 'static + HttpClient + Clone> OrdersMicroservice for OrdersMicroserviceI
         );
         let order_state = payload.state;
         Box::new(
-            super::request::<_, UpdateStatePayload, Option<Order>>(
+            super::request::<_, SetOrderStatePayload, Option<Order>>(
                 self.http_client.clone(),
                 Method::Put,
                 url,
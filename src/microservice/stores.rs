@@ -6,6 +6,7 @@ use stq_http::client::HttpClient;
 use stq_routes::model::Model as StqModel;
 use stq_routes::service::Service as StqService;
 use stq_types::*;
+use uuid::Uuid;
 
 use super::{ApiFuture, Initiator};
 
@@ -19,9 +20,11 @@ pub trait StoresMicroservice {
     fn create_stores_role(&self, initiator: Option<Initiator>, payload: NewRole<StoresRole>) -> ApiFuture<NewRole<StoresRole>>;
     fn delete_store(&self, initiator: Option<Initiator>, store_id: StoreId) -> ApiFuture<Store>;
     fn create_store(&self, initiator: Option<Initiator>, payload: NewStore) -> ApiFuture<Store>;
+    fn update_store(&self, initiator: Option<Initiator>, store_id: StoreId, payload: UpdateStore) -> ApiFuture<Store>;
     fn use_coupon(&self, initiator: Initiator, coupon: CouponId, user: UserId) -> ApiFuture<UsedCoupon>;
     fn get(&self, store: StoreId, visibility: Visibility) -> ApiFuture<Option<Store>>;
     fn get_base_product(&self, base_product_id: BaseProductId, visibility: Visibility) -> ApiFuture<Option<BaseProduct>>;
+    fn get_product(&self, product_id: ProductId, visibility: Visibility) -> ApiFuture<Option<Product>>;
     fn get_products_by_base_product(&self, base_product_id: BaseProductId) -> ApiFuture<Vec<Product>>;
     fn get_products_by_store(&self, store_id: StoreId) -> ApiFuture<Vec<Product>>;
     fn set_store_moderation_status(&self, payload: StoreModerate) -> ApiFuture<Store>;
@@ -31,6 +34,8 @@ pub trait StoresMicroservice {
     fn get_moderators(&self, initiator: Initiator) -> ApiFuture<Vec<UserId>>;
     fn deactivate_base_product(&self, initiator: Option<Initiator>, base_product_id: BaseProductId) -> ApiFuture<BaseProduct>;
     fn deactivate_store(&self, initiator: Option<Initiator>, store_id: StoreId) -> ApiFuture<Store>;
+    fn activate_store(&self, initiator: Option<Initiator>, store_id: StoreId) -> ApiFuture<Store>;
+    fn activate_base_product(&self, initiator: Option<Initiator>, base_product_id: BaseProductId) -> ApiFuture<BaseProduct>;
     fn deactivate_store_by_saga_id(&self, initiator: Option<Initiator>, saga_id: SagaId) -> ApiFuture<Store>;
     fn deactivate_product(&self, initiator: Option<Initiator>, product_id: ProductId) -> ApiFuture<Product>;
     fn update_base_product(
@@ -44,6 +49,60 @@ pub trait StoresMicroservice {
         initiator: Option<Initiator>,
         payload: NewBaseProductWithVariants,
     ) -> ApiFuture<BaseProduct>;
+    fn get_notification_opt_outs(&self, initiator: Option<Initiator>, store_id: StoreId) -> ApiFuture<NotificationOptOuts>;
+    fn set_notification_opt_outs(
+        &self,
+        initiator: Option<Initiator>,
+        store_id: StoreId,
+        payload: NotificationOptOuts,
+    ) -> ApiFuture<NotificationOptOuts>;
+    fn get_store_staff(&self, initiator: Option<Initiator>, store_id: StoreId) -> ApiFuture<Vec<StoreStaffMember>>;
+    fn add_store_staff_member(&self, initiator: Option<Initiator>, payload: StoreStaffMember) -> ApiFuture<StoreStaffMember>;
+    fn delete_store_staff_member(&self, initiator: Option<Initiator>, store_id: StoreId, user_id: UserId) -> ApiFuture<()>;
+    fn create_store_staff_invitation(&self, initiator: Option<Initiator>, payload: StoreStaffInvitation)
+        -> ApiFuture<StoreStaffInvitation>;
+    fn search_store_staff_invitations(
+        &self,
+        initiator: Option<Initiator>,
+        payload: StoreStaffInvitationSearch,
+    ) -> ApiFuture<Vec<StoreStaffInvitation>>;
+    fn delete_store_staff_invitation(&self, initiator: Option<Initiator>, store_id: StoreId, invitation_id: Uuid) -> ApiFuture<()>;
+    fn save_base_product_deactivation(
+        &self,
+        initiator: Option<Initiator>,
+        payload: BaseProductDeactivation,
+    ) -> ApiFuture<BaseProductDeactivation>;
+    fn get_base_product_deactivation(
+        &self,
+        initiator: Option<Initiator>,
+        base_product_id: BaseProductId,
+    ) -> ApiFuture<Option<BaseProductDeactivation>>;
+    fn get_base_product_deactivations_by_store(
+        &self,
+        initiator: Option<Initiator>,
+        store_id: StoreId,
+    ) -> ApiFuture<Vec<BaseProductDeactivation>>;
+    fn delete_base_product_deactivation(&self, initiator: Option<Initiator>, base_product_id: BaseProductId) -> ApiFuture<()>;
+    fn get_base_product_pending_changes(
+        &self,
+        initiator: Option<Initiator>,
+        base_product_id: BaseProductId,
+    ) -> ApiFuture<Option<UpdateBaseProduct>>;
+    fn set_base_product_pending_changes(
+        &self,
+        initiator: Option<Initiator>,
+        base_product_id: BaseProductId,
+        payload: UpdateBaseProduct,
+    ) -> ApiFuture<UpdateBaseProduct>;
+    fn delete_base_product_pending_changes(&self, initiator: Option<Initiator>, base_product_id: BaseProductId) -> ApiFuture<()>;
+    fn get_moderation_assignments(&self, initiator: Option<Initiator>) -> ApiFuture<Vec<ModerationAssignment>>;
+    fn set_moderation_assignment(&self, initiator: Option<Initiator>, payload: ModerationAssignment) -> ApiFuture<ModerationAssignment>;
+    fn delete_moderation_assignment(
+        &self,
+        initiator: Option<Initiator>,
+        subject: ModerationSubject,
+    ) -> ApiFuture<Option<ModerationAssignment>>;
+    fn add_moderation_history_entry(&self, initiator: Option<Initiator>, payload: NewModerationHistoryEntry) -> ApiFuture<()>;
 }
 
 pub struct StoresMicroserviceImpl<T: 'static + HttpClient + Clone> {
@@ -96,6 +155,33 @@ impl<T: 'static + HttpClient + Clone> StoresMicroservice for StoresMicroserviceI
         )
     }
 
+    fn activate_store(&self, initiator: Option<Initiator>, store_id: StoreId) -> ApiFuture<Store> {
+        let url = format!("{}/{}/{}/activate", self.stores_url(), StqModel::Store.to_url(), store_id);
+        Box::new(
+            super::request::<_, (), _>(self.http_client.clone(), Method::Post, url, None, initiator.map(Into::into)).map_err(|e| {
+                e.context("Activate store in stores microservice failed.")
+                    .context(Error::HttpClient)
+                    .into()
+            }),
+        )
+    }
+
+    fn activate_base_product(&self, initiator: Option<Initiator>, base_product_id: BaseProductId) -> ApiFuture<BaseProduct> {
+        let url = format!(
+            "{}/{}/{}/activate",
+            self.stores_url(),
+            StqModel::BaseProduct.to_url(),
+            base_product_id
+        );
+        Box::new(
+            super::request::<_, (), _>(self.http_client.clone(), Method::Post, url, None, initiator.map(Into::into)).map_err(|e| {
+                e.context("Activate base product in stores microservice failed.")
+                    .context(Error::HttpClient)
+                    .into()
+            }),
+        )
+    }
+
     fn deactivate_store_by_saga_id(&self, initiator: Option<Initiator>, saga_id: SagaId) -> ApiFuture<Store> {
         let url = format!("{}/{}/by_saga_id/{}", self.stores_url(), StqModel::Store.to_url(), saga_id);
         Box::new(
@@ -178,6 +264,18 @@ impl<T: 'static + HttpClient + Clone> StoresMicroservice for StoresMicroserviceI
         )
     }
 
+    fn update_store(&self, initiator: Option<Initiator>, store_id: StoreId, payload: UpdateStore) -> ApiFuture<Store> {
+        let url = format!("{}/{}/{}", self.stores_url(), StqModel::Store.to_url(), store_id);
+        Box::new(
+            super::request::<_, UpdateStore, Store>(self.http_client.clone(), Method::Put, url, Some(payload), initiator.map(Into::into))
+                .map_err(|e| {
+                    e.context("Updating store in stores microservice failed.")
+                        .context(Error::HttpClient)
+                        .into()
+                }),
+        )
+    }
+
     fn get(&self, store: StoreId, visibility: Visibility) -> ApiFuture<Option<Store>> {
         let url = format!(
             "{}/{}/{}?visibility={}",
@@ -212,6 +310,23 @@ impl<T: 'static + HttpClient + Clone> StoresMicroservice for StoresMicroserviceI
         )
     }
 
+    fn get_product(&self, product_id: ProductId, visibility: Visibility) -> ApiFuture<Option<Product>> {
+        let url = format!(
+            "{}/{}/{}?visibility={}",
+            self.stores_url(),
+            StqModel::Product.to_url(),
+            product_id,
+            visibility
+        );
+        Box::new(
+            super::request::<_, (), Option<Product>>(self.http_client.clone(), Method::Get, url, None, None).map_err(|e| {
+                e.context("Getting product in stores microservice failed.")
+                    .context(Error::HttpClient)
+                    .into()
+            }),
+        )
+    }
+
     fn get_products_by_base_product(&self, base_product_id: BaseProductId) -> ApiFuture<Vec<Product>> {
         let url = format!(
             "{}/{}/by_base_product/{}",
@@ -348,6 +463,453 @@ impl<T: 'static + HttpClient + Clone> StoresMicroservice for StoresMicroserviceI
             }),
         )
     }
+
+    fn get_notification_opt_outs(&self, initiator: Option<Initiator>, store_id: StoreId) -> ApiFuture<NotificationOptOuts> {
+        let url = format!(
+            "{}/{}/{}/notification_opt_outs",
+            self.stores_url(),
+            StqModel::Store.to_url(),
+            store_id
+        );
+
+        Box::new(
+            super::request::<_, (), NotificationOptOuts>(self.http_client.clone(), Method::Get, url, None, initiator.map(Into::into))
+                .map_err(|e| {
+                    e.context("Getting store notification opt-outs in stores microservice failed.")
+                        .context(Error::HttpClient)
+                        .into()
+                }),
+        )
+    }
+
+    fn set_notification_opt_outs(
+        &self,
+        initiator: Option<Initiator>,
+        store_id: StoreId,
+        payload: NotificationOptOuts,
+    ) -> ApiFuture<NotificationOptOuts> {
+        let url = format!(
+            "{}/{}/{}/notification_opt_outs",
+            self.stores_url(),
+            StqModel::Store.to_url(),
+            store_id
+        );
+
+        Box::new(
+            super::request::<_, NotificationOptOuts, NotificationOptOuts>(
+                self.http_client.clone(),
+                Method::Put,
+                url,
+                Some(payload),
+                initiator.map(Into::into),
+            )
+            .map_err(|e| {
+                e.context("Setting store notification opt-outs in stores microservice failed.")
+                    .context(Error::HttpClient)
+                    .into()
+            }),
+        )
+    }
+
+    fn get_store_staff(&self, initiator: Option<Initiator>, store_id: StoreId) -> ApiFuture<Vec<StoreStaffMember>> {
+        let url = format!("{}/{}/{}/staff", self.stores_url(), StqModel::Store.to_url(), store_id);
+
+        Box::new(
+            super::request::<_, (), Vec<StoreStaffMember>>(self.http_client.clone(), Method::Get, url, None, initiator.map(Into::into))
+                .map_err(move |e| {
+                    e.context(format!("Getting staff of store {} in stores microservice failed.", store_id))
+                        .context(Error::HttpClient)
+                        .into()
+                }),
+        )
+    }
+
+    fn add_store_staff_member(&self, initiator: Option<Initiator>, payload: StoreStaffMember) -> ApiFuture<StoreStaffMember> {
+        let store_id = payload.store_id;
+        let url = format!("{}/{}/{}/staff", self.stores_url(), StqModel::Store.to_url(), store_id);
+
+        Box::new(
+            super::request::<_, StoreStaffMember, StoreStaffMember>(
+                self.http_client.clone(),
+                Method::Post,
+                url,
+                Some(payload),
+                initiator.map(Into::into),
+            )
+            .map_err(move |e| {
+                e.context(format!("Adding staff member of store {} in stores microservice failed.", store_id))
+                    .context(Error::HttpClient)
+                    .into()
+            }),
+        )
+    }
+
+    fn delete_store_staff_member(&self, initiator: Option<Initiator>, store_id: StoreId, user_id: UserId) -> ApiFuture<()> {
+        let url = format!("{}/{}/{}/staff/{}", self.stores_url(), StqModel::Store.to_url(), store_id, user_id);
+
+        Box::new(
+            super::request::<_, (), ()>(self.http_client.clone(), Method::Delete, url, None, initiator.map(Into::into)).map_err(move |e| {
+                e.context(format!(
+                    "Deleting staff member {} of store {} in stores microservice failed.",
+                    user_id, store_id
+                ))
+                .context(Error::HttpClient)
+                .into()
+            }),
+        )
+    }
+
+    fn create_store_staff_invitation(
+        &self,
+        initiator: Option<Initiator>,
+        payload: StoreStaffInvitation,
+    ) -> ApiFuture<StoreStaffInvitation> {
+        let store_id = payload.store_id;
+        let url = format!("{}/{}/{}/staff_invitations", self.stores_url(), StqModel::Store.to_url(), store_id);
+
+        Box::new(
+            super::request::<_, StoreStaffInvitation, StoreStaffInvitation>(
+                self.http_client.clone(),
+                Method::Post,
+                url,
+                Some(payload),
+                initiator.map(Into::into),
+            )
+            .map_err(move |e| {
+                e.context(format!(
+                    "Creating staff invitation of store {} in stores microservice failed.",
+                    store_id
+                ))
+                .context(Error::HttpClient)
+                .into()
+            }),
+        )
+    }
+
+    fn search_store_staff_invitations(
+        &self,
+        initiator: Option<Initiator>,
+        payload: StoreStaffInvitationSearch,
+    ) -> ApiFuture<Vec<StoreStaffInvitation>> {
+        let url = format!("{}/{}/staff_invitations/search", self.stores_url(), StqModel::Store.to_url());
+
+        Box::new(
+            super::request::<_, StoreStaffInvitationSearch, Vec<StoreStaffInvitation>>(
+                self.http_client.clone(),
+                Method::Post,
+                url,
+                Some(payload),
+                initiator.map(Into::into),
+            )
+            .map_err(|e| {
+                e.context("Searching store staff invitations in stores microservice failed.")
+                    .context(Error::HttpClient)
+                    .into()
+            }),
+        )
+    }
+
+    fn delete_store_staff_invitation(&self, initiator: Option<Initiator>, store_id: StoreId, invitation_id: Uuid) -> ApiFuture<()> {
+        let url = format!(
+            "{}/{}/{}/staff_invitations/{}",
+            self.stores_url(),
+            StqModel::Store.to_url(),
+            store_id,
+            invitation_id
+        );
+
+        Box::new(
+            super::request::<_, (), ()>(self.http_client.clone(), Method::Delete, url, None, initiator.map(Into::into)).map_err(move |e| {
+                e.context(format!(
+                    "Deleting staff invitation {} of store {} in stores microservice failed.",
+                    invitation_id, store_id
+                ))
+                .context(Error::HttpClient)
+                .into()
+            }),
+        )
+    }
+
+    fn save_base_product_deactivation(
+        &self,
+        initiator: Option<Initiator>,
+        payload: BaseProductDeactivation,
+    ) -> ApiFuture<BaseProductDeactivation> {
+        let base_product_id = payload.base_product_id;
+        let url = format!(
+            "{}/{}/{}/deactivation",
+            self.stores_url(),
+            StqModel::BaseProduct.to_url(),
+            base_product_id
+        );
+
+        Box::new(
+            super::request::<_, BaseProductDeactivation, BaseProductDeactivation>(
+                self.http_client.clone(),
+                Method::Post,
+                url,
+                Some(payload),
+                initiator.map(Into::into),
+            )
+            .map_err(move |e| {
+                e.context(format!(
+                    "Saving deactivation of base product {} in stores microservice failed.",
+                    base_product_id
+                ))
+                .context(Error::HttpClient)
+                .into()
+            }),
+        )
+    }
+
+    fn get_base_product_deactivation(
+        &self,
+        initiator: Option<Initiator>,
+        base_product_id: BaseProductId,
+    ) -> ApiFuture<Option<BaseProductDeactivation>> {
+        let url = format!(
+            "{}/{}/{}/deactivation",
+            self.stores_url(),
+            StqModel::BaseProduct.to_url(),
+            base_product_id
+        );
+
+        Box::new(
+            super::request::<_, (), Option<BaseProductDeactivation>>(
+                self.http_client.clone(),
+                Method::Get,
+                url,
+                None,
+                initiator.map(Into::into),
+            )
+            .map_err(move |e| {
+                e.context(format!(
+                    "Getting deactivation of base product {} in stores microservice failed.",
+                    base_product_id
+                ))
+                .context(Error::HttpClient)
+                .into()
+            }),
+        )
+    }
+
+    fn get_base_product_deactivations_by_store(
+        &self,
+        initiator: Option<Initiator>,
+        store_id: StoreId,
+    ) -> ApiFuture<Vec<BaseProductDeactivation>> {
+        let url = format!("{}/{}/{}/deactivations", self.stores_url(), StqModel::Store.to_url(), store_id);
+
+        Box::new(
+            super::request::<_, (), Vec<BaseProductDeactivation>>(
+                self.http_client.clone(),
+                Method::Get,
+                url,
+                None,
+                initiator.map(Into::into),
+            )
+            .map_err(move |e| {
+                e.context(format!(
+                    "Getting deactivations of base products of store {} in stores microservice failed.",
+                    store_id
+                ))
+                .context(Error::HttpClient)
+                .into()
+            }),
+        )
+    }
+
+    fn delete_base_product_deactivation(&self, initiator: Option<Initiator>, base_product_id: BaseProductId) -> ApiFuture<()> {
+        let url = format!(
+            "{}/{}/{}/deactivation",
+            self.stores_url(),
+            StqModel::BaseProduct.to_url(),
+            base_product_id
+        );
+
+        Box::new(
+            super::request::<_, (), ()>(self.http_client.clone(), Method::Delete, url, None, initiator.map(Into::into)).map_err(move |e| {
+                e.context(format!(
+                    "Deleting deactivation of base product {} in stores microservice failed.",
+                    base_product_id
+                ))
+                .context(Error::HttpClient)
+                .into()
+            }),
+        )
+    }
+
+    fn get_base_product_pending_changes(
+        &self,
+        initiator: Option<Initiator>,
+        base_product_id: BaseProductId,
+    ) -> ApiFuture<Option<UpdateBaseProduct>> {
+        let url = format!(
+            "{}/{}/{}/pending_changes",
+            self.stores_url(),
+            StqModel::BaseProduct.to_url(),
+            base_product_id
+        );
+
+        Box::new(
+            super::request::<_, (), Option<UpdateBaseProduct>>(self.http_client.clone(), Method::Get, url, None, initiator.map(Into::into))
+                .map_err(move |e| {
+                    e.context(format!(
+                        "Getting pending changes of base product {} in stores microservice failed.",
+                        base_product_id
+                    ))
+                    .context(Error::HttpClient)
+                    .into()
+                }),
+        )
+    }
+
+    fn set_base_product_pending_changes(
+        &self,
+        initiator: Option<Initiator>,
+        base_product_id: BaseProductId,
+        payload: UpdateBaseProduct,
+    ) -> ApiFuture<UpdateBaseProduct> {
+        let url = format!(
+            "{}/{}/{}/pending_changes",
+            self.stores_url(),
+            StqModel::BaseProduct.to_url(),
+            base_product_id
+        );
+
+        Box::new(
+            super::request::<_, UpdateBaseProduct, UpdateBaseProduct>(
+                self.http_client.clone(),
+                Method::Put,
+                url,
+                Some(payload),
+                initiator.map(Into::into),
+            )
+            .map_err(move |e| {
+                e.context(format!(
+                    "Setting pending changes of base product {} in stores microservice failed.",
+                    base_product_id
+                ))
+                .context(Error::HttpClient)
+                .into()
+            }),
+        )
+    }
+
+    fn delete_base_product_pending_changes(&self, initiator: Option<Initiator>, base_product_id: BaseProductId) -> ApiFuture<()> {
+        let url = format!(
+            "{}/{}/{}/pending_changes",
+            self.stores_url(),
+            StqModel::BaseProduct.to_url(),
+            base_product_id
+        );
+
+        Box::new(
+            super::request::<_, (), ()>(self.http_client.clone(), Method::Delete, url, None, initiator.map(Into::into)).map_err(move |e| {
+                e.context(format!(
+                    "Deleting pending changes of base product {} in stores microservice failed.",
+                    base_product_id
+                ))
+                .context(Error::HttpClient)
+                .into()
+            }),
+        )
+    }
+
+    fn get_moderation_assignments(&self, initiator: Option<Initiator>) -> ApiFuture<Vec<ModerationAssignment>> {
+        let url = format!("{}/moderation_assignments", self.stores_url());
+
+        Box::new(
+            super::request::<_, (), Vec<ModerationAssignment>>(self.http_client.clone(), Method::Get, url, None, initiator.map(Into::into))
+                .map_err(|e| {
+                    e.context("Getting moderation assignments in stores microservice failed.")
+                        .context(Error::HttpClient)
+                        .into()
+                }),
+        )
+    }
+
+    fn set_moderation_assignment(&self, initiator: Option<Initiator>, payload: ModerationAssignment) -> ApiFuture<ModerationAssignment> {
+        let subject = payload.subject;
+        let url = format!("{}/moderation_assignments", self.stores_url());
+
+        Box::new(
+            super::request::<_, ModerationAssignment, ModerationAssignment>(
+                self.http_client.clone(),
+                Method::Put,
+                url,
+                Some(payload),
+                initiator.map(Into::into),
+            )
+            .map_err(move |e| {
+                e.context(format!(
+                    "Setting moderation assignment of {:?} in stores microservice failed.",
+                    subject
+                ))
+                .context(Error::HttpClient)
+                .into()
+            }),
+        )
+    }
+
+    fn delete_moderation_assignment(
+        &self,
+        initiator: Option<Initiator>,
+        subject: ModerationSubject,
+    ) -> ApiFuture<Option<ModerationAssignment>> {
+        let url = match subject {
+            ModerationSubject::Store(store_id) => {
+                format!("{}/moderation_assignments/{}/{}", self.stores_url(), subject.as_label(), store_id)
+            }
+            ModerationSubject::BaseProduct(base_product_id) => format!(
+                "{}/moderation_assignments/{}/{}",
+                self.stores_url(),
+                subject.as_label(),
+                base_product_id
+            ),
+        };
+
+        Box::new(
+            super::request::<_, (), Option<ModerationAssignment>>(
+                self.http_client.clone(),
+                Method::Delete,
+                url,
+                None,
+                initiator.map(Into::into),
+            )
+            .map_err(move |e| {
+                e.context(format!(
+                    "Deleting moderation assignment of {:?} in stores microservice failed.",
+                    subject
+                ))
+                .context(Error::HttpClient)
+                .into()
+            }),
+        )
+    }
+
+    fn add_moderation_history_entry(&self, initiator: Option<Initiator>, payload: NewModerationHistoryEntry) -> ApiFuture<()> {
+        let subject = payload.subject;
+        let url = format!("{}/moderation_history", self.stores_url());
+
+        Box::new(
+            super::request::<_, NewModerationHistoryEntry, ()>(
+                self.http_client.clone(),
+                Method::Post,
+                url,
+                Some(payload),
+                initiator.map(Into::into),
+            )
+            .map_err(move |e| {
+                e.context(format!(
+                    "Adding moderation history entry of {:?} in stores microservice failed.",
+                    subject
+                ))
+                .context(Error::HttpClient)
+                .into()
+            }),
+        )
+    }
 }
 
 impl<T: 'static + HttpClient + Clone> StoresMicroserviceImpl<T> {
@@ -26,6 +26,13 @@ pub trait UsersMicroservice {
     fn create_user(&self, initiator: Option<Initiator>, payload: SagaCreateProfile) -> ApiFuture<User>;
     fn get(&self, initiator: Option<Initiator>, user_id: UserId) -> ApiFuture<Option<User>>;
     fn update_user(&self, initiator: Option<Initiator>, user_id: UserId, payload: UpdateUser) -> ApiFuture<User>;
+    fn get_notification_opt_outs(&self, initiator: Option<Initiator>, user_id: UserId) -> ApiFuture<NotificationOptOuts>;
+    fn set_notification_opt_outs(
+        &self,
+        initiator: Option<Initiator>,
+        user_id: UserId,
+        payload: NotificationOptOuts,
+    ) -> ApiFuture<NotificationOptOuts>;
 }
 
 pub struct UsersMicroserviceImpl<T: 'static + HttpClient + Clone> {
@@ -189,6 +196,33 @@ impl<T: 'static + HttpClient + Clone> UsersMicroservice for UsersMicroserviceImp
             }),
         )
     }
+
+    fn get_notification_opt_outs(&self, initiator: Option<Initiator>, user_id: UserId) -> ApiFuture<NotificationOptOuts> {
+        let url = format!("{}/{}/{}/notification_opt_outs", self.users_url(), StqModel::User.to_url(), user_id);
+        Box::new(
+            super::request::<_, (), _>(self.http_client.clone(), Method::Get, url, None, initiator.map(Into::into)).map_err(|e| {
+                e.context("Getting user notification opt-outs in users microservice failed.")
+                    .context(Error::HttpClient)
+                    .into()
+            }),
+        )
+    }
+
+    fn set_notification_opt_outs(
+        &self,
+        initiator: Option<Initiator>,
+        user_id: UserId,
+        payload: NotificationOptOuts,
+    ) -> ApiFuture<NotificationOptOuts> {
+        let url = format!("{}/{}/{}/notification_opt_outs", self.users_url(), StqModel::User.to_url(), user_id);
+        Box::new(
+            super::request(self.http_client.clone(), Method::Put, url, Some(payload), initiator.map(Into::into)).map_err(|e| {
+                e.context("Setting user notification opt-outs in users microservice failed.")
+                    .context(Error::HttpClient)
+                    .into()
+            }),
+        )
+    }
 }
 
 impl<T: 'static + HttpClient + Clone> UsersMicroserviceImpl<T> {
@@ -30,6 +30,8 @@ pub trait WarehousesMicroservice {
         quantity: Quantity,
     ) -> ApiFuture<Stock>;
     fn find_by_store_id(&self, initiator: Option<Initiator>, store_id: StoreId) -> ApiFuture<Vec<Warehouse>>;
+    fn delete_warehouse(&self, initiator: Option<Initiator>, warehouse_id: WarehouseId) -> ApiFuture<Option<Warehouse>>;
+    fn get_warehouse_roles_for_user(&self, initiator: Option<Initiator>, user_id: UserId) -> ApiFuture<Vec<RoleEntry<NewWarehouseRole>>>;
 }
 
 pub struct WarehousesMicroserviceImpl<T: 'static + HttpClient + Clone> {
@@ -122,6 +124,33 @@ impl<T: 'static + HttpClient + Clone> WarehousesMicroservice for WarehousesMicro
                 }),
         )
     }
+
+    fn delete_warehouse(&self, initiator: Option<Initiator>, warehouse_id: WarehouseId) -> ApiFuture<Option<Warehouse>> {
+        let url = format!(
+            "{}/warehouses/{}",
+            self.warehouses_url(),
+            warehouse_identifier_route(&WarehouseIdentifier::Id(warehouse_id))
+        );
+        Box::new(
+            super::request::<_, (), Option<Warehouse>>(self.http_client.clone(), Method::Delete, url, None, initiator.map(Into::into))
+                .map_err(|e| {
+                    e.context("Deleting warehouse in warehouses microservice failed.")
+                        .context(Error::HttpClient)
+                        .into()
+                }),
+        )
+    }
+
+    fn get_warehouse_roles_for_user(&self, initiator: Option<Initiator>, user_id: UserId) -> ApiFuture<Vec<RoleEntry<NewWarehouseRole>>> {
+        let url = format!("{}/roles/by-user-id/{}", self.warehouses_url(), user_id);
+        Box::new(
+            super::request::<_, (), _>(self.http_client.clone(), Method::Get, url, None, initiator.map(Into::into)).map_err(|e| {
+                e.context("Getting user roles in warehouses microservice failed.")
+                    .context(Error::HttpClient)
+                    .into()
+            }),
+        )
+    }
 }
 
 impl<T: 'static + HttpClient + Clone> WarehousesMicroserviceImpl<T> {
@@ -1,5 +1,8 @@
 use stq_static_resources::Currency;
-use stq_types::{CategoryId, Quantity, StoreId};
+use stq_types::{BaseProductId, CategoryId, ProductId, Quantity, StoreId, WarehouseId};
+
+use config::BaseProductField;
+use models::Shipping;
 
 #[derive(Serialize, Deserialize, Clone, Debug, Default)]
 pub struct UpdateBaseProduct {
@@ -17,6 +20,63 @@ pub struct UpdateBaseProduct {
     pub weight_g: Option<i32>,
 }
 
+impl UpdateBaseProduct {
+    /// Moves changes of `fields` out of the update, returns the rest of the update and the moved changes
+    pub fn split_off(mut self, fields: &[BaseProductField]) -> (Self, Self) {
+        let mut moved = UpdateBaseProduct::default();
+        for field in fields {
+            match field {
+                BaseProductField::Name => moved.name = self.name.take(),
+                BaseProductField::ShortDescription => moved.short_description = self.short_description.take(),
+                BaseProductField::LongDescription => moved.long_description = self.long_description.take(),
+                BaseProductField::SeoTitle => moved.seo_title = self.seo_title.take(),
+                BaseProductField::SeoDescription => moved.seo_description = self.seo_description.take(),
+                BaseProductField::Currency => moved.currency = self.currency.take(),
+                BaseProductField::CategoryId => moved.category_id = self.category_id.take(),
+                BaseProductField::Slug => moved.slug = self.slug.take(),
+                BaseProductField::LengthCm => moved.length_cm = self.length_cm.take(),
+                BaseProductField::WidthCm => moved.width_cm = self.width_cm.take(),
+                BaseProductField::HeightCm => moved.height_cm = self.height_cm.take(),
+                BaseProductField::WeightG => moved.weight_g = self.weight_g.take(),
+            }
+        }
+        (self, moved)
+    }
+
+    /// Combines two updates, fields set in `newer` win
+    pub fn merge(self, newer: Self) -> Self {
+        Self {
+            name: newer.name.or(self.name),
+            short_description: newer.short_description.or(self.short_description),
+            long_description: newer.long_description.or(self.long_description),
+            seo_title: newer.seo_title.or(self.seo_title),
+            seo_description: newer.seo_description.or(self.seo_description),
+            currency: newer.currency.or(self.currency),
+            category_id: newer.category_id.or(self.category_id),
+            slug: newer.slug.or(self.slug),
+            length_cm: newer.length_cm.or(self.length_cm),
+            width_cm: newer.width_cm.or(self.width_cm),
+            height_cm: newer.height_cm.or(self.height_cm),
+            weight_g: newer.weight_g.or(self.weight_g),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.name.is_none()
+            && self.short_description.is_none()
+            && self.long_description.is_none()
+            && self.seo_title.is_none()
+            && self.seo_description.is_none()
+            && self.currency.is_none()
+            && self.category_id.is_none()
+            && self.slug.is_none()
+            && self.length_cm.is_none()
+            && self.width_cm.is_none()
+            && self.height_cm.is_none()
+            && self.weight_g.is_none()
+    }
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct NewBaseProductWithVariants {
     pub uuid: String,
@@ -64,3 +124,64 @@ pub struct ProdAttrValue {
     pub value: String,
     pub meta_field: Option<String>,
 }
+
+/// Stock of base product variant that was cleared on deactivation
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct DeactivatedStock {
+    pub warehouse_id: WarehouseId,
+    pub product_id: ProductId,
+    pub quantity: Quantity,
+}
+
+/// Warehouse and shipping state of base product taken away by store deactivation, kept in stores microservice until reactivation
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct BaseProductDeactivation {
+    pub base_product_id: BaseProductId,
+    pub store_id: StoreId,
+    pub stocks: Vec<DeactivatedStock>,
+    pub shipping: Option<Shipping>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sensitive_changes_are_split_off() {
+        let update = UpdateBaseProduct {
+            name: Some(serde_json::Value::String("New name".to_string())),
+            category_id: Some(CategoryId(12)),
+            weight_g: Some(500),
+            ..Default::default()
+        };
+
+        let (rest, moved) = update.split_off(&[BaseProductField::Name, BaseProductField::CategoryId]);
+        assert!(rest.name.is_none());
+        assert!(rest.category_id.is_none());
+        assert_eq!(rest.weight_g, Some(500));
+        assert!(moved.name.is_some());
+        assert_eq!(moved.category_id, Some(CategoryId(12)));
+        assert!(moved.weight_g.is_none());
+
+        let (rest, moved) = rest.split_off(&[BaseProductField::Name]);
+        assert!(moved.is_empty());
+        assert!(!rest.is_empty());
+    }
+
+    #[test]
+    fn newer_changes_win_on_merge() {
+        let older = UpdateBaseProduct {
+            slug: Some("old".to_string()),
+            weight_g: Some(500),
+            ..Default::default()
+        };
+        let newer = UpdateBaseProduct {
+            slug: Some("new".to_string()),
+            ..Default::default()
+        };
+
+        let merged = older.merge(newer);
+        assert_eq!(merged.slug, Some("new".to_string()));
+        assert_eq!(merged.weight_g, Some(500));
+    }
+}
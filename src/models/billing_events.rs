@@ -0,0 +1,119 @@
+use std::collections::HashMap;
+
+use stq_static_resources::OrderState;
+use stq_types::OrderId;
+
+use models::BillingOrderInfo;
+
+/// Result of reserving billing callback sequence number in orders microservice,
+/// only `New` events are applied to orders
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BillingEventStatus {
+    New,
+    Duplicate,
+    Stale,
+}
+
+impl BillingEventStatus {
+    pub fn as_label(&self) -> &'static str {
+        match self {
+            BillingEventStatus::New => "new",
+            BillingEventStatus::Duplicate => "duplicate",
+            BillingEventStatus::Stale => "stale",
+        }
+    }
+}
+
+/// Sequence number of billing callback reserved for the order, orders microservice keeps the last one
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct BillingEventReservation {
+    pub sequence_number: u64,
+}
+
+/// Leaves the latest event of every order in the batch, the rest are returned with their status
+pub fn latest_billing_events(events: Vec<BillingOrderInfo>) -> (Vec<BillingOrderInfo>, Vec<(BillingOrderInfo, BillingEventStatus)>) {
+    let mut latest: Vec<BillingOrderInfo> = vec![];
+    let mut skipped = vec![];
+    let mut positions = HashMap::<OrderId, usize>::new();
+
+    for event in events {
+        let sequence_number = match event.sequence_number {
+            Some(sequence_number) => sequence_number,
+            None => {
+                latest.push(event);
+                continue;
+            }
+        };
+
+        match positions.get(&event.order_id).cloned() {
+            None => {
+                positions.insert(event.order_id, latest.len());
+                latest.push(event);
+            }
+            Some(position) => {
+                let kept = latest[position].sequence_number.unwrap_or(0);
+                if sequence_number > kept {
+                    let older = ::std::mem::replace(&mut latest[position], event);
+                    skipped.push((older, BillingEventStatus::Stale));
+                } else if sequence_number == kept {
+                    skipped.push((event, BillingEventStatus::Duplicate));
+                } else {
+                    skipped.push((event, BillingEventStatus::Stale));
+                }
+            }
+        }
+    }
+
+    (latest, skipped)
+}
+
+/// Billing must not move an order that is already paid back to one of the pre-payment states
+pub fn is_billing_state_regression(current: OrderState, incoming: OrderState) -> bool {
+    let pre_payment_states = [
+        OrderState::New,
+        OrderState::PaymentAwaited,
+        OrderState::TransactionPending,
+        OrderState::AmountExpired,
+    ];
+
+    pre_payment_states.contains(&incoming) && !pre_payment_states.contains(&current)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use stq_types::{StoreId, UserId};
+
+    fn event(order_id: OrderId, sequence_number: Option<u64>) -> BillingOrderInfo {
+        BillingOrderInfo {
+            order_id,
+            customer_id: UserId(1),
+            store_id: StoreId(1),
+            status: OrderState::Paid,
+            sequence_number,
+        }
+    }
+
+    #[test]
+    fn only_latest_event_of_order_is_left_in_batch() {
+        let first = OrderId::new();
+        let second = OrderId::new();
+        let (latest, skipped) = latest_billing_events(vec![
+            event(first, Some(2)),
+            event(first, Some(2)),
+            event(second, Some(1)),
+            event(first, Some(3)),
+            event(second, None),
+        ]);
+
+        let latest = latest.iter().map(|e| (e.order_id, e.sequence_number)).collect::<Vec<_>>();
+        assert_eq!(latest, vec![(first, Some(3)), (second, Some(1)), (second, None)]);
+        let skipped = skipped.iter().map(|(e, status)| (e.sequence_number, *status)).collect::<Vec<_>>();
+        assert_eq!(
+            skipped,
+            vec![(Some(2), BillingEventStatus::Duplicate), (Some(2), BillingEventStatus::Stale)]
+        );
+    }
+}
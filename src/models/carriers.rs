@@ -0,0 +1,108 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use failure::Error as FailureError;
+use failure::Fail;
+use regex::Regex;
+
+use stq_api::orders::Order;
+use stq_types::CompanyPackageId;
+
+use config;
+
+/// Placeholder in carrier tracking url template replaced with tracking number
+const TRACK_ID_PLACEHOLDER: &str = "{track_id}";
+
+#[derive(Debug)]
+pub struct Carrier {
+    pub name: String,
+    tracking_number: Regex,
+    tracking_url: String,
+}
+
+impl Carrier {
+    pub fn is_valid_tracking_number(&self, track_id: &str) -> bool {
+        self.tracking_number.is_match(track_id.trim())
+    }
+
+    pub fn tracking_url(&self, track_id: &str) -> String {
+        self.tracking_url.replace(TRACK_ID_PLACEHOLDER, track_id.trim())
+    }
+}
+
+/// Carriers known to saga coordinator by company package used for delivery
+#[derive(Debug, Default)]
+pub struct CarrierRegistry {
+    carriers: HashMap<CompanyPackageId, Arc<Carrier>>,
+}
+
+impl CarrierRegistry {
+    pub fn new(settings: &[config::Carrier]) -> Result<Self, FailureError> {
+        let mut carriers = HashMap::new();
+        for setting in settings {
+            let tracking_number = Regex::new(&format!("^(?:{})$", setting.tracking_number_pattern))
+                .map_err(|e| e.context(format!("Invalid tracking number pattern of carrier {}", setting.name)))?;
+            let carrier = Arc::new(Carrier {
+                name: setting.name.clone(),
+                tracking_number,
+                tracking_url: setting.tracking_url.clone(),
+            });
+            for company_package_id in &setting.company_package_ids {
+                carriers.insert(*company_package_id, carrier.clone());
+            }
+        }
+        Ok(Self { carriers })
+    }
+
+    pub fn get(&self, company_package_id: CompanyPackageId) -> Option<&Carrier> {
+        self.carriers.get(&company_package_id).map(|carrier| carrier.as_ref())
+    }
+
+    pub fn for_order(&self, order: &Order) -> Option<&Carrier> {
+        order.company_package_id.and_then(|company_package_id| self.get(company_package_id))
+    }
+
+    /// Tracking url of the order if it has tracking number and its carrier is known
+    pub fn order_tracking_url(&self, order: &Order) -> Option<String> {
+        match (self.for_order(order), &order.track_id) {
+            (Some(carrier), Some(track_id)) => Some(carrier.tracking_url(track_id)),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn registry() -> CarrierRegistry {
+        CarrierRegistry::new(&[config::Carrier {
+            name: "UPS".to_string(),
+            company_package_ids: vec![CompanyPackageId(1), CompanyPackageId(2)],
+            tracking_number_pattern: "1Z[0-9A-Z]{16}".to_string(),
+            tracking_url: "https://www.ups.com/track?tracknum={track_id}".to_string(),
+        }])
+        .unwrap()
+    }
+
+    #[test]
+    fn tracking_number_validation() {
+        let registry = registry();
+        let carrier = registry.get(CompanyPackageId(2)).unwrap();
+        assert!(carrier.is_valid_tracking_number("1Z999AA10123456784"));
+        assert!(carrier.is_valid_tracking_number(" 1Z999AA10123456784 "));
+        assert!(!carrier.is_valid_tracking_number("1Z999AA10123456784X"));
+        assert!(!carrier.is_valid_tracking_number("RU123456789CN"));
+        assert!(registry.get(CompanyPackageId(3)).is_none());
+    }
+
+    #[test]
+    fn tracking_url() {
+        let registry = registry();
+        let carrier = registry.get(CompanyPackageId(1)).unwrap();
+        assert_eq!(
+            carrier.tracking_url("1Z999AA10123456784"),
+            "https://www.ups.com/track?tracknum=1Z999AA10123456784"
+        );
+    }
+}
@@ -118,14 +118,17 @@ pub struct BillingOrderInfo {
     pub customer_id: UserId,
     pub store_id: StoreId,
     pub status: OrderState,
+    /// Monotonically increasing number of billing event for this order
+    #[serde(default)]
+    pub sequence_number: Option<u64>,
 }
 
 impl fmt::Display for BillingOrderInfo {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(
             f,
-            "BillingOrderInfo - order_id: {}, customer_id: {}, store_id: {}, status: {})",
-            self.order_id, self.customer_id, self.store_id, self.status
+            "BillingOrderInfo - order_id: {}, customer_id: {}, store_id: {}, status: {}, sequence_number: {:?})",
+            self.order_id, self.customer_id, self.store_id, self.status, self.sequence_number
         )
     }
 }
@@ -147,12 +150,45 @@ pub struct UpdateStatePayload {
     pub committer_role: CommitterRole,
 }
 
-impl From<BillingOrderInfo> for UpdateStatePayload {
+/// State change sent to orders microservice, `committer_role` is `None` when
+/// saga coordinator changes the state on its own
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SetOrderStatePayload {
+    pub state: OrderState,
+    pub track_id: Option<String>,
+    pub comment: Option<String>,
+    pub committer_role: Option<CommitterRole>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct BulkUpdateStateItem {
+    pub order_slug: OrderSlug,
+    pub state: OrderState,
+    pub track_id: Option<String>,
+    pub comment: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct BulkUpdateStatePayload {
+    pub items: Vec<BulkUpdateStateItem>,
+    pub committer_role: CommitterRole,
+}
+
+/// Outcome of setting state of a single order in bulk update
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BulkUpdateStateResult {
+    pub order_slug: OrderSlug,
+    /// Updated order, `None` if state was already set or update failed
+    pub order: Option<Order>,
+    pub error: Option<String>,
+}
+
+impl From<BillingOrderInfo> for SetOrderStatePayload {
     fn from(order_info: BillingOrderInfo) -> Self {
         let comment = Some(match order_info.status {
             OrderState::TransactionPending => "Found new transaction in blockchain, waiting for it confirmation.".to_string(),
             OrderState::AmountExpired => {
-                "Invoice amount expiration timeout occurred, total amount will be recalculated by billing service.".to_string()
+                "Invoice amount expiration timeout occurred, invoice will be re-created with fresh prices.".to_string()
             }
             _ => format!("State changed to {} by billing service.", order_info.status).to_string(),
         });
@@ -160,7 +196,7 @@ impl From<BillingOrderInfo> for UpdateStatePayload {
             state: order_info.status,
             track_id: None,
             comment,
-            committer_role: CommitterRole::Customer,
+            committer_role: None,
         }
     }
 }
@@ -253,6 +289,32 @@ pub struct OrderPaymentStateRequest {
     pub state: PaymentState,
 }
 
+/// Fresh product price set to the order before its invoice is re-created. Orders microservice
+/// recalculates the total with the coupon and delivery of the order the same way as on cart conversion
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct UpdateOrderPrice {
+    pub price: ProductPrice,
+}
+
+/// Order as it is known to billing microservice
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct BillingOrder {
+    pub id: OrderId,
+    pub store_id: StoreId,
+    pub state: PaymentState,
+    /// Currency the seller is paid in
+    pub seller_currency: Currency,
+    pub total_amount: ProductPrice,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct BillingOrderSearchTerms {
+    pub payment_state: PaymentState,
+    /// Only orders that are or are not included in some payout
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub paid_out: Option<bool>,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone, Copy, Eq, PartialEq)]
 #[serde(rename_all = "lowercase")]
 pub enum PaymentState {
@@ -270,4 +332,96 @@ pub enum PaymentState {
     PaidToSeller,
     /// Need money payment to seller
     PaymentToSellerNeeded,
+    /// Order is claimed by payout run, money is being paid to seller
+    PaymentToSellerInProgress,
+}
+
+impl PaymentState {
+    /// Payment states order may move to from this one
+    pub fn next_states(self) -> &'static [PaymentState] {
+        match self {
+            PaymentState::Initial => &[PaymentState::Declined, PaymentState::Captured],
+            PaymentState::Captured => &[PaymentState::RefundNeeded, PaymentState::PaymentToSellerNeeded],
+            PaymentState::RefundNeeded => &[PaymentState::Refunded],
+            PaymentState::PaymentToSellerNeeded => &[PaymentState::PaymentToSellerInProgress, PaymentState::PaidToSeller],
+            // failed payout returns its orders to the next run
+            PaymentState::PaymentToSellerInProgress => &[PaymentState::PaidToSeller, PaymentState::PaymentToSellerNeeded],
+            PaymentState::Declined | PaymentState::Refunded | PaymentState::PaidToSeller => &[],
+        }
+    }
+
+    pub fn can_move_to(self, next: PaymentState) -> bool {
+        self.next_states().contains(&next)
+    }
+
+    /// Whether order in `order_state` may have this payment state
+    pub fn is_consistent_with(self, order_state: OrderState) -> bool {
+        match self {
+            PaymentState::Initial => match order_state {
+                OrderState::New
+                | OrderState::PaymentAwaited
+                | OrderState::TransactionPending
+                | OrderState::AmountExpired
+                | OrderState::Paid => true,
+                _ => false,
+            },
+            PaymentState::Declined | PaymentState::RefundNeeded | PaymentState::Refunded => order_state == OrderState::Cancelled,
+            // disputed order keeps captured money until the dispute is resolved
+            PaymentState::Captured => match order_state {
+                OrderState::InProcessing | OrderState::Sent | OrderState::Delivered | OrderState::Received | OrderState::Dispute => true,
+                _ => false,
+            },
+            PaymentState::PaymentToSellerNeeded | PaymentState::PaymentToSellerInProgress | PaymentState::PaidToSeller => {
+                order_state == OrderState::Complete
+            }
+        }
+    }
+}
+
+/// Side that wins the order dispute
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Eq, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum DisputeResolution {
+    /// Money is refunded to customer, order is cancelled
+    Buyer,
+    /// Order is completed, money is paid to seller
+    Seller,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ResolveDisputePayload {
+    pub comment: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn payment_state_transitions() {
+        assert!(PaymentState::Initial.can_move_to(PaymentState::Captured));
+        assert!(PaymentState::RefundNeeded.can_move_to(PaymentState::Refunded));
+        assert!(PaymentState::PaymentToSellerNeeded.can_move_to(PaymentState::PaidToSeller));
+        assert!(!PaymentState::Initial.can_move_to(PaymentState::PaidToSeller));
+        assert!(!PaymentState::Refunded.can_move_to(PaymentState::Captured));
+        assert!(!PaymentState::PaidToSeller.can_move_to(PaymentState::RefundNeeded));
+    }
+
+    #[test]
+    fn payout_claim_transitions() {
+        assert!(PaymentState::PaymentToSellerNeeded.can_move_to(PaymentState::PaymentToSellerInProgress));
+        assert!(PaymentState::PaymentToSellerInProgress.can_move_to(PaymentState::PaidToSeller));
+        assert!(PaymentState::PaymentToSellerInProgress.can_move_to(PaymentState::PaymentToSellerNeeded));
+        // orders paid to seller by hand are never claimed by payout run
+        assert!(!PaymentState::PaidToSeller.can_move_to(PaymentState::PaymentToSellerInProgress));
+    }
+
+    #[test]
+    fn payment_state_consistency() {
+        assert!(PaymentState::Refunded.is_consistent_with(OrderState::Cancelled));
+        assert!(PaymentState::PaidToSeller.is_consistent_with(OrderState::Complete));
+        assert!(!PaymentState::PaidToSeller.is_consistent_with(OrderState::Dispute));
+        assert!(PaymentState::Captured.is_consistent_with(OrderState::Dispute));
+        assert!(!PaymentState::Captured.is_consistent_with(OrderState::Paid));
+    }
 }
@@ -4,7 +4,9 @@ use serde_json;
 use uuid::Uuid;
 
 use stq_static_resources::ModerationStatus;
-use stq_types::{RoleEntryId, RoleId, SagaId, StoreId, UserId};
+use stq_types::{BaseProductId, BillingRole, DeliveryRole, ProductId, Quantity, RoleEntryId, RoleId, SagaId, StoreId, UserId, WarehouseId};
+
+use models::{NewOrdersRole, NewRole, NewWarehouseRole, RoleEntry};
 
 /// Payload for querying stores
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -75,6 +77,19 @@ pub struct NewStore {
     pub uuid: Uuid,
 }
 
+/// Store fields changed by saga coordinator, fields that are not set stay as they are
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct UpdateStore {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user_id: Option<UserId>,
+}
+
+/// Payload for moving store to another owner
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TransferStoreOwnership {
+    pub user_id: UserId,
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct CreateStoreMerchantPayload {
     pub id: StoreId,
@@ -98,3 +113,32 @@ pub enum CreateStoreOperationStage {
     BillingCreateMerchantStart(StoreId),
     BillingCreateMerchantComplete(StoreId),
 }
+
+pub type DeleteStoreOperationLog = Vec<DeleteStoreOperationStage>;
+
+/// Completed steps of store deletion, deactivation or role revocation, each keeps what is needed to restore the resource
+#[derive(Clone, Debug)]
+pub enum DeleteStoreOperationStage {
+    WarehousesRoleDeleted(RoleEntry<NewWarehouseRole>),
+    OrdersRoleDeleted(RoleEntry<NewOrdersRole>),
+    BillingRoleDeleted(NewRole<BillingRole>),
+    DeliveryRoleDeleted(NewRole<DeliveryRole>),
+    BillingDeleteMerchantComplete(CreateStoreMerchantPayload),
+    WarehouseStockCleared {
+        warehouse_id: WarehouseId,
+        product_id: ProductId,
+        quantity: Quantity,
+    },
+    StoreDeactivated(StoreId),
+    BaseProductDeactivated(BaseProductId),
+    BaseProductDeactivationSaved(BaseProductId),
+}
+
+pub type ReactivateStoreOperationLog = Vec<ReactivateStoreOperationStage>;
+
+/// Entities activated by store or base product reactivation, only these are deactivated back on failure
+#[derive(Clone, Debug)]
+pub enum ReactivateStoreOperationStage {
+    StoreActivated(StoreId),
+    BaseProductActivated(BaseProductId),
+}
@@ -46,6 +46,43 @@ pub struct Shipping {
     pub pickup: Option<Pickups>,
 }
 
+impl Shipping {
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty() && self.pickup.is_none()
+    }
+
+    /// Delivery microservice returns neither measurements nor origin of shipping items,
+    /// so items can only be set again when both are known, otherwise only pickup is kept
+    pub fn into_new_shipping(self, measurements: Option<Measurements>, delivery_from: Option<Alpha3>) -> NewShipping {
+        let items = match (measurements, delivery_from) {
+            (Some(measurements), Some(delivery_from)) => self
+                .items
+                .into_iter()
+                .map(|item| NewProducts {
+                    base_product_id: item.product.base_product_id,
+                    store_id: item.product.store_id,
+                    company_package_id: item.product.company_package_id,
+                    price: item.product.price,
+                    measurements,
+                    delivery_from: delivery_from.clone(),
+                    deliveries_to: item.product.deliveries_to,
+                    shipping: item.product.shipping,
+                    currency: item.product.currency,
+                })
+                .collect(),
+            _ => vec![],
+        };
+        let pickup = self.pickup.map(|pickup| NewPickups {
+            base_product_id: pickup.base_product_id,
+            store_id: pickup.store_id,
+            pickup: pickup.pickup,
+            price: pickup.price,
+        });
+
+        NewShipping { items, pickup }
+    }
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct ShippingProducts {
     pub product: Products,
@@ -1,21 +1,41 @@
 pub mod base_product;
+pub mod billing_events;
+pub mod carriers;
 pub mod create_order;
 pub mod create_profile;
 pub mod create_store;
 pub mod delivery;
 pub mod moderate;
+pub mod moderation_queue;
 pub mod notifications;
+pub mod order_history;
+pub mod order_reminders;
+pub mod order_search;
+pub mod payouts;
+pub mod quote;
 pub mod roles;
+pub mod scheduler_runs;
+pub mod store_staff;
 pub mod visibility;
 pub mod warehouses;
 
 pub use self::base_product::*;
+pub use self::billing_events::*;
+pub use self::carriers::*;
 pub use self::create_order::*;
 pub use self::create_profile::*;
 pub use self::create_store::*;
 pub use self::delivery::*;
 pub use self::moderate::*;
+pub use self::moderation_queue::*;
 pub use self::notifications::*;
+pub use self::order_history::*;
+pub use self::order_reminders::*;
+pub use self::order_search::*;
+pub use self::payouts::*;
+pub use self::quote::*;
 pub use self::roles::*;
+pub use self::scheduler_runs::*;
+pub use self::store_staff::*;
 pub use self::visibility::*;
 pub use self::warehouses::*;
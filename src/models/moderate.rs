@@ -3,16 +3,127 @@ use std::time::SystemTime;
 use stq_static_resources::{Currency, ModerationStatus, Translation};
 use stq_types::{BaseProductId, CategoryId, ProductId, ProductPrice, StoreId};
 
+use models::{Measurements, ModerationSubject};
+
+/// Why moderator declined or blocked store or base product
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ModerationRejectionReason {
+    IncompleteInformation,
+    ProhibitedContent,
+    PoorQualityImages,
+    MisleadingDescription,
+    IntellectualPropertyViolation,
+    Other,
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct StoreModerate {
     pub store_id: StoreId,
     pub status: ModerationStatus,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<ModerationRejectionReason>,
+    /// Moderator's comment for the seller
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub comment: Option<String>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct BaseProductModerate {
     pub base_product_id: BaseProductId,
     pub status: ModerationStatus,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<ModerationRejectionReason>,
+    /// Moderator's comment for the seller
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub comment: Option<String>,
+}
+
+/// Moderator's decision kept in moderation history of store or base product
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct NewModerationHistoryEntry {
+    pub subject: ModerationSubject,
+    pub status: ModerationStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<ModerationRejectionReason>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub comment: Option<String>,
+}
+
+impl StoreModerate {
+    pub fn history_entry(&self) -> NewModerationHistoryEntry {
+        NewModerationHistoryEntry {
+            subject: ModerationSubject::Store(self.store_id),
+            status: self.status,
+            reason: self.reason,
+            comment: self.comment.clone(),
+        }
+    }
+}
+
+impl BaseProductModerate {
+    pub fn history_entry(&self) -> NewModerationHistoryEntry {
+        NewModerationHistoryEntry {
+            subject: ModerationSubject::BaseProduct(self.base_product_id),
+            status: self.status,
+            reason: self.reason,
+            comment: self.comment.clone(),
+        }
+    }
+}
+
+/// Stores and base products moderated at once
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct BulkModeratePayload {
+    #[serde(default)]
+    pub stores: Vec<StoreModerate>,
+    #[serde(default)]
+    pub base_products: Vec<BaseProductModerate>,
+}
+
+impl BulkModeratePayload {
+    pub fn into_items(self) -> Vec<ModerateItem> {
+        self.stores
+            .into_iter()
+            .map(ModerateItem::Store)
+            .chain(self.base_products.into_iter().map(ModerateItem::BaseProduct))
+            .collect()
+    }
+}
+
+/// Single item of bulk moderation
+#[derive(Clone, Debug)]
+pub enum ModerateItem {
+    Store(StoreModerate),
+    BaseProduct(BaseProductModerate),
+}
+
+impl ModerateItem {
+    pub fn subject(&self) -> ModerationSubject {
+        match self {
+            ModerateItem::Store(payload) => ModerationSubject::Store(payload.store_id),
+            ModerateItem::BaseProduct(payload) => ModerationSubject::BaseProduct(payload.base_product_id),
+        }
+    }
+}
+
+/// Outcome of moderating a single store or base product in bulk moderation
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BulkModerateResult {
+    pub subject: ModerationSubject,
+    /// Status set by moderation, `None` if moderation failed
+    pub status: Option<ModerationStatus>,
+    pub error: Option<String>,
+}
+
+/// Rejection reason can only be given together with status that rejects store or base product
+pub fn is_rejection_status(status: ModerationStatus) -> bool {
+    status == ModerationStatus::Decline || status == ModerationStatus::Blocked
+}
+
+/// Published store or base product has to pass moderation again after it is reactivated
+pub fn is_moderation_required_after_reactivation(status: ModerationStatus) -> bool {
+    status == ModerationStatus::Published
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -41,6 +152,18 @@ pub struct BaseProduct {
     pub weight_g: Option<i32>,
 }
 
+impl BaseProduct {
+    pub fn measurements(&self) -> Option<Measurements> {
+        match (self.volume_cubic_cm, self.weight_g) {
+            (Some(volume_cubic_cm), Some(weight_g)) if volume_cubic_cm >= 0 && weight_g >= 0 => Some(Measurements {
+                volume_cubic_cm: volume_cubic_cm as u32,
+                weight_g: weight_g as u32,
+            }),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Product {
     pub uuid: String,
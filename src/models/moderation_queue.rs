@@ -0,0 +1,294 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Duration, Utc};
+
+use stq_types::{BaseProductId, StoreId, UserId};
+
+/// Store or base product waiting for moderation
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(tag = "type", content = "id", rename_all = "snake_case")]
+pub enum ModerationSubject {
+    Store(StoreId),
+    BaseProduct(BaseProductId),
+}
+
+impl ModerationSubject {
+    pub fn as_label(&self) -> &'static str {
+        match self {
+            ModerationSubject::Store(_) => "store",
+            ModerationSubject::BaseProduct(_) => "base_product",
+        }
+    }
+}
+
+/// Moderator responsible for the subject
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ModerationAssignment {
+    pub subject: ModerationSubject,
+    pub store_id: StoreId,
+    pub moderator_id: UserId,
+    /// When the subject was sent to moderation, kept across reassignments
+    pub enqueued_at: DateTime<Utc>,
+    pub assigned_at: DateTime<Utc>,
+    /// When the assigned moderator was reminded that the subject is overdue
+    #[serde(default)]
+    pub reminded_at: Option<DateTime<Utc>>,
+    /// When all moderators were told that the subject is overdue
+    #[serde(default)]
+    pub escalated_at: Option<DateTime<Utc>>,
+}
+
+/// Subjects waiting for moderation, every one of them is assigned to a single moderator.
+/// Moderator with the fewest assignments gets the next subject, ties are broken round-robin.
+#[derive(Debug, Default)]
+pub struct ModerationQueue {
+    assignments: HashMap<ModerationSubject, ModerationAssignment>,
+    last_moderator: Option<UserId>,
+}
+
+impl ModerationQueue {
+    /// Queue over assignments kept in stores microservice, the latest assignment tells whose turn is next
+    pub fn new(assignments: Vec<ModerationAssignment>) -> Self {
+        let last_moderator = assignments
+            .iter()
+            .max_by_key(|assignment| assignment.assigned_at)
+            .map(|assignment| assignment.moderator_id);
+        Self {
+            assignments: assignments.into_iter().map(|assignment| (assignment.subject, assignment)).collect(),
+            last_moderator,
+        }
+    }
+
+    /// Assigns subject to a moderator, subject sent to moderation again stays with its moderator
+    /// if they are still a moderator
+    pub fn assign(
+        &mut self,
+        subject: ModerationSubject,
+        store_id: StoreId,
+        moderators: &[UserId],
+        now: DateTime<Utc>,
+    ) -> Option<ModerationAssignment> {
+        if let Some(assignment) = self.assignments.get_mut(&subject) {
+            if moderators.contains(&assignment.moderator_id) {
+                assignment.assigned_at = now;
+                assignment.reminded_at = None;
+                return Some(assignment.clone());
+            }
+        }
+
+        let moderator_id = self.pick_moderator(moderators, None)?;
+        let (enqueued_at, escalated_at) = self
+            .assignments
+            .get(&subject)
+            .map(|assignment| (assignment.enqueued_at, assignment.escalated_at))
+            .unwrap_or((now, None));
+        let assignment = ModerationAssignment {
+            subject,
+            store_id,
+            moderator_id,
+            enqueued_at,
+            assigned_at: now,
+            reminded_at: None,
+            escalated_at,
+        };
+        self.assignments.insert(subject, assignment.clone());
+        Some(assignment)
+    }
+
+    /// Moves subject to another moderator if there is one
+    pub fn reassign(&mut self, subject: ModerationSubject, moderators: &[UserId], now: DateTime<Utc>) -> Option<ModerationAssignment> {
+        let current = self.assignments.get(&subject)?.moderator_id;
+        let moderator_id = self.pick_moderator(moderators, Some(current))?;
+        let assignment = self.assignments.get_mut(&subject)?;
+        assignment.moderator_id = moderator_id;
+        assignment.assigned_at = now;
+        assignment.reminded_at = None;
+        Some(assignment.clone())
+    }
+
+    /// Removes subject from the queue once its moderation is finished
+    pub fn complete(&mut self, subject: ModerationSubject) -> Option<ModerationAssignment> {
+        self.assignments.remove(&subject)
+    }
+
+    /// Subjects their moderator did not handle in time
+    pub fn expired(&self, now: DateTime<Utc>, timeout: Duration) -> Vec<ModerationSubject> {
+        self.assignments
+            .values()
+            .filter(|assignment| assignment.assigned_at + timeout <= now)
+            .map(|assignment| assignment.subject)
+            .collect()
+    }
+
+    /// Subjects waiting longer than `sla` since their moderator got them, every moderator is reminded once
+    pub fn due_reminders(&mut self, now: DateTime<Utc>, sla: Duration) -> Vec<ModerationAssignment> {
+        self.assignments
+            .values_mut()
+            .filter(|assignment| assignment.reminded_at.is_none() && assignment.assigned_at + sla <= now)
+            .map(|assignment| {
+                assignment.reminded_at = Some(now);
+                assignment.clone()
+            })
+            .collect()
+    }
+
+    /// Subjects waiting longer than `threshold` since they were sent to moderation, every subject is escalated once
+    pub fn due_escalations(&mut self, now: DateTime<Utc>, threshold: Duration) -> Vec<ModerationAssignment> {
+        self.assignments
+            .values_mut()
+            .filter(|assignment| assignment.escalated_at.is_none() && assignment.enqueued_at + threshold <= now)
+            .map(|assignment| {
+                assignment.escalated_at = Some(now);
+                assignment.clone()
+            })
+            .collect()
+    }
+
+    /// All subjects waiting for moderation
+    pub fn waiting(&self) -> Vec<ModerationAssignment> {
+        self.assignments.values().cloned().collect()
+    }
+
+    /// Assignments of the moderator, oldest first
+    pub fn assigned_to(&self, moderator_id: UserId) -> Vec<ModerationAssignment> {
+        let mut assignments = self
+            .assignments
+            .values()
+            .filter(|assignment| assignment.moderator_id == moderator_id)
+            .cloned()
+            .collect::<Vec<_>>();
+        assignments.sort_by_key(|assignment| assignment.assigned_at);
+        assignments
+    }
+
+    fn pick_moderator(&mut self, moderators: &[UserId], exclude: Option<UserId>) -> Option<UserId> {
+        // candidates start right after the last picked moderator, so equally loaded ones take turns
+        let start = self
+            .last_moderator
+            .and_then(|last| moderators.iter().position(|moderator_id| *moderator_id == last))
+            .map(|position| position + 1)
+            .unwrap_or(0);
+        let candidates = moderators
+            .iter()
+            .cycle()
+            .skip(start)
+            .take(moderators.len())
+            .cloned()
+            .filter(|moderator_id| Some(*moderator_id) != exclude);
+
+        let moderator_id = {
+            let load = |moderator_id: UserId| {
+                self.assignments
+                    .values()
+                    .filter(|assignment| assignment.moderator_id == moderator_id)
+                    .count()
+            };
+            candidates.min_by_key(|moderator_id| load(*moderator_id))?
+        };
+        self.last_moderator = Some(moderator_id);
+        Some(moderator_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn moderators() -> Vec<UserId> {
+        vec![UserId(1), UserId(2), UserId(3)]
+    }
+
+    #[test]
+    fn subjects_go_to_least_loaded_moderator() {
+        let mut queue = ModerationQueue::default();
+        let now = Utc::now();
+        let assigned = (1..4)
+            .map(|id| {
+                queue
+                    .assign(ModerationSubject::Store(StoreId(id)), StoreId(id), &moderators(), now)
+                    .unwrap()
+                    .moderator_id
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(assigned, vec![UserId(1), UserId(2), UserId(3)]);
+
+        queue.complete(ModerationSubject::Store(StoreId(2)));
+        let assignment = queue
+            .assign(ModerationSubject::BaseProduct(BaseProductId(1)), StoreId(1), &moderators(), now)
+            .unwrap();
+        assert_eq!(assignment.moderator_id, UserId(2));
+    }
+
+    #[test]
+    fn restored_queue_continues_round_robin() {
+        let mut queue = ModerationQueue::default();
+        let now = Utc::now();
+        queue
+            .assign(ModerationSubject::Store(StoreId(1)), StoreId(1), &moderators(), now)
+            .unwrap();
+        queue
+            .assign(
+                ModerationSubject::Store(StoreId(2)),
+                StoreId(2),
+                &moderators(),
+                now + Duration::minutes(1),
+            )
+            .unwrap();
+        queue.complete(ModerationSubject::Store(StoreId(1)));
+
+        let mut restored = ModerationQueue::new(queue.waiting());
+        let assignment = restored
+            .assign(ModerationSubject::Store(StoreId(3)), StoreId(3), &moderators(), now)
+            .unwrap();
+        assert_eq!(assignment.moderator_id, UserId(3));
+    }
+
+    #[test]
+    fn resent_subject_stays_with_its_moderator() {
+        let mut queue = ModerationQueue::default();
+        let now = Utc::now();
+        let subject = ModerationSubject::Store(StoreId(1));
+        queue.assign(subject, StoreId(1), &moderators(), now).unwrap();
+        let assignment = queue.assign(subject, StoreId(1), &moderators(), now + Duration::hours(1)).unwrap();
+        assert_eq!(assignment.moderator_id, UserId(1));
+        assert_eq!(assignment.enqueued_at, now);
+        assert_eq!(queue.assigned_to(UserId(1)).len(), 1);
+    }
+
+    #[test]
+    fn expired_subject_is_reassigned_to_another_moderator() {
+        let mut queue = ModerationQueue::default();
+        let now = Utc::now();
+        let subject = ModerationSubject::Store(StoreId(1));
+        queue.assign(subject, StoreId(1), &moderators(), now).unwrap();
+
+        assert!(queue.expired(now + Duration::hours(1), Duration::hours(2)).is_empty());
+        assert_eq!(queue.expired(now + Duration::hours(2), Duration::hours(2)), vec![subject]);
+
+        let assignment = queue.reassign(subject, &moderators(), now + Duration::hours(2)).unwrap();
+        assert_ne!(assignment.moderator_id, UserId(1));
+        assert_eq!(assignment.enqueued_at, now);
+        assert!(queue.assigned_to(UserId(1)).is_empty());
+        assert!(queue.reassign(subject, &[assignment.moderator_id], now).is_none());
+    }
+
+    #[test]
+    fn overdue_subject_is_reminded_and_escalated_once() {
+        let mut queue = ModerationQueue::default();
+        let now = Utc::now();
+        let subject = ModerationSubject::Store(StoreId(1));
+        queue.assign(subject, StoreId(1), &moderators(), now).unwrap();
+
+        assert!(queue.due_reminders(now + Duration::hours(1), Duration::hours(2)).is_empty());
+        assert_eq!(queue.due_reminders(now + Duration::hours(2), Duration::hours(2)).len(), 1);
+        assert!(queue.due_reminders(now + Duration::hours(3), Duration::hours(2)).is_empty());
+
+        // new moderator gets own reminder, escalation counts from the time subject was sent to moderation
+        queue.reassign(subject, &moderators(), now + Duration::hours(3)).unwrap();
+        assert!(queue.due_reminders(now + Duration::hours(4), Duration::hours(2)).is_empty());
+        assert_eq!(queue.due_reminders(now + Duration::hours(5), Duration::hours(2)).len(), 1);
+
+        assert_eq!(queue.due_escalations(now + Duration::hours(5), Duration::hours(5)).len(), 1);
+        assert!(queue.due_escalations(now + Duration::hours(6), Duration::hours(5)).is_empty());
+    }
+}
@@ -1,4 +1,11 @@
-use stq_types::{Alpha3, EmarsysId, UserId};
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+
+use stq_static_resources::{Currency, EmailUser, ModerationStatus, OrderState};
+use stq_types::{Alpha3, EmarsysId, StoreId, UserId};
+
+use models::{ModerationRejectionReason, ModerationSubject};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CreateEmarsysContactPayload {
@@ -14,3 +21,228 @@ pub struct CreatedEmarsysContact {
     pub user_id: UserId,
     pub emarsys_id: EmarsysId,
 }
+
+/// Reminds the store that a paid order will be declined if it is not confirmed before `deadline`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrderConfirmationReminderForStore {
+    pub store_email: String,
+    pub store_id: String,
+    pub order_slug: String,
+    pub deadline: DateTime<Utc>,
+    pub cluster_url: String,
+}
+
+/// Tells moderator that customer opened a dispute on the order
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrderDisputeForModerator {
+    pub user: EmailUser,
+    pub store_id: String,
+    pub order_slug: String,
+    pub cluster_url: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrderStateForUser {
+    pub order_slug: String,
+    pub order_state: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tracking_url: Option<String>,
+}
+
+/// Tells user about state changes of several orders at once
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrdersUpdateStateForUser {
+    pub user: EmailUser,
+    pub orders: Vec<OrderStateForUser>,
+    pub cluster_url: String,
+}
+
+/// Same as `OrderUpdateStateForUser` from static resources, with link to carrier tracking page
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrderUpdateStateWithTrackingForUser {
+    pub user: EmailUser,
+    pub order_slug: String,
+    pub order_state: String,
+    pub cluster_url: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tracking_url: Option<String>,
+}
+
+/// Reminds the store to ship pre-ordered item before `ship_deadline`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PreOrderShipReminderForStore {
+    pub store_email: String,
+    pub store_id: String,
+    pub order_slug: String,
+    pub ship_deadline: DateTime<Utc>,
+    pub cluster_url: String,
+}
+
+/// Tells user when paid pre-ordered item is expected to be shipped
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PreOrderExpectedDateForUser {
+    pub user: EmailUser,
+    pub order_slug: String,
+    pub expected_date: DateTime<Utc>,
+    pub cluster_url: String,
+}
+
+/// Tells moderator about order state change
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrderUpdateStateForModerator {
+    pub user: EmailUser,
+    pub store_id: String,
+    pub order_slug: String,
+    pub order_state: String,
+    pub cluster_url: String,
+}
+
+/// Tells user about money of the order being refunded or paid to seller
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrderPaymentStateForUser {
+    pub user: EmailUser,
+    pub order_slug: String,
+    pub payment_state: String,
+    pub cluster_url: String,
+}
+
+/// Tells store about money of the order being refunded or paid to seller
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrderPaymentStateForStore {
+    pub store_email: String,
+    pub store_id: String,
+    pub order_slug: String,
+    pub payment_state: String,
+    pub cluster_url: String,
+}
+
+/// Tells store about orders paid out to the seller
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PayoutStatementForStore {
+    pub store_email: String,
+    pub store_id: String,
+    pub currency: Currency,
+    pub amount: f64,
+    pub orders: Vec<PayoutStatementOrder>,
+    pub cluster_url: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PayoutStatementOrder {
+    pub order_id: String,
+    pub amount: f64,
+}
+
+/// Tells user about new invoice created after amount of the previous one expired
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InvoiceRequotedForUser {
+    pub user: EmailUser,
+    pub order_slugs: Vec<String>,
+    pub amount: f64,
+    pub currency: Currency,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub wallet: Option<String>,
+    pub cluster_url: String,
+}
+
+/// Tells buyer the store of their open orders was deactivated
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StoreDeactivatedForUser {
+    pub user: EmailUser,
+    pub store_id: String,
+    pub order_slugs: Vec<String>,
+    pub cluster_url: String,
+}
+
+/// Tells user they were added to store staff
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StoreStaffInvitationForUser {
+    pub user: EmailUser,
+    pub store_id: String,
+    /// Users without an account have to sign up to join the staff
+    pub has_account: bool,
+    pub cluster_url: String,
+}
+
+/// Order states user or store does not want to be emailed about
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct NotificationOptOuts {
+    pub order_states: Vec<OrderState>,
+}
+
+/// Opt-outs of all recipients of a notification batch
+#[derive(Debug, Clone, Default)]
+pub struct RecipientsOptOuts {
+    pub users: HashMap<UserId, Vec<OrderState>>,
+    pub stores: HashMap<StoreId, Vec<OrderState>>,
+}
+
+impl RecipientsOptOuts {
+    pub fn user(&self, user_id: UserId) -> &[OrderState] {
+        self.users.get(&user_id).map(|states| states.as_slice()).unwrap_or(&[])
+    }
+
+    pub fn store(&self, store_id: StoreId) -> &[OrderState] {
+        self.stores.get(&store_id).map(|states| states.as_slice()).unwrap_or(&[])
+    }
+}
+
+/// Same as `StoreModerationStatusForUser` from static resources, with moderator's reason and comment
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StoreModerationStatusWithReasonForUser {
+    pub store_email: String,
+    pub store_id: String,
+    pub status: ModerationStatus,
+    pub cluster_url: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<ModerationRejectionReason>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub comment: Option<String>,
+}
+
+/// Same as `BaseProductModerationStatusForUser` from static resources, with moderator's reason and comment
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BaseProductModerationStatusWithReasonForUser {
+    pub store_email: String,
+    pub store_id: String,
+    pub base_product_id: String,
+    pub status: ModerationStatus,
+    pub cluster_url: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<ModerationRejectionReason>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub comment: Option<String>,
+}
+
+/// Moderation result of a single store or base product in the digest
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModerationStatusDigestItem {
+    pub store_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub base_product_id: Option<String>,
+    pub status: ModerationStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<ModerationRejectionReason>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub comment: Option<String>,
+}
+
+/// Tells store manager about moderation of several stores and base products at once
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModerationStatusDigestForUser {
+    pub store_email: String,
+    pub items: Vec<ModerationStatusDigestItem>,
+    pub cluster_url: String,
+}
+
+/// Tells moderator that store or base product waits for moderation for too long,
+/// escalated ones are sent to all moderators
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModerationOverdueForModerator {
+    pub user: EmailUser,
+    pub subject: ModerationSubject,
+    pub store_id: String,
+    pub waiting_hours: i64,
+    pub escalated: bool,
+    pub cluster_url: String,
+}
@@ -0,0 +1,90 @@
+use chrono::{DateTime, Utc};
+
+use stq_static_resources::{CommitterRole, OrderState};
+use stq_types::{OrderId, OrderSlug, UserId};
+
+use models::PaymentState;
+
+/// What triggered order state change
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StateChangeSource {
+    /// State was set by user through saga coordinator
+    Manual,
+    /// State was set by billing state callback
+    BillingCallback,
+    /// State was set by saga coordinator scheduler
+    Scheduler,
+    /// Dispute was resolved by moderator
+    DisputeResolution,
+}
+
+impl StateChangeSource {
+    /// Only manual changes are made by a user, the rest are recorded as made by the system
+    pub fn committer_role(&self, role: Option<CommitterRole>) -> HistoryCommitterRole {
+        match (self, role) {
+            (StateChangeSource::Manual, Some(role)) => HistoryCommitterRole::User(role),
+            (StateChangeSource::DisputeResolution, _) => HistoryCommitterRole::Moderator,
+            _ => HistoryCommitterRole::System,
+        }
+    }
+}
+
+/// Role the order state was changed in, `System` is saga coordinator acting on its own
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HistoryCommitterRole {
+    User(CommitterRole),
+    Moderator,
+    System,
+}
+
+/// Call to billing microservice caused by order state change
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BillingSideEffect {
+    DeclineOrder,
+    CaptureOrder,
+    SetPaymentState(PaymentState),
+}
+
+/// Billing call needed when order moves from `old_state` to `new_state`
+pub fn billing_side_effect(old_state: OrderState, new_state: OrderState, pre_order: bool) -> Option<BillingSideEffect> {
+    match (old_state, new_state) {
+        // order canceled by seller - we need to do refund on billing
+        (OrderState::Paid, OrderState::Cancelled) => Some(BillingSideEffect::DeclineOrder),
+        // order confirmed by seller - we need to do capture on billing
+        (OrderState::Paid, OrderState::InProcessing) => Some(BillingSideEffect::CaptureOrder),
+        // dispute resolved in favour of buyer - we need to do refund on billing
+        (OrderState::Dispute, OrderState::Cancelled) => Some(BillingSideEffect::SetPaymentState(PaymentState::RefundNeeded)),
+        // pre-order canceled after money was captured - we need to do refund on billing
+        (OrderState::InProcessing, OrderState::Cancelled) if pre_order => {
+            Some(BillingSideEffect::SetPaymentState(PaymentState::RefundNeeded))
+        }
+        // order completed by seller or buyer - we need to send money to seller on billing
+        (_, OrderState::Complete) => Some(BillingSideEffect::SetPaymentState(PaymentState::PaymentToSellerNeeded)),
+        _ => None,
+    }
+}
+
+/// Single order state change as it is stored in orders microservice
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct NewOrderHistoryEntry {
+    pub order_id: OrderId,
+    pub order_slug: OrderSlug,
+    pub previous_state: OrderState,
+    pub state: OrderState,
+    pub committer: Option<UserId>,
+    pub committer_role: HistoryCommitterRole,
+    pub comment: Option<String>,
+    pub track_id: Option<String>,
+    pub source: StateChangeSource,
+    pub billing_side_effect: Option<BillingSideEffect>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct OrderHistoryEntry {
+    #[serde(flatten)]
+    pub entry: NewOrderHistoryEntry,
+    pub created_at: DateTime<Utc>,
+}
@@ -0,0 +1,35 @@
+use std::fmt;
+
+use chrono::{DateTime, Utc};
+
+/// Reminder sent to store about the order it has to act on
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OrderReminderKind {
+    ConfirmOrder,
+    ShipPreOrder,
+}
+
+impl fmt::Display for OrderReminderKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s = match self {
+            OrderReminderKind::ConfirmOrder => "confirm_order",
+            OrderReminderKind::ShipPreOrder => "ship_pre_order",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+/// Reminder to be recorded unless one of the same kind was recorded after `since`
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct NewOrderReminder {
+    pub kind: OrderReminderKind,
+    pub since: DateTime<Utc>,
+}
+
+/// Reminder recorded in orders microservice
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct OrderReminder {
+    pub kind: OrderReminderKind,
+    pub reminded_at: DateTime<Utc>,
+}
@@ -0,0 +1,51 @@
+use chrono::{DateTime, Utc};
+
+use stq_static_resources::OrderState;
+use stq_types::StoreId;
+
+/// Filter for searching orders in orders microservice
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct OrderSearchTerms {
+    pub state: Option<OrderState>,
+    pub store_id: Option<StoreId>,
+    pub pre_order: Option<bool>,
+    /// Only orders that were updated after this moment
+    pub updated_from: Option<DateTime<Utc>>,
+    /// Only orders that were not updated after this moment
+    pub updated_to: Option<DateTime<Utc>>,
+}
+
+impl OrderSearchTerms {
+    /// Orders waiting for seller confirmation since before `updated_to`,
+    /// pre-orders are left to their own ship deadline
+    pub fn awaiting_confirmation(updated_to: DateTime<Utc>) -> Self {
+        OrderSearchTerms {
+            state: Some(OrderState::Paid),
+            pre_order: Some(false),
+            updated_to: Some(updated_to),
+            ..Default::default()
+        }
+    }
+}
+
+/// Order is open until it is either completed or cancelled
+pub fn is_open_order_state(state: OrderState) -> bool {
+    match state {
+        OrderState::Complete | OrderState::Cancelled => false,
+        _ => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn awaiting_confirmation_skips_pre_orders() {
+        let updated_to = Utc::now();
+        let terms = OrderSearchTerms::awaiting_confirmation(updated_to);
+        assert_eq!(terms.state, Some(OrderState::Paid));
+        assert_eq!(terms.pre_order, Some(false));
+        assert_eq!(terms.updated_to, Some(updated_to));
+    }
+}
@@ -0,0 +1,190 @@
+use chrono::{DateTime, Utc};
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+use stq_static_resources::Currency;
+use stq_types::{MerchantId, OrderId, ProductPrice, StoreId};
+
+use models::{period_start, BillingOrder, PaymentState};
+
+/// Orders of one store paid out to the seller in one currency
+#[derive(Clone, Debug, PartialEq)]
+pub struct PayoutGroup {
+    pub store_id: StoreId,
+    pub currency: Currency,
+    pub orders: Vec<BillingOrder>,
+}
+
+impl PayoutGroup {
+    /// Groups orders waiting for payment to seller by store and currency
+    pub fn from_orders(orders: Vec<BillingOrder>) -> Vec<PayoutGroup> {
+        let mut groups: Vec<PayoutGroup> = vec![];
+        for order in orders {
+            let position = groups
+                .iter()
+                .position(|group| group.store_id == order.store_id && group.currency == order.seller_currency);
+            match position {
+                Some(position) => groups[position].orders.push(order),
+                None => groups.push(PayoutGroup {
+                    store_id: order.store_id,
+                    currency: order.seller_currency,
+                    orders: vec![order],
+                }),
+            }
+        }
+        groups
+    }
+
+    pub fn order_ids(&self) -> Vec<OrderId> {
+        self.orders.iter().map(|order| order.id).collect()
+    }
+
+    pub fn total_amount(&self) -> ProductPrice {
+        ProductPrice(self.orders.iter().map(|order| order.total_amount.0).sum())
+    }
+
+    /// Same orders always give the same id, so billing recognizes a repeated request for the payout
+    pub fn payout_id(&self) -> Uuid {
+        let mut order_ids = self.orders.iter().map(|order| order.id.to_string()).collect::<Vec<_>>();
+        order_ids.sort();
+        let mut hasher = Sha256::default();
+        for order_id in order_ids {
+            hasher.input(order_id.as_bytes());
+        }
+        let hash = hasher.result();
+        Uuid::from_bytes(&hash[..16]).expect("First 16 bytes of the hash always make uuid")
+    }
+}
+
+/// Orders that may be claimed for payout, payout of orders in dispute is frozen until the dispute is resolved
+pub fn claimable_orders(orders: Vec<BillingOrder>, disputed: &[OrderId]) -> Vec<BillingOrder> {
+    orders.into_iter().filter(|order| !disputed.contains(&order.id)).collect()
+}
+
+/// Payment state claimed orders are left in after their payout: paid when billing created
+/// the payout, returned to the next run otherwise
+pub fn settled_payment_state(payout_created: bool) -> PaymentState {
+    if payout_created {
+        PaymentState::PaidToSeller
+    } else {
+        PaymentState::PaymentToSellerNeeded
+    }
+}
+
+/// Payout run of one period, billing reserves it for the first instance asking
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct NewPayoutRun {
+    pub period_start: DateTime<Utc>,
+}
+
+impl NewPayoutRun {
+    /// Run of the period `now` falls into
+    pub fn containing(now: DateTime<Utc>, interval_s: i64) -> Self {
+        NewPayoutRun {
+            period_start: period_start(now, interval_s),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CreatePayout {
+    /// Billing creates payout with the same id only once
+    pub id: Uuid,
+    pub merchant_id: MerchantId,
+    pub currency: Currency,
+    pub order_ids: Vec<OrderId>,
+    pub amount: ProductPrice,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Payout {
+    pub id: Uuid,
+    pub merchant_id: MerchantId,
+    pub currency: Currency,
+    pub order_ids: Vec<OrderId>,
+    pub amount: ProductPrice,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use chrono::TimeZone;
+
+    fn billing_order(id: OrderId, store_id: i32, seller_currency: Currency, total_amount: f64) -> BillingOrder {
+        BillingOrder {
+            id,
+            store_id: StoreId(store_id),
+            state: PaymentState::PaymentToSellerNeeded,
+            seller_currency,
+            total_amount: ProductPrice(total_amount),
+        }
+    }
+
+    #[test]
+    fn payout_groups() {
+        let (first, second, third) = (OrderId::new(), OrderId::new(), OrderId::new());
+        let groups = PayoutGroup::from_orders(vec![
+            billing_order(first, 1, Currency::ETH, 1.5),
+            billing_order(second, 2, Currency::ETH, 3.0),
+            billing_order(third, 1, Currency::ETH, 2.0),
+            billing_order(OrderId::new(), 1, Currency::BTC, 0.1),
+        ]);
+
+        assert_eq!(groups.len(), 3);
+        assert_eq!(groups[0].store_id, StoreId(1));
+        assert_eq!(groups[0].order_ids(), vec![first, third]);
+        assert_eq!(groups[0].total_amount(), ProductPrice(3.5));
+        assert_eq!(groups[1].order_ids(), vec![second]);
+        assert_eq!(groups[2].currency, Currency::BTC);
+    }
+
+    #[test]
+    fn payout_id_depends_on_orders_only() {
+        let (first, second) = (OrderId::new(), OrderId::new());
+        let group = PayoutGroup::from_orders(vec![
+            billing_order(first, 1, Currency::ETH, 1.5),
+            billing_order(second, 1, Currency::ETH, 3.0),
+        ])
+        .remove(0);
+        let reordered = PayoutGroup::from_orders(vec![
+            billing_order(second, 1, Currency::ETH, 3.0),
+            billing_order(first, 1, Currency::ETH, 1.5),
+        ])
+        .remove(0);
+        let other = PayoutGroup::from_orders(vec![billing_order(first, 1, Currency::ETH, 1.5)]).remove(0);
+
+        assert_eq!(group.payout_id(), reordered.payout_id());
+        assert_ne!(group.payout_id(), other.payout_id());
+    }
+
+    #[test]
+    fn disputed_orders_are_not_paid_out() {
+        let (completed, disputed) = (OrderId::new(), OrderId::new());
+        let orders = claimable_orders(
+            vec![
+                billing_order(completed, 1, Currency::ETH, 1.5),
+                billing_order(disputed, 1, Currency::ETH, 2.0),
+            ],
+            &[disputed],
+        );
+
+        assert_eq!(orders.iter().map(|order| order.id).collect::<Vec<_>>(), vec![completed]);
+    }
+
+    #[test]
+    fn failed_payout_returns_orders_to_next_run() {
+        let failed = settled_payment_state(false);
+        assert_eq!(failed, PaymentState::PaymentToSellerNeeded);
+        assert!(PaymentState::PaymentToSellerInProgress.can_move_to(failed));
+        assert!(PaymentState::PaymentToSellerInProgress.can_move_to(settled_payment_state(true)));
+    }
+
+    #[test]
+    fn payout_run_period() {
+        let day = 24 * 3600;
+        let run = NewPayoutRun::containing(Utc.timestamp(3 * day + 100, 0), day);
+        assert_eq!(run.period_start, Utc.timestamp(3 * day, 0));
+        assert_eq!(NewPayoutRun::containing(Utc.timestamp(4 * day - 1, 0), day), run);
+    }
+}
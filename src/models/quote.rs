@@ -0,0 +1,159 @@
+use stq_api::orders::Order;
+use stq_static_resources::Currency;
+use stq_types::{CouponId, ProductId, ProductPrice, Quantity, StoreId};
+
+/// Price breakdown of a single cart item
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct QuoteItem {
+    pub product_id: ProductId,
+    pub quantity: Quantity,
+    pub price: ProductPrice,
+    /// Price multiplied by quantity
+    pub subtotal: ProductPrice,
+    pub coupon_id: Option<CouponId>,
+    pub coupon_discount: ProductPrice,
+    pub delivery_price: ProductPrice,
+    /// Amount the customer will be charged for this item
+    pub total: ProductPrice,
+}
+
+/// Items of one store paid in one currency
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct StoreQuote {
+    pub store_id: StoreId,
+    pub currency: Currency,
+    pub items: Vec<QuoteItem>,
+    pub subtotal: ProductPrice,
+    pub coupons_discount: ProductPrice,
+    pub delivery_price: ProductPrice,
+    pub total: ProductPrice,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CurrencyTotal {
+    pub currency: Currency,
+    pub total: ProductPrice,
+}
+
+/// Breakdown of what converting the cart would cost, nothing is created for it
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Quote {
+    pub stores: Vec<StoreQuote>,
+    pub totals: Vec<CurrencyTotal>,
+}
+
+impl Quote {
+    /// Builds quote from orders previewed by orders microservice, totals are taken
+    /// from orders as is, so the quote matches what invoice will charge
+    pub fn from_orders(orders: &[Order]) -> Self {
+        let items = orders
+            .iter()
+            .map(|order| {
+                let item = QuoteItem {
+                    product_id: order.product,
+                    quantity: order.quantity,
+                    price: order.price,
+                    subtotal: ProductPrice(order.price.0 * f64::from(order.quantity.0)),
+                    coupon_id: order.coupon_id,
+                    coupon_discount: order.coupon_discount.unwrap_or(ProductPrice(0.0)),
+                    delivery_price: ProductPrice(order.delivery_price),
+                    total: order.total_amount,
+                };
+                (order.store, order.currency, item)
+            })
+            .collect();
+        Quote::from_items(items)
+    }
+
+    /// Groups items by store and currency, totals are summed per currency
+    pub fn from_items(items: Vec<(StoreId, Currency, QuoteItem)>) -> Self {
+        let mut quote = Quote::default();
+
+        for (store_id, currency, item) in items {
+            let position = quote
+                .stores
+                .iter()
+                .position(|store| store.store_id == store_id && store.currency == currency);
+            let store = match position {
+                Some(position) => &mut quote.stores[position],
+                None => {
+                    quote.stores.push(StoreQuote {
+                        store_id,
+                        currency,
+                        items: vec![],
+                        subtotal: ProductPrice(0.0),
+                        coupons_discount: ProductPrice(0.0),
+                        delivery_price: ProductPrice(0.0),
+                        total: ProductPrice(0.0),
+                    });
+                    quote.stores.last_mut().unwrap()
+                }
+            };
+            store.subtotal.0 += item.subtotal.0;
+            store.coupons_discount.0 += item.coupon_discount.0;
+            store.delivery_price.0 += item.delivery_price.0;
+            store.total.0 += item.total.0;
+            store.items.push(item);
+        }
+
+        for store in &quote.stores {
+            let position = quote.totals.iter().position(|total| total.currency == store.currency);
+            match position {
+                Some(position) => quote.totals[position].total.0 += store.total.0,
+                None => quote.totals.push(CurrencyTotal {
+                    currency: store.currency,
+                    total: store.total,
+                }),
+            }
+        }
+
+        quote
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn quote_item(product_id: i32, quantity: i32, price: f64, coupon_discount: f64, delivery_price: f64) -> QuoteItem {
+        let subtotal = price * f64::from(quantity);
+        QuoteItem {
+            product_id: ProductId(product_id),
+            quantity: Quantity(quantity),
+            price: ProductPrice(price),
+            subtotal: ProductPrice(subtotal),
+            coupon_id: if coupon_discount > 0.0 { Some(CouponId(1)) } else { None },
+            coupon_discount: ProductPrice(coupon_discount),
+            delivery_price: ProductPrice(delivery_price),
+            total: ProductPrice(subtotal - coupon_discount + delivery_price),
+        }
+    }
+
+    #[test]
+    fn quote_from_items() {
+        let quote = Quote::from_items(vec![
+            (StoreId(1), Currency::ETH, quote_item(1, 2, 1.5, 0.5, 0.25)),
+            (StoreId(2), Currency::ETH, quote_item(2, 1, 4.0, 0.0, 1.0)),
+            (StoreId(1), Currency::ETH, quote_item(3, 1, 2.0, 0.0, 0.25)),
+            (StoreId(1), Currency::BTC, quote_item(4, 1, 0.5, 0.0, 0.0)),
+        ]);
+
+        assert_eq!(quote.stores.len(), 3);
+        let first = &quote.stores[0];
+        assert_eq!(first.store_id, StoreId(1));
+        assert_eq!(first.items.len(), 2);
+        assert_eq!(first.subtotal, ProductPrice(5.0));
+        assert_eq!(first.coupons_discount, ProductPrice(0.5));
+        assert_eq!(first.delivery_price, ProductPrice(0.5));
+        assert_eq!(first.total, ProductPrice(5.0));
+        assert_eq!(quote.stores[1].store_id, StoreId(2));
+        assert_eq!(quote.stores[1].total, ProductPrice(5.0));
+        assert_eq!(quote.stores[2].currency, Currency::BTC);
+
+        assert_eq!(quote.totals.len(), 2);
+        assert_eq!(quote.totals[0].currency, Currency::ETH);
+        assert_eq!(quote.totals[0].total, ProductPrice(10.0));
+        assert_eq!(quote.totals[1].currency, Currency::BTC);
+        assert_eq!(quote.totals[1].total, ProductPrice(0.5));
+    }
+}
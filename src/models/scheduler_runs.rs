@@ -0,0 +1,51 @@
+use chrono::{DateTime, TimeZone, Utc};
+
+/// Scheduler job that has to run once per tick whatever number of saga coordinator instances is running
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SchedulerJob {
+    AutoCompleteOrders,
+    AutoDeclineOrders,
+}
+
+/// Run of the job in one tick period, orders microservice reserves it for the first instance asking
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct NewSchedulerRun {
+    pub job: SchedulerJob,
+    pub period_start: DateTime<Utc>,
+}
+
+impl NewSchedulerRun {
+    pub fn containing(job: SchedulerJob, now: DateTime<Utc>, interval_s: i64) -> Self {
+        NewSchedulerRun {
+            job,
+            period_start: period_start(now, interval_s),
+        }
+    }
+}
+
+/// Start of the period `now` falls into, periods are counted from unix epoch
+pub fn period_start(now: DateTime<Utc>, interval_s: i64) -> DateTime<Utc> {
+    let timestamp = now.timestamp();
+    Utc.timestamp(timestamp - timestamp % interval_s, 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scheduler_run_period() {
+        let tick = 600;
+        let run = NewSchedulerRun::containing(SchedulerJob::AutoDeclineOrders, Utc.timestamp(3 * tick + 10, 0), tick);
+        assert_eq!(run.period_start, Utc.timestamp(3 * tick, 0));
+        assert_eq!(
+            NewSchedulerRun::containing(SchedulerJob::AutoDeclineOrders, Utc.timestamp(4 * tick - 1, 0), tick),
+            run
+        );
+        assert_ne!(
+            NewSchedulerRun::containing(SchedulerJob::AutoCompleteOrders, Utc.timestamp(3 * tick + 10, 0), tick),
+            run
+        );
+    }
+}
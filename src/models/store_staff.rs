@@ -0,0 +1,95 @@
+use uuid::Uuid;
+
+use stq_types::{StoreId, UserId};
+
+/// Services store staff member gets store manager role in, all of them unless restricted
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StaffProfile {
+    pub warehouses: bool,
+    pub orders: bool,
+    pub billing: bool,
+    pub delivery: bool,
+}
+
+impl Default for StaffProfile {
+    fn default() -> Self {
+        Self {
+            warehouses: true,
+            orders: true,
+            billing: true,
+            delivery: true,
+        }
+    }
+}
+
+impl StaffProfile {
+    /// Profile that does not give access to any service
+    pub fn is_empty(&self) -> bool {
+        !(self.warehouses || self.orders || self.billing || self.delivery)
+    }
+}
+
+/// Payload for adding existing user to store staff
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct NewStoreStaff {
+    pub user_id: UserId,
+    #[serde(default)]
+    pub profile: StaffProfile,
+}
+
+/// Payload for inviting user to store staff by email
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct StoreStaffInvite {
+    pub email: String,
+    #[serde(default)]
+    pub profile: StaffProfile,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct StoreStaffMember {
+    pub store_id: StoreId,
+    pub user_id: UserId,
+    pub profile: StaffProfile,
+}
+
+/// Invitation of the user without an account, accepted once they verify the email
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct StoreStaffInvitation {
+    pub id: Uuid,
+    pub store_id: StoreId,
+    pub email: String,
+    pub profile: StaffProfile,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct StoreStaffInvitationSearch {
+    pub email: String,
+}
+
+/// Users with an account are added to the staff at once, the rest are invited
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum StoreStaffInviteResult {
+    Added(StoreStaffMember),
+    Invited(StoreStaffInvitation),
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json;
+
+    use super::*;
+
+    #[test]
+    fn staff_profile_defaults_to_full_access() {
+        let new_staff = serde_json::from_str::<NewStoreStaff>(r#"{"user_id": 1}"#).unwrap();
+        assert_eq!(new_staff.profile, StaffProfile::default());
+
+        let new_staff =
+            serde_json::from_str::<NewStoreStaff>(r#"{"user_id": 1, "profile": {"billing": false, "delivery": false}}"#).unwrap();
+        assert!(new_staff.profile.warehouses && new_staff.profile.orders);
+        assert!(!new_staff.profile.billing && !new_staff.profile.delivery);
+        assert!(!new_staff.profile.is_empty());
+    }
+}
@@ -0,0 +1,225 @@
+//! `Scheduler` runs periodic jobs of saga coordinator, i.e. sagas
+//! that are not triggered by any http request.
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::Utc;
+use failure::Error as FailureError;
+use futures::future;
+use futures::prelude::*;
+use futures::stream::iter_ok;
+use tokio_core::reactor::{Handle, Interval};
+
+use stq_http::client::{ClientHandle as HttpClientHandle, TimeLimitedHttpClient};
+
+use config::Config;
+use metrics;
+use microservice::{
+    BillingMicroserviceImpl, DeliveryMicroserviceImpl, NotificationsMicroserviceImpl, OrdersMicroserviceImpl, StoresMicroserviceImpl,
+    UsersMicroserviceImpl, WarehousesMicroserviceImpl,
+};
+use models::CarrierRegistry;
+use sentry_integration::log_and_capture_error;
+use services::order::{OrderService, OrderServiceImpl};
+use services::store::{StoreService, StoreServiceImpl};
+
+type JobFuture = Box<Future<Item = (), Error = ()>>;
+
+#[derive(Clone)]
+pub struct Scheduler {
+    pub config: Config,
+    pub http_client: HttpClientHandle,
+    pub carriers: Arc<CarrierRegistry>,
+}
+
+impl Scheduler {
+    /// Spawns scheduler jobs on the event loop
+    pub fn start(self, handle: &Handle) {
+        let tick_interval = Duration::from_secs(self.config.scheduler.tick_interval_s);
+        let interval = Interval::new(tick_interval, handle).expect("Could not create scheduler interval");
+
+        info!("Scheduler started with tick interval {:?}", tick_interval);
+        handle.spawn(
+            interval
+                .map_err(|e| error!("Scheduler interval error: {}", e))
+                .for_each(move |_| self.run_jobs()),
+        );
+    }
+
+    fn run_jobs(&self) -> impl Future<Item = (), Error = ()> {
+        debug!("Running scheduler jobs");
+        let jobs: Vec<fn(&Scheduler) -> JobFuture> = vec![
+            Scheduler::auto_complete_orders,
+            Scheduler::remind_stores_to_confirm_orders,
+            Scheduler::auto_decline_orders,
+            Scheduler::remind_stores_to_ship_pre_orders,
+            Scheduler::auto_cancel_pre_orders,
+            Scheduler::pay_sellers,
+            Scheduler::reassign_expired_moderations,
+            Scheduler::remind_overdue_moderations,
+            Scheduler::escalate_overdue_moderations,
+            Scheduler::report_moderation_wait_times,
+        ];
+        let scheduler = self.clone();
+        iter_ok(jobs).for_each(move |job| job(&scheduler))
+    }
+
+    // every instance ticks, orders are completed by the one which reserved the run of the current tick
+    fn auto_complete_orders(&self) -> JobFuture {
+        Box::new(self.order_service().auto_complete_orders().then(|res| {
+            match res {
+                Ok((_, orders)) => info!("Scheduler completed {} orders", orders.into_iter().filter(Option::is_some).count()),
+                Err((_, e)) => log_and_capture_error(&FailureError::from(e.context("Scheduler could not complete orders"))),
+            };
+            future::ok(())
+        }))
+    }
+
+    fn remind_stores_to_confirm_orders(&self) -> JobFuture {
+        Box::new(self.order_service().remind_stores_to_confirm_orders().then(|res| {
+            if let Err((_, e)) = res {
+                log_and_capture_error(&FailureError::from(
+                    e.context("Scheduler could not remind stores to confirm orders"),
+                ));
+            }
+            future::ok(())
+        }))
+    }
+
+    // declined by the instance which reserved the run of the current tick, like completed orders
+    fn auto_decline_orders(&self) -> JobFuture {
+        Box::new(self.order_service().auto_decline_orders().then(|res| {
+            match res {
+                Ok((_, orders)) => info!("Scheduler declined {} orders", orders.into_iter().filter(Option::is_some).count()),
+                Err((_, e)) => log_and_capture_error(&FailureError::from(e.context("Scheduler could not decline orders"))),
+            };
+            future::ok(())
+        }))
+    }
+
+    fn remind_stores_to_ship_pre_orders(&self) -> JobFuture {
+        Box::new(self.order_service().remind_stores_to_ship_pre_orders().then(|res| {
+            if let Err((_, e)) = res {
+                log_and_capture_error(&FailureError::from(
+                    e.context("Scheduler could not remind stores to ship pre-orders"),
+                ));
+            }
+            future::ok(())
+        }))
+    }
+
+    fn auto_cancel_pre_orders(&self) -> JobFuture {
+        Box::new(self.order_service().auto_cancel_pre_orders().then(|res| {
+            match res {
+                Ok((_, orders)) => info!(
+                    "Scheduler cancelled {} pre-orders",
+                    orders.into_iter().filter(Option::is_some).count()
+                ),
+                Err((_, e)) => log_and_capture_error(&FailureError::from(e.context("Scheduler could not cancel pre-orders"))),
+            };
+            future::ok(())
+        }))
+    }
+
+    // every tick tries to reserve the payout run of the current interval, so a missed tick does not skip it
+    fn pay_sellers(&self) -> JobFuture {
+        Box::new(self.order_service().pay_sellers().then(|res| {
+            match res {
+                Ok((_, payouts)) => info!("Scheduler made {} payouts to sellers", payouts.len()),
+                Err((_, e)) => log_and_capture_error(&FailureError::from(e.context("Scheduler could not pay sellers"))),
+            };
+            future::ok(())
+        }))
+    }
+
+    fn reassign_expired_moderations(&self) -> JobFuture {
+        Box::new(self.store_service().reassign_expired_moderations().then(|res| {
+            match res {
+                Ok((_, assignments)) => info!("Scheduler reassigned {} moderations", assignments.len()),
+                Err((_, e)) => log_and_capture_error(&FailureError::from(e.context("Scheduler could not reassign moderations"))),
+            };
+            future::ok(())
+        }))
+    }
+
+    fn remind_overdue_moderations(&self) -> JobFuture {
+        Box::new(self.store_service().remind_overdue_moderations().then(|res| {
+            match res {
+                Ok((_, assignments)) => info!("Scheduler reminded moderators about {} overdue moderations", assignments.len()),
+                Err((_, e)) => log_and_capture_error(&FailureError::from(
+                    e.context("Scheduler could not remind moderators about overdue moderations"),
+                )),
+            };
+            future::ok(())
+        }))
+    }
+
+    fn escalate_overdue_moderations(&self) -> JobFuture {
+        Box::new(self.store_service().escalate_overdue_moderations().then(|res| {
+            match res {
+                Ok((_, assignments)) => info!("Scheduler escalated {} overdue moderations", assignments.len()),
+                Err((_, e)) => log_and_capture_error(&FailureError::from(e.context("Scheduler could not escalate overdue moderations"))),
+            };
+            future::ok(())
+        }))
+    }
+
+    // Gauges are set from assignments kept in stores microservice, so every coordinator instance reports the same values
+    fn report_moderation_wait_times(&self) -> JobFuture {
+        Box::new(self.store_service().get_waiting_moderations().then(|res| {
+            match res {
+                Ok((_, waiting)) => {
+                    let now = Utc::now();
+                    // labels of `ModerationSubject`, gauges are reset to zero when nothing of the kind is waiting
+                    for label in ["store", "base_product"].iter() {
+                        let wait_times = waiting
+                            .iter()
+                            .filter(|assignment| assignment.subject.as_label() == *label)
+                            .map(|assignment| now.signed_duration_since(assignment.enqueued_at).num_seconds())
+                            .collect::<Vec<_>>();
+                        metrics::MODERATION_WAITING
+                            .with_label_values(&[*label])
+                            .set(wait_times.len() as f64);
+                        metrics::MODERATION_OLDEST_WAIT_SECONDS
+                            .with_label_values(&[*label])
+                            .set(wait_times.into_iter().max().unwrap_or(0) as f64);
+                    }
+                }
+                Err((_, e)) => log_and_capture_error(&FailureError::from(e.context("Scheduler could not report moderation wait times"))),
+            };
+            future::ok(())
+        }))
+    }
+
+    fn order_service(&self) -> OrderServiceImpl {
+        let http_client = TimeLimitedHttpClient::new(self.http_client.clone(), Duration::from_millis(self.config.client.http_timeout_ms));
+
+        OrderServiceImpl::new(
+            self.config.clone(),
+            Arc::new(OrdersMicroserviceImpl::new(http_client.clone(), self.config.clone())),
+            Arc::new(StoresMicroserviceImpl::new(http_client.clone(), self.config.clone())),
+            Arc::new(NotificationsMicroserviceImpl::new(http_client.clone(), self.config.clone())),
+            Arc::new(UsersMicroserviceImpl::new(http_client.clone(), self.config.clone())),
+            Arc::new(BillingMicroserviceImpl::new(http_client.clone(), self.config.clone())),
+            Arc::new(WarehousesMicroserviceImpl::new(http_client, self.config.clone())),
+            self.carriers.clone(),
+            None,
+            None,
+        )
+    }
+
+    fn store_service(&self) -> StoreServiceImpl {
+        let http_client = TimeLimitedHttpClient::new(self.http_client.clone(), Duration::from_millis(self.config.client.http_timeout_ms));
+
+        StoreServiceImpl::new(
+            self.config.clone(),
+            Arc::new(OrdersMicroserviceImpl::new(http_client.clone(), self.config.clone())),
+            Arc::new(StoresMicroserviceImpl::new(http_client.clone(), self.config.clone())),
+            Arc::new(NotificationsMicroserviceImpl::new(http_client.clone(), self.config.clone())),
+            Arc::new(BillingMicroserviceImpl::new(http_client.clone(), self.config.clone())),
+            Arc::new(WarehousesMicroserviceImpl::new(http_client.clone(), self.config.clone())),
+            Arc::new(UsersMicroserviceImpl::new(http_client.clone(), self.config.clone())),
+            Arc::new(DeliveryMicroserviceImpl::new(http_client, self.config.clone())),
+        )
+    }
+}
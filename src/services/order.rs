@@ -1,6 +1,7 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::{Arc, Mutex};
 
+use chrono::{DateTime, Duration, Utc};
 use failure::Error as FailureError;
 use failure::Fail;
 use futures::future::{self, join_all, Either};
@@ -9,15 +10,16 @@ use futures::stream::iter_ok;
 
 use stq_api::orders::Order;
 use stq_static_resources::{
-    CommitterRole, EmailUser, OrderCreateForStore, OrderCreateForUser, OrderState, OrderUpdateStateForStore, OrderUpdateStateForUser,
+    CommitterRole, EmailUser, OrderCreateForStore, OrderCreateForUser, OrderState, OrderUpdateStateForStore, Project,
 };
-use stq_types::{ConversionId, CouponId, OrderId, OrderIdentifier, OrderSlug, Quantity, SagaId, StoreId, UserId};
+use stq_types::{ConversionId, CouponId, OrderId, OrderIdentifier, OrderSlug, ProductId, ProductPrice, Quantity, SagaId, StoreId, UserId};
 
 use super::parse_validation_errors;
 use config;
 use errors::Error;
+use metrics;
 use microservice::{
-    BillingMicroservice, Initiator, NotificationsMicroservice, OrdersMicroservice, StoresMicroservice, UsersMicroservice,
+    ApiFuture, BillingMicroservice, Initiator, NotificationsMicroservice, OrdersMicroservice, StoresMicroservice, UsersMicroservice,
     WarehousesMicroservice,
 };
 use models::*;
@@ -25,6 +27,8 @@ use services::types::ServiceFuture;
 
 pub trait OrderService {
     fn create(self, input: ConvertCart) -> ServiceFuture<Box<OrderService>, Invoice>;
+    /// Calculate what converting the cart would cost without creating anything
+    fn quote(self, input: ConvertCart) -> ServiceFuture<Box<OrderService>, Quote>;
     fn create_buy_now(self, input: BuyNow) -> ServiceFuture<Box<OrderService>, Invoice>;
     fn update_state_by_billing(self, orders_info: BillingOrdersVec) -> ServiceFuture<Box<OrderService>, ()>;
     fn manual_set_state(
@@ -35,7 +39,43 @@ pub trait OrderService {
         comment: Option<String>,
         committer_role: CommitterRole,
     ) -> ServiceFuture<Box<OrderService>, Option<Order>>;
+    /// Set payment state of the order, it must follow the current one and match order state
     fn manual_set_payment_state(self, order_id: OrderId, payload: OrderPaymentStateRequest) -> ServiceFuture<Box<OrderService>, ()>;
+    /// Complete orders which stay delivered longer than hold period
+    fn auto_complete_orders(self) -> ServiceFuture<Box<OrderService>, Vec<Option<Order>>>;
+    /// Decline paid orders which were not confirmed by seller in time
+    fn auto_decline_orders(self) -> ServiceFuture<Box<OrderService>, Vec<Option<Order>>>;
+    /// Remind stores about paid orders approaching confirmation deadline
+    fn remind_stores_to_confirm_orders(self) -> ServiceFuture<Box<OrderService>, ()>;
+    /// Resolve order dispute in favour of buyer or seller
+    fn resolve_dispute(
+        self,
+        order_slug: OrderSlug,
+        resolution: DisputeResolution,
+        payload: ResolveDisputePayload,
+    ) -> ServiceFuture<Box<OrderService>, Option<Order>>;
+    /// Get all state changes of the order
+    fn get_order_history(self, order_slug: OrderSlug) -> ServiceFuture<Box<OrderService>, Vec<OrderHistoryEntry>>;
+    /// Set states of several orders, result is reported for every order
+    fn bulk_set_state(self, payload: BulkUpdateStatePayload) -> ServiceFuture<Box<OrderService>, Vec<BulkUpdateStateResult>>;
+    /// Remind stores about pre-orders approaching promised ship date
+    fn remind_stores_to_ship_pre_orders(self) -> ServiceFuture<Box<OrderService>, ()>;
+    /// Cancel pre-orders which were not sent in promised time
+    fn auto_cancel_pre_orders(self) -> ServiceFuture<Box<OrderService>, Vec<Option<Order>>>;
+    /// Set order states user does not want to be emailed about
+    fn set_user_notification_opt_outs(
+        self,
+        user_id: UserId,
+        opt_outs: NotificationOptOuts,
+    ) -> ServiceFuture<Box<OrderService>, NotificationOptOuts>;
+    /// Set order states store does not want to be emailed about
+    fn set_store_notification_opt_outs(
+        self,
+        store_id: StoreId,
+        opt_outs: NotificationOptOuts,
+    ) -> ServiceFuture<Box<OrderService>, NotificationOptOuts>;
+    /// Pay sellers for completed orders, one payout per store and currency
+    fn pay_sellers(self) -> ServiceFuture<Box<OrderService>, Vec<Payout>>;
 }
 
 /// Orders services, responsible for Creating orders
@@ -48,6 +88,9 @@ pub struct OrderServiceImpl {
     pub warehouses_microservice: Arc<WarehousesMicroservice>,
     pub config: config::Config,
     pub log: Arc<Mutex<CreateOrderOperationLog>>,
+    pub carriers: Arc<CarrierRegistry>,
+    pub user_id: Option<UserId>,
+    pub project: Option<Project>,
 }
 
 impl OrderServiceImpl {
@@ -59,11 +102,17 @@ impl OrderServiceImpl {
         users_microservice: Arc<UsersMicroservice>,
         billing_microservice: Arc<BillingMicroservice>,
         warehouses_microservice: Arc<WarehousesMicroservice>,
+        carriers: Arc<CarrierRegistry>,
+        user_id: Option<UserId>,
+        project: Option<Project>,
     ) -> Self {
         let log = Arc::new(Mutex::new(CreateOrderOperationLog::new()));
         Self {
             config,
             log,
+            carriers,
+            user_id,
+            project,
             orders_microservice,
             stores_microservice,
             notifications_microservice,
@@ -73,6 +122,22 @@ impl OrderServiceImpl {
         }
     }
 
+    // Service for running one more saga alongside this one, operation log is not shared
+    fn fork(&self) -> Self {
+        Self::new(
+            self.config.clone(),
+            self.orders_microservice.clone(),
+            self.stores_microservice.clone(),
+            self.notifications_microservice.clone(),
+            self.users_microservice.clone(),
+            self.billing_microservice.clone(),
+            self.warehouses_microservice.clone(),
+            self.carriers.clone(),
+            self.user_id,
+            self.project.clone(),
+        )
+    }
+
     fn convert_cart(self, input: ConvertCart) -> impl Future<Item = (Self, Vec<Order>), Error = (Self, FailureError)> {
         // Create Order
         debug!("Converting cart, input: {:?}", input);
@@ -97,6 +162,17 @@ impl OrderServiceImpl {
             })
     }
 
+    fn quote_cart(self, input: ConvertCart) -> impl Future<Item = (Self, Quote), Error = (Self, FailureError)> {
+        debug!("Quoting cart, input: {:?}", input);
+        let mut payload: ConvertCartPayload = ConvertCartWithConversionId::from(input).into();
+        payload.conversion_id = None;
+
+        self.orders_microservice.preview_convert_cart(payload).then(|res| match res {
+            Ok(orders) => Ok((self, Quote::from_orders(&orders))),
+            Err(e) => Err((self, e)),
+        })
+    }
+
     fn commit_coupon(self, payload: (CouponId, UserId)) -> impl Future<Item = (Self, UsedCoupon), Error = (Self, FailureError)> {
         let (coupon_id, customer) = payload;
 
@@ -179,69 +255,79 @@ impl OrderServiceImpl {
             })
     }
 
+    // Users without first or last name are greeted with placeholders
+    fn get_email_user(
+        users_microservice: Arc<UsersMicroservice>,
+        initiator: Initiator,
+        user_id: UserId,
+    ) -> impl Future<Item = Option<EmailUser>, Error = FailureError> {
+        users_microservice.get(Some(initiator), user_id).map(|user| {
+            user.map(|user| EmailUser {
+                email: user.email.clone(),
+                first_name: user.first_name.unwrap_or_else(|| "user".to_string()),
+                last_name: user.last_name.unwrap_or_else(|| "".to_string()),
+            })
+        })
+    }
+
+    fn email_user_for(&self, user_id: UserId) -> impl Future<Item = EmailUser, Error = FailureError> {
+        Self::get_email_user(self.users_microservice.clone(), user_id.into(), user_id).and_then(move |user| {
+            user.ok_or_else(|| {
+                error!(
+                    "Sending notification to user can not be done. User with id: {} is not found.",
+                    user_id
+                );
+                format_err!("User is not found in users microservice.")
+                    .context(Error::NotFound)
+                    .into()
+            })
+        })
+    }
+
+    // Stores without email are not notified, `None` is returned for them
+    fn store_email_for(&self, store_id: StoreId) -> impl Future<Item = Option<String>, Error = FailureError> {
+        self.stores_microservice.get(store_id, Visibility::Active).and_then(move |store| {
+            store.map(|store| store.email).ok_or_else(|| {
+                error!(
+                    "Sending notification to store can not be done. Store with id: {} is not found.",
+                    store_id
+                );
+                format_err!("Store is not found in stores microservice.")
+                    .context(Error::NotFound)
+                    .into()
+            })
+        })
+    }
+
     fn notify_user_create_order(&self, user_id: UserId, order_slug: OrderSlug) -> impl Future<Item = (), Error = FailureError> {
         let cluster_url = self.config.cluster.url.clone();
         let notifications_microservice = self.notifications_microservice.clone();
-        self.users_microservice
-            .get(Some(user_id.into()), user_id)
-            .and_then(move |user| {
-                user.ok_or_else(|| {
-                    error!(
-                        "Sending notification to user can not be done. User with id: {} is not found.",
-                        user_id
-                    );
-                    format_err!("User is not found in users microservice.")
-                        .context(Error::NotFound)
-                        .into()
-                })
-                .into_future()
-            })
-            .and_then(move |user| {
-                let user = EmailUser {
-                    email: user.email.clone(),
-                    first_name: user.first_name.unwrap_or_else(|| "user".to_string()),
-                    last_name: user.last_name.unwrap_or_else(|| "".to_string()),
-                };
-                let email = OrderCreateForUser {
-                    user,
-                    order_slug: order_slug.to_string(),
-                    cluster_url,
-                };
-                notifications_microservice.order_create_for_user(Initiator::Superadmin, email)
-            })
+        self.email_user_for(user_id).and_then(move |user| {
+            let email = OrderCreateForUser {
+                user,
+                order_slug: order_slug.to_string(),
+                cluster_url,
+            };
+            notifications_microservice.order_create_for_user(Initiator::Superadmin, email)
+        })
     }
 
     fn notify_store_create_order(&self, store_id: StoreId, order_slug: OrderSlug) -> impl Future<Item = (), Error = FailureError> {
         let cluster_url = self.config.cluster.url.clone();
         let notifications_microservice = self.notifications_microservice.clone();
-        self.stores_microservice
-            .get(store_id, Visibility::Active)
-            .and_then(move |store| {
-                store
-                    .ok_or_else(|| {
-                        error!(
-                            "Sending notification to store can not be done. Store with id: {} is not found.",
-                            store_id
-                        );
-                        format_err!("Store is not found in stores microservice.")
-                            .context(Error::NotFound)
-                            .into()
-                    })
-                    .into_future()
-            })
-            .and_then(move |store| {
-                if let Some(store_email) = store.email {
-                    let email = OrderCreateForStore {
-                        store_email,
-                        store_id: store_id.to_string(),
-                        order_slug: order_slug.to_string(),
-                        cluster_url,
-                    };
-                    Either::A(notifications_microservice.order_create_for_store(Initiator::Superadmin, email))
-                } else {
-                    Either::B(future::ok(()))
-                }
-            })
+        self.store_email_for(store_id).and_then(move |store_email| {
+            if let Some(store_email) = store_email {
+                let email = OrderCreateForStore {
+                    store_email,
+                    store_id: store_id.to_string(),
+                    order_slug: order_slug.to_string(),
+                    cluster_url,
+                };
+                Either::A(notifications_microservice.order_create_for_store(Initiator::Superadmin, email))
+            } else {
+                Either::B(future::ok(()))
+            }
+        })
     }
 
     fn notify_user_update_order(
@@ -249,37 +335,135 @@ impl OrderServiceImpl {
         user_id: UserId,
         order_slug: OrderSlug,
         order_state: OrderState,
+        tracking_url: Option<String>,
     ) -> impl Future<Item = (), Error = FailureError> {
         let cluster_url = self.config.cluster.url.clone();
         let notifications_microservice = self.notifications_microservice.clone();
-        self.users_microservice
-            .get(Some(user_id.into()), user_id)
-            .and_then(move |user| {
-                user.ok_or_else(|| {
-                    error!(
-                        "Sending notification to user can not be done. User with id: {} is not found.",
-                        user_id
-                    );
-                    format_err!("User is not found in users microservice.")
-                        .context(Error::NotFound)
-                        .into()
-                })
-                .into_future()
+        self.email_user_for(user_id).and_then(move |user| {
+            let email = OrderUpdateStateWithTrackingForUser {
+                user,
+                order_slug: order_slug.to_string(),
+                order_state: order_state.to_string(),
+                cluster_url,
+                tracking_url,
+            };
+            notifications_microservice.order_update_state_for_user(Initiator::Superadmin, email)
+        })
+    }
+
+    fn notify_user_update_orders(&self, user_id: UserId, orders: Vec<Order>) -> impl Future<Item = (), Error = FailureError> {
+        let cluster_url = self.config.cluster.url.clone();
+        let orders = orders
+            .into_iter()
+            .map(|order| OrderStateForUser {
+                order_slug: order.slug.to_string(),
+                order_state: order.state.to_string(),
+                tracking_url: self.carriers.order_tracking_url(&order),
             })
-            .and_then(move |user| {
-                let user = EmailUser {
-                    email: user.email.clone(),
-                    first_name: user.first_name.unwrap_or_else(|| "user".to_string()),
-                    last_name: user.last_name.unwrap_or_else(|| "".to_string()),
-                };
-                let email = OrderUpdateStateForUser {
-                    user,
+            .collect::<Vec<_>>();
+        let notifications_microservice = self.notifications_microservice.clone();
+        self.email_user_for(user_id).and_then(move |user| {
+            let email = OrdersUpdateStateForUser { user, orders, cluster_url };
+            notifications_microservice.orders_update_state_for_user(Initiator::Superadmin, email)
+        })
+    }
+
+    fn notify_user_payment_state(
+        &self,
+        user_id: UserId,
+        order_slug: OrderSlug,
+        payment_state: PaymentState,
+    ) -> impl Future<Item = (), Error = FailureError> {
+        let cluster_url = self.config.cluster.url.clone();
+        let notifications_microservice = self.notifications_microservice.clone();
+        self.email_user_for(user_id).and_then(move |user| {
+            let email = OrderPaymentStateForUser {
+                user,
+                order_slug: order_slug.to_string(),
+                payment_state: format!("{:?}", payment_state),
+                cluster_url,
+            };
+            notifications_microservice.order_payment_state_for_user(Initiator::Superadmin, email)
+        })
+    }
+
+    fn notify_store_payment_state(
+        &self,
+        store_id: StoreId,
+        order_slug: OrderSlug,
+        payment_state: PaymentState,
+    ) -> impl Future<Item = (), Error = FailureError> {
+        let cluster_url = self.config.cluster.url.clone();
+        let notifications_microservice = self.notifications_microservice.clone();
+        self.store_email_for(store_id).and_then(move |store_email| {
+            if let Some(store_email) = store_email {
+                let email = OrderPaymentStateForStore {
+                    store_email,
+                    store_id: store_id.to_string(),
                     order_slug: order_slug.to_string(),
-                    order_state: order_state.to_string(),
+                    payment_state: format!("{:?}", payment_state),
                     cluster_url,
                 };
-                notifications_microservice.order_update_state_for_user(Initiator::Superadmin, email)
+                Either::A(notifications_microservice.order_payment_state_for_store(Initiator::Superadmin, email))
+            } else {
+                Either::B(future::ok(()))
+            }
+        })
+    }
+
+    fn notify_store_payout(&self, store_id: StoreId, group: &PayoutGroup, payout: &Payout) -> impl Future<Item = (), Error = FailureError> {
+        let cluster_url = self.config.cluster.url.clone();
+        let notifications_microservice = self.notifications_microservice.clone();
+        let currency = payout.currency;
+        let amount = payout.amount.0;
+        let orders = group
+            .orders
+            .iter()
+            .map(|order| PayoutStatementOrder {
+                order_id: order.id.to_string(),
+                amount: order.total_amount.0,
             })
+            .collect::<Vec<_>>();
+        self.store_email_for(store_id).and_then(move |store_email| {
+            if let Some(store_email) = store_email {
+                let email = PayoutStatementForStore {
+                    store_email,
+                    store_id: store_id.to_string(),
+                    currency,
+                    amount,
+                    orders,
+                    cluster_url,
+                };
+                Either::A(notifications_microservice.payout_statement_for_store(Initiator::Superadmin, email))
+            } else {
+                Either::B(future::ok(()))
+            }
+        })
+    }
+
+    fn notify_user_invoice_requoted(
+        &self,
+        user_id: UserId,
+        orders: &[Order],
+        invoice: &Invoice,
+    ) -> impl Future<Item = (), Error = FailureError> {
+        let cluster_url = self.config.cluster.url.clone();
+        let notifications_microservice = self.notifications_microservice.clone();
+        let order_slugs = orders.iter().map(|order| order.slug.to_string()).collect::<Vec<_>>();
+        let amount = invoice.amount.0;
+        let currency = invoice.currency;
+        let wallet = invoice.wallet.clone();
+        self.email_user_for(user_id).and_then(move |user| {
+            let email = InvoiceRequotedForUser {
+                user,
+                order_slugs,
+                amount,
+                currency,
+                wallet,
+                cluster_url,
+            };
+            notifications_microservice.invoice_requoted_for_user(Initiator::Superadmin, email)
+        })
     }
 
     fn notify_store_update_order(
@@ -290,213 +474,1353 @@ impl OrderServiceImpl {
     ) -> impl Future<Item = (), Error = FailureError> {
         let cluster_url = self.config.cluster.url.clone();
         let notifications_microservice = self.notifications_microservice.clone();
+        self.store_email_for(store_id).and_then(move |store_email| {
+            if let Some(store_email) = store_email {
+                let email = OrderUpdateStateForStore {
+                    store_email,
+                    store_id: store_id.to_string(),
+                    order_slug: order_slug.to_string(),
+                    order_state: order_state.to_string(),
+                    cluster_url,
+                };
+                Either::A(notifications_microservice.order_update_state_for_store(Initiator::Superadmin, email))
+            } else {
+                Either::B(future::ok(()))
+            }
+        })
+    }
+
+    fn notify_store_confirmation_reminder(
+        &self,
+        store_id: StoreId,
+        order_slug: OrderSlug,
+        deadline: DateTime<Utc>,
+    ) -> impl Future<Item = (), Error = FailureError> {
+        let cluster_url = self.config.cluster.url.clone();
+        let notifications_microservice = self.notifications_microservice.clone();
+        self.store_email_for(store_id).and_then(move |store_email| {
+            if let Some(store_email) = store_email {
+                let email = OrderConfirmationReminderForStore {
+                    store_email,
+                    store_id: store_id.to_string(),
+                    order_slug: order_slug.to_string(),
+                    deadline,
+                    cluster_url,
+                };
+                Either::A(notifications_microservice.order_confirmation_reminder_for_store(Initiator::Superadmin, email))
+            } else {
+                Either::B(future::ok(()))
+            }
+        })
+    }
+
+    fn notify_moderators_update_order(self, order: &Order) -> impl Future<Item = (Self, ()), Error = (Self, FailureError)> {
+        let notifications_microservice = self.notifications_microservice.clone();
+        let users_microservice = self.users_microservice.clone();
+        let cluster_url = self.config.cluster.url.clone();
+        let store_id = order.store;
+        let order_slug = order.slug;
+        let order_state = order.state;
+
         self.stores_microservice
-            .get(store_id, Visibility::Active)
-            .and_then(move |store| {
-                store
-                    .ok_or_else(|| {
-                        error!(
-                            "Sending notification to store can not be done. Store with id: {} is not found.",
-                            store_id
-                        );
-                        format_err!("Store is not found in stores microservice.")
-                            .context(Error::NotFound)
-                            .into()
+            .get_moderators(Initiator::Superadmin)
+            .and_then(move |moderators| {
+                iter_ok::<_, FailureError>(moderators).for_each(move |moderator_id| {
+                    let notifications_microservice = notifications_microservice.clone();
+                    let cluster_url = cluster_url.clone();
+
+                    Self::get_email_user(users_microservice.clone(), Initiator::Superadmin, moderator_id).and_then(move |moderator| {
+                        if let Some(user) = moderator {
+                            let send = if order_state == OrderState::Dispute {
+                                let email = OrderDisputeForModerator {
+                                    user,
+                                    store_id: store_id.to_string(),
+                                    order_slug: order_slug.to_string(),
+                                    cluster_url,
+                                };
+                                notifications_microservice.order_dispute_for_moderator(Initiator::Superadmin, email)
+                            } else {
+                                let email = OrderUpdateStateForModerator {
+                                    user,
+                                    store_id: store_id.to_string(),
+                                    order_slug: order_slug.to_string(),
+                                    order_state: order_state.to_string(),
+                                    cluster_url,
+                                };
+                                notifications_microservice.order_update_state_for_moderator(Initiator::Superadmin, email)
+                            };
+                            Either::A(send.then(|_| Ok(())))
+                        } else {
+                            Either::B(future::ok(()))
+                        }
                     })
-                    .into_future()
+                })
             })
-            .and_then(move |store| {
-                if let Some(store_email) = store.email {
-                    let email = OrderUpdateStateForStore {
-                        store_email,
-                        store_id: store.id.to_string(),
-                        order_slug: order_slug.to_string(),
-                        order_state: order_state.to_string(),
-                        cluster_url,
-                    };
-                    Either::A(notifications_microservice.order_update_state_for_store(Initiator::Superadmin, email))
-                } else {
-                    Either::B(future::ok(()))
-                }
+            .then(|res| match res {
+                Ok(_) => Ok((self, ())),
+                Err(e) => Err((self, e)),
+            })
+    }
+
+    fn notify_store_pre_order_ship_reminder(
+        &self,
+        store_id: StoreId,
+        order_slug: OrderSlug,
+        ship_deadline: DateTime<Utc>,
+    ) -> impl Future<Item = (), Error = FailureError> {
+        let cluster_url = self.config.cluster.url.clone();
+        let notifications_microservice = self.notifications_microservice.clone();
+        self.store_email_for(store_id).and_then(move |store_email| {
+            if let Some(store_email) = store_email {
+                let email = PreOrderShipReminderForStore {
+                    store_email,
+                    store_id: store_id.to_string(),
+                    order_slug: order_slug.to_string(),
+                    ship_deadline,
+                    cluster_url,
+                };
+                Either::A(notifications_microservice.pre_order_ship_reminder_for_store(Initiator::Superadmin, email))
+            } else {
+                Either::B(future::ok(()))
+            }
+        })
+    }
+
+    fn notify_user_pre_order_expected_date(
+        &self,
+        user_id: UserId,
+        order_slug: OrderSlug,
+        expected_date: DateTime<Utc>,
+    ) -> impl Future<Item = (), Error = FailureError> {
+        let cluster_url = self.config.cluster.url.clone();
+        let notifications_microservice = self.notifications_microservice.clone();
+        self.email_user_for(user_id).and_then(move |user| {
+            let email = PreOrderExpectedDateForUser {
+                user,
+                order_slug: order_slug.to_string(),
+                expected_date,
+                cluster_url,
+            };
+            notifications_microservice.pre_order_expected_date_for_user(Initiator::Superadmin, email)
+        })
+    }
+
+    // Project comes from the request, scheduler jobs have no request and use marketplace rules
+    fn notification_rules(&self) -> &config::OrderNotificationRules {
+        let project = self.project.clone().unwrap_or_else(|| Project::MarketPlace);
+        self.config.order_notifications.for_project(&project)
+    }
+
+    // Fetches opt-outs of customers and stores that may opt out of current states of the orders.
+    // Opt-outs that could not be fetched are treated as empty, so notification is rather sent than lost
+    fn get_opt_outs(self, orders: &[Order]) -> impl Future<Item = (Self, RecipientsOptOuts), Error = (Self, FailureError)> {
+        let (customers, stores) = {
+            let rules = self.notification_rules();
+            let customers = orders
+                .iter()
+                .filter(|order| rules.customer.opt_out_allowed.contains(&order.state))
+                .map(|order| order.customer)
+                .collect::<HashSet<UserId>>();
+            let stores = orders
+                .iter()
+                .filter(|order| rules.store.opt_out_allowed.contains(&order.state))
+                .map(|order| order.store)
+                .collect::<HashSet<StoreId>>();
+            (customers, stores)
+        };
+
+        let users_opt_outs = customers
+            .into_iter()
+            .map(|user_id| {
+                self.users_microservice
+                    .get_notification_opt_outs(Some(Initiator::Superadmin), user_id)
+                    .then(move |res| {
+                        let opt_outs = res.unwrap_or_else(|e| {
+                            error!("Getting notification opt-outs of user {} failed: {}", user_id, e);
+                            NotificationOptOuts::default()
+                        });
+                        Ok::<_, FailureError>((user_id, opt_outs.order_states))
+                    })
+            })
+            .collect::<Vec<_>>();
+        let stores_opt_outs = stores
+            .into_iter()
+            .map(|store_id| {
+                self.stores_microservice
+                    .get_notification_opt_outs(Some(Initiator::Superadmin), store_id)
+                    .then(move |res| {
+                        let opt_outs = res.unwrap_or_else(|e| {
+                            error!("Getting notification opt-outs of store {} failed: {}", store_id, e);
+                            NotificationOptOuts::default()
+                        });
+                        Ok::<_, FailureError>((store_id, opt_outs.order_states))
+                    })
             })
+            .collect::<Vec<_>>();
+
+        join_all(users_opt_outs).join(join_all(stores_opt_outs)).then(|res| match res {
+            Ok((users, stores)) => Ok((
+                self,
+                RecipientsOptOuts {
+                    users: users.into_iter().collect(),
+                    stores: stores.into_iter().collect(),
+                },
+            )),
+            Err(e) => Err((self, e)),
+        })
     }
 
     fn notify(self, orders: &[Option<Order>]) -> impl Future<Item = (Self, ()), Error = (Self, FailureError)> {
+        self.notify_recipients(orders, true)
+    }
+
+    // Customers are left out when they get one aggregated notification for several orders instead
+    fn notify_recipients(
+        self,
+        orders: &[Option<Order>],
+        notify_customers: bool,
+    ) -> impl Future<Item = (Self, ()), Error = (Self, FailureError)> {
+        let orders = orders.iter().filter_map(|order| order.clone()).collect::<Vec<Order>>();
+        self.get_opt_outs(&orders)
+            .and_then(move |(s, opt_outs)| s.notify_by_rules(&orders, &opt_outs, notify_customers))
+    }
+
+    fn notify_by_rules(
+        self,
+        orders: &[Order],
+        opt_outs: &RecipientsOptOuts,
+        notify_customers: bool,
+    ) -> impl Future<Item = (Self, ()), Error = (Self, FailureError)> {
         let mut orders_futures = vec![];
         for order in orders {
-            if let Some(order) = order {
-                let send_to_client = match order.state {
-                    OrderState::New | OrderState::PaymentAwaited | OrderState::TransactionPending | OrderState::AmountExpired => {
-                        Box::new(future::ok(())) as Box<Future<Item = (), Error = FailureError>>
+            let rules = self.notification_rules();
+            let send_to_client = if !notify_customers || !rules.customer.should_notify(order.state, opt_outs.user(order.customer)) {
+                Box::new(future::ok(())) as Box<Future<Item = (), Error = FailureError>>
+            } else if order.state == OrderState::Paid {
+                Box::new(self.notify_user_create_order(order.customer, order.slug)) as Box<Future<Item = (), Error = FailureError>>
+            } else {
+                Box::new(self.notify_user_update_order(order.customer, order.slug, order.state, self.carriers.order_tracking_url(order)))
+                    as Box<Future<Item = (), Error = FailureError>>
+            };
+            let send_to_store = if !rules.store.should_notify(order.state, opt_outs.store(order.store)) {
+                Box::new(future::ok(())) as Box<Future<Item = (), Error = FailureError>>
+            } else if order.state == OrderState::Paid {
+                Box::new(self.notify_store_create_order(order.store, order.slug)) as Box<Future<Item = (), Error = FailureError>>
+            } else {
+                Box::new(self.notify_store_update_order(order.store, order.slug, order.state))
+                    as Box<Future<Item = (), Error = FailureError>>
+            };
+
+            let res = send_to_client.then(|_| send_to_store).then(|_| Ok(()));
+            orders_futures.push(res);
+        }
+
+        join_all(orders_futures)
+            .map_err(|e: FailureError| e.context("Notifying on update orders error.".to_string()).into())
+            .then(|res| match res {
+                Ok(_) => Ok((self, ())),
+                Err(e) => Err((self, e)),
+            })
+    }
+
+    // Contains happy path for Order creation
+    fn create_happy(self, input: ConvertCart) -> impl Future<Item = (Self, Invoice), Error = (Self, FailureError)> {
+        self.convert_cart(input.clone()).and_then(move |(s, orders)| {
+            let create_invoice = CreateInvoice {
+                customer_id: input.customer_id,
+                orders: orders.clone(),
+                currency: input.currency,
+                saga_id: SagaId::new(),
+            };
+            s.create_invoice(&create_invoice).and_then(move |(s, invoice)| {
+                s.commit_coupons(orders.clone()).and_then(move |(s, _)| {
+                    s.notify(&orders.into_iter().map(Some).collect::<Vec<Option<Order>>>())
+                        .then(|res| match res {
+                            Ok((s, _)) => Ok((s, invoice)),
+                            Err((s, _)) => Ok((s, invoice)),
+                        })
+                })
+            })
+        })
+    }
+
+    fn create_from_buy_now(self, input: BuyNow) -> impl Future<Item = (Self, Invoice), Error = (Self, FailureError)> {
+        self.buy_now(input.clone()).and_then(move |(s, orders)| {
+            let create_invoice = CreateInvoice {
+                customer_id: input.customer_id,
+                orders: orders.clone(),
+                currency: input.currency,
+                saga_id: SagaId::new(),
+            };
+            s.create_invoice(&create_invoice).and_then(move |(s, invoice)| {
+                s.notify(&orders.into_iter().map(Some).collect::<Vec<Option<Order>>>())
+                    .then(|res| match res {
+                        Ok((s, _)) => Ok((s, invoice)),
+                        Err((s, _)) => Ok((s, invoice)),
+                    })
+            })
+        })
+    }
+
+    // Contains happy path for Order creation
+    fn update_orders_happy(self, orders_info: BillingOrdersVec) -> impl Future<Item = (Self, ()), Error = (Self, FailureError)> {
+        self.update_orders(orders_info)
+            .and_then(move |(s, orders)| {
+                s.update_warehouse(&orders).then(|res| match res {
+                    Ok((s, _)) => Ok((s, orders)),
+                    Err((s, _)) => Ok((s, orders)),
+                })
+            })
+            .and_then(move |(s, orders)| {
+                s.notify(&orders).then(|res| match res {
+                    Ok((s, _)) => Ok((s, orders)),
+                    Err((s, _)) => Ok((s, orders)),
+                })
+            })
+            .and_then(move |(s, orders)| {
+                s.notify_pre_orders_paid(&orders).then(|res| match res {
+                    Ok((s, _)) => Ok((s, orders)),
+                    Err((s, _)) => Ok((s, orders)),
+                })
+            })
+            .and_then(move |(s, orders)| {
+                s.requote_expired_orders(&orders).then(|res| match res {
+                    Ok((s, _)) => Ok((s, ())),
+                    Err((s, e)) => {
+                        error!("Re-quoting expired orders failed: {}", e);
+                        Ok((s, ()))
                     }
-                    OrderState::Paid => {
-                        Box::new(self.notify_user_create_order(order.customer, order.slug)) as Box<Future<Item = (), Error = FailureError>>
+                })
+            })
+    }
+
+    // Re-creates invoices which amount expired, invoices are handled one by one and failures are logged
+    fn requote_expired_orders(self, orders: &[Option<Order>]) -> impl Future<Item = (Self, ()), Error = (Self, FailureError)> {
+        let billing_microservice = self.billing_microservice.clone();
+        let service = self.fork();
+
+        let invoices = orders
+            .iter()
+            .filter_map(|order| order.clone())
+            .filter(|order| order.state == OrderState::AmountExpired)
+            .map(move |order| {
+                billing_microservice
+                    .get_invoice_by_order_id(Initiator::Superadmin, order.id)
+                    .map(move |invoice| (invoice, order))
+            })
+            .collect::<Vec<_>>();
+
+        join_all(invoices)
+            .map(|invoices| {
+                // all orders of the invoice expire together
+                let mut stale_invoices: Vec<(Invoice, Vec<Order>)> = vec![];
+                for (invoice, order) in invoices {
+                    let invoice = match invoice {
+                        Some(invoice) => invoice,
+                        None => {
+                            warn!("Invoice of expired order {} is not found in billing microservice", order.slug);
+                            continue;
+                        }
+                    };
+                    match stale_invoices.iter().position(|(stale_invoice, _)| stale_invoice.id == invoice.id) {
+                        Some(position) => stale_invoices[position].1.push(order),
+                        None => stale_invoices.push((invoice, vec![order])),
                     }
-                    OrderState::InProcessing
-                    | OrderState::Cancelled
-                    | OrderState::Sent
-                    | OrderState::Delivered
-                    | OrderState::Received
-                    | OrderState::Dispute
-                    | OrderState::Complete => Box::new(self.notify_user_update_order(order.customer, order.slug, order.state))
-                        as Box<Future<Item = (), Error = FailureError>>,
+                }
+                stale_invoices
+            })
+            .and_then(move |stale_invoices| {
+                iter_ok::<_, FailureError>(stale_invoices).for_each(move |(stale_invoice, orders)| {
+                    let saga_id = stale_invoice.id;
+                    service.fork().requote_invoice(stale_invoice, orders).then(move |res| {
+                        if let Err((_, e)) = res {
+                            error!("Re-quoting expired invoice {} failed: {}", saga_id, e);
+                        }
+                        Ok(())
+                    })
+                })
+            })
+            .then(|res| match res {
+                Ok(_) => Ok((self, ())),
+                Err(e) => Err((self, e)),
+            })
+    }
+
+    // Orders get fresh prices and new invoice is created under new saga id, then the stale invoice is reverted.
+    // If the stale invoice can not be reverted the new one is reverted, so buyer never has two invoices to pay.
+    // Orders get their previous prices back when the stale invoice stays in force
+    fn requote_invoice(
+        self,
+        stale_invoice: Invoice,
+        orders: Vec<Order>,
+    ) -> impl Future<Item = (Self, Invoice), Error = (Self, FailureError)> {
+        let customer_id = orders[0].customer;
+        let currency = stale_invoice.currency;
+        let stale_saga_id = stale_invoice.id;
+        let previous_prices = orders.iter().map(|order| (order.id, order.price)).collect::<Vec<_>>();
+
+        self.reprice_orders(orders)
+            .and_then(move |(s, orders)| {
+                let create_invoice = CreateInvoice {
+                    orders: orders.clone(),
+                    customer_id,
+                    currency,
+                    saga_id: SagaId::new(),
                 };
-                let send_to_store = match order.state {
-                    OrderState::New | OrderState::PaymentAwaited | OrderState::TransactionPending | OrderState::AmountExpired => {
-                        Box::new(future::ok(())) as Box<Future<Item = (), Error = FailureError>>
-                    }
-                    OrderState::Paid => {
-                        Box::new(self.notify_store_create_order(order.store, order.slug)) as Box<Future<Item = (), Error = FailureError>>
+                s.create_invoice(&create_invoice).map(move |(s, invoice)| (s, invoice, orders))
+            })
+            .and_then(move |(s, invoice, orders)| {
+                s.revert_stale_invoice(stale_saga_id, invoice.id)
+                    .map(move |(s, _)| (s, invoice, orders))
+            })
+            .or_else(move |(s, e)| {
+                s.restore_order_prices(previous_prices).then(move |res| match res {
+                    Ok((s, _)) | Err((s, _)) => Err((s, e)),
+                })
+            })
+            .and_then(move |(s, invoice, orders)| {
+                s.notify_user_invoice_requoted(customer_id, &orders, &invoice).then(move |res| {
+                    if let Err(e) = res {
+                        error!("Notifying user {} on re-created invoice {} failed: {}", customer_id, invoice.id, e);
                     }
-                    OrderState::InProcessing
-                    | OrderState::Cancelled
-                    | OrderState::Sent
-                    | OrderState::Delivered
-                    | OrderState::Received
-                    | OrderState::Dispute
-                    | OrderState::Complete => Box::new(self.notify_store_update_order(order.store, order.slug, order.state))
-                        as Box<Future<Item = (), Error = FailureError>>,
-                };
+                    Ok((s, invoice))
+                })
+            })
+    }
 
-                let res = send_to_client.then(|_| send_to_store).then(|_| Ok(()));
-                orders_futures.push(res);
+    fn reprice_orders(self, orders: Vec<Order>) -> impl Future<Item = (Self, Vec<Order>), Error = (Self, FailureError)> {
+        let repriced_orders = orders
+            .into_iter()
+            .map(|order| {
+                let orders_microservice = self.orders_microservice.clone();
+                let product_id = order.product;
+                self.stores_microservice
+                    .get_product(product_id, Visibility::Active)
+                    .and_then(move |product| {
+                        product.ok_or_else(|| {
+                            format_err!("Product is not found in stores microservice! id: {}", product_id)
+                                .context(Error::NotFound)
+                                .into()
+                        })
+                    })
+                    .and_then(move |product| {
+                        orders_microservice.update_order_price(
+                            Some(Initiator::Superadmin),
+                            order.id,
+                            UpdateOrderPrice { price: product.price },
+                        )
+                    })
+            })
+            .collect::<Vec<_>>();
+
+        join_all(repriced_orders).then(|res| match res {
+            Ok(orders) => Ok((self, orders)),
+            Err(e) => Err((self, e)),
+        })
+    }
+
+    // Every order gets its price back even if some of them fail, failures are logged
+    fn restore_order_prices(
+        self,
+        previous_prices: Vec<(OrderId, ProductPrice)>,
+    ) -> impl Future<Item = (Self, ()), Error = (Self, FailureError)> {
+        let restored_prices = previous_prices
+            .into_iter()
+            .map(|(order_id, price)| {
+                self.orders_microservice
+                    .update_order_price(Some(Initiator::Superadmin), order_id, UpdateOrderPrice { price })
+                    .then(move |res| {
+                        if let Err(e) = res {
+                            error!("Restoring price of order {} failed: {}", order_id, e);
+                        }
+                        Ok::<_, FailureError>(())
+                    })
+            })
+            .collect::<Vec<_>>();
+
+        join_all(restored_prices).then(|res| match res {
+            Ok(_) => Ok((self, ())),
+            Err(e) => Err((self, e)),
+        })
+    }
+
+    fn revert_stale_invoice(
+        self,
+        stale_saga_id: SagaId,
+        new_saga_id: SagaId,
+    ) -> impl Future<Item = (Self, ()), Error = (Self, FailureError)> {
+        let billing_microservice = self.billing_microservice.clone();
+        self.billing_microservice
+            .revert_create_invoice(Initiator::Superadmin, stale_saga_id)
+            .or_else(move |e| {
+                billing_microservice
+                    .revert_create_invoice(Initiator::Superadmin, new_saga_id)
+                    .then(move |res| {
+                        if let Err(revert_error) = res {
+                            error!("Reverting re-created invoice {} failed: {}", new_saga_id, revert_error);
+                        }
+                        Err(e)
+                    })
+            })
+            .then(|res| match res {
+                Ok(_) => Ok((self, ())),
+                Err(e) => Err((self, e)),
+            })
+    }
+
+    // Contains happy path for Order set state
+    fn set_state_happy(
+        self,
+        order_slug: OrderSlug,
+        order_state: OrderState,
+        track_id: Option<String>,
+        comment: Option<String>,
+        committer_role: Option<CommitterRole>,
+        source: StateChangeSource,
+        notify_customer: bool,
+    ) -> impl Future<Item = (Self, Option<Order>), Error = (Self, FailureError)> {
+        self.set_state(order_slug, order_state, track_id, comment, committer_role, source)
+            .and_then(move |(s, order)| {
+                s.notify_recipients(&[order.clone()], notify_customer).then(|res| match res {
+                    Ok((s, _)) => Ok((s, order)),
+                    Err((s, _)) => Ok((s, order)),
+                })
+            })
+            .and_then(move |(s, order)| s.notify_moderators(order))
+    }
+
+    fn notify_moderators(self, order: Option<Order>) -> impl Future<Item = (Self, Option<Order>), Error = (Self, FailureError)> {
+        match order.clone() {
+            Some(ref updated_order) if self.notification_rules().moderators.contains(&updated_order.state) => {
+                Either::A(self.notify_moderators_update_order(updated_order).then(|res| match res {
+                    Ok((s, _)) => Ok((s, order)),
+                    Err((s, _)) => Ok((s, order)),
+                }))
             }
+            _ => Either::B(future::ok((self, order))),
         }
+    }
+
+    // Sets states with bounded concurrency, customers get one notification for all their orders
+    fn bulk_set_state_happy(
+        self,
+        payload: BulkUpdateStatePayload,
+    ) -> impl Future<Item = (Self, Vec<BulkUpdateStateResult>), Error = (Self, FailureError)> {
+        let concurrency = self.config.service.bulk_concurrency;
+        let committer_role = payload.committer_role;
+        let service = self.fork();
+
+        iter_ok::<_, FailureError>(payload.items)
+            .map(move |item| {
+                let order_slug = item.order_slug;
+                service
+                    .fork()
+                    .set_state_happy(
+                        order_slug,
+                        item.state,
+                        item.track_id,
+                        item.comment,
+                        Some(committer_role.clone()),
+                        StateChangeSource::Manual,
+                        false,
+                    )
+                    .then(move |res| {
+                        let result = match res {
+                            Ok((_, order)) => BulkUpdateStateResult {
+                                order_slug,
+                                order,
+                                error: None,
+                            },
+                            Err((_, e)) => {
+                                error!("Bulk setting state for order {} failed: {}", order_slug, e);
+                                BulkUpdateStateResult {
+                                    order_slug,
+                                    order: None,
+                                    error: Some(e.to_string()),
+                                }
+                            }
+                        };
+                        Ok(result)
+                    })
+            })
+            .buffered(concurrency)
+            .collect()
+            .then(|res| match res {
+                Ok(results) => Ok((self, results)),
+                Err(e) => Err((self, e)),
+            })
+            .and_then(|(s, results)| {
+                let orders = results.iter().filter_map(|result| result.order.clone()).collect::<Vec<Order>>();
+                s.get_opt_outs(&orders).map(|(s, opt_outs)| (s, results, orders, opt_outs))
+            })
+            .and_then(|(s, results, orders, opt_outs)| {
+                let mut orders_by_customer = HashMap::<UserId, Vec<Order>>::new();
+                for order in orders {
+                    if s.notification_rules()
+                        .customer
+                        .should_notify(order.state, opt_outs.user(order.customer))
+                    {
+                        orders_by_customer.entry(order.customer).or_insert_with(Vec::new).push(order);
+                    }
+                }
+
+                let notifications = orders_by_customer
+                    .into_iter()
+                    .map(|(customer, orders)| {
+                        s.notify_user_update_orders(customer, orders).then(move |res| {
+                            if let Err(e) = res {
+                                error!("Notifying user {} on bulk orders update failed: {}", customer, e);
+                            }
+                            Ok(())
+                        })
+                    })
+                    .collect::<Vec<_>>();
+
+                join_all(notifications).then(|res: Result<Vec<()>, FailureError>| match res {
+                    Ok(_) => Ok((s, results)),
+                    Err(e) => Err((s, e)),
+                })
+            })
+    }
+
+    // Resolves dispute by moving order to `Cancelled` (refund) or `Complete` (payment to seller)
+    fn resolve_dispute_happy(
+        self,
+        order_slug: OrderSlug,
+        resolution: DisputeResolution,
+        payload: ResolveDisputePayload,
+    ) -> impl Future<Item = (Self, Option<Order>), Error = (Self, FailureError)> {
+        let orders_microservice = self.orders_microservice.clone();
+        let user_id = self.user_id;
+
+        // only moderators resolve disputes, whatever the request says about its committer
+        self.stores_microservice
+            .get_moderators(Initiator::Superadmin)
+            .and_then(move |moderators| match user_id {
+                Some(user_id) if moderators.contains(&user_id) => Ok(()),
+                Some(user_id) => Err(format_err!("User {} is not a moderator and can not resolve disputes", user_id)
+                    .context(Error::Forbidden)
+                    .into()),
+                None => Err(format_err!("Dispute can not be resolved by anonymous user")
+                    .context(Error::Unauthorized)
+                    .into()),
+            })
+            .and_then(move |_| orders_microservice.get_order(None, OrderIdentifier::Slug(order_slug)))
+            .and_then(move |order| match order {
+                Some(ref order) if order.state == OrderState::Dispute => Ok(()),
+                Some(_) => Err(Error::Validate(validation_errors!({"state": ["state" => "Order is not in dispute"]})).into()),
+                None => Err(format_err!("Order is not found in orders microservice! slug: {}", order_slug)
+                    .context(Error::NotFound)
+                    .into()),
+            })
+            .then(|res| match res {
+                Ok(_) => Ok(self),
+                Err(e) => Err((self, e)),
+            })
+            .and_then(move |s| {
+                // orders microservice knows buyer and seller roles only, moderator acts for the side
+                // that could make the change itself, history records it as made by moderator
+                let (order_state, committer_role) = match resolution {
+                    DisputeResolution::Buyer => (OrderState::Cancelled, CommitterRole::Seller),
+                    DisputeResolution::Seller => (OrderState::Complete, CommitterRole::Customer),
+                };
+                s.set_state_happy(
+                    order_slug,
+                    order_state,
+                    None,
+                    payload.comment,
+                    Some(committer_role),
+                    StateChangeSource::DisputeResolution,
+                    true,
+                )
+            })
+    }
+
+    // Sets state for each order one by one, failure of one order does not stop the others
+    fn set_states_happy(
+        self,
+        orders: Vec<Order>,
+        order_state: OrderState,
+        comment: String,
+    ) -> impl Future<Item = (Self, Vec<Option<Order>>), Error = (Self, FailureError)> {
+        iter_ok::<_, (Self, FailureError)>(orders).fold((self, vec![]), move |(s, mut updated_orders), order| {
+            let order_slug = order.slug;
+            s.set_state_happy(
+                order_slug,
+                order_state,
+                None,
+                Some(comment.clone()),
+                None,
+                StateChangeSource::Scheduler,
+                true,
+            )
+            .then(move |res| {
+                let s = match res {
+                    Ok((s, order)) => {
+                        updated_orders.push(order);
+                        s
+                    }
+                    Err((s, e)) => {
+                        error!("Setting state {} for order {} failed: {}", order_state, order_slug, e);
+                        s
+                    }
+                };
+                Ok((s, updated_orders)) as Result<(Self, Vec<Option<Order>>), (Self, FailureError)>
+            })
+        })
+    }
+
+    // Searches orders in each of the states, other search terms are the same for every state
+    fn search_orders_by_states(
+        self,
+        states: Vec<OrderState>,
+        terms: OrderSearchTerms,
+    ) -> impl Future<Item = (Self, Vec<Order>), Error = (Self, FailureError)> {
+        let searches = states
+            .into_iter()
+            .map(|state| {
+                self.orders_microservice.search_orders(
+                    Some(Initiator::Superadmin),
+                    OrderSearchTerms {
+                        state: Some(state),
+                        ..terms.clone()
+                    },
+                )
+            })
+            .collect::<Vec<_>>();
+
+        join_all(searches).then(|res| match res {
+            Ok(orders) => Ok((self, orders.into_iter().flatten().collect())),
+            Err(e) => Err((self, e)),
+        })
+    }
+
+    // Job runs once per tick by the instance which reserved the run first, the others skip it
+    fn reserve_scheduler_run(self, job: SchedulerJob) -> impl Future<Item = (Self, bool), Error = (Self, FailureError)> {
+        let run = NewSchedulerRun::containing(job, Utc::now(), self.config.scheduler.tick_interval_s as i64);
+        let period_start = run.period_start;
+
+        self.orders_microservice
+            .reserve_scheduler_run(Some(Initiator::Superadmin), run)
+            .then(move |res| match res {
+                Ok(reserved) => {
+                    if !reserved {
+                        debug!("{:?} run of period starting {} is already reserved", job, period_start);
+                    }
+                    Ok((self, reserved))
+                }
+                Err(e) => Err((self, e)),
+            })
+    }
+
+    fn auto_complete_orders_happy(self) -> impl Future<Item = (Self, Vec<Option<Order>>), Error = (Self, FailureError)> {
+        let updated_to = Utc::now() - Duration::hours(self.config.scheduler.delivered_hold_period_h);
+        let terms = OrderSearchTerms {
+            updated_to: Some(updated_to),
+            ..Default::default()
+        };
+        self.reserve_scheduler_run(SchedulerJob::AutoCompleteOrders)
+            .and_then(move |(s, reserved)| {
+                if reserved {
+                    Either::A(s.search_orders_by_states(vec![OrderState::Delivered, OrderState::Received], terms))
+                } else {
+                    Either::B(future::ok((s, vec![])))
+                }
+            })
+            .and_then(|(s, orders)| {
+                debug!("Completing {} orders after hold period", orders.len());
+                s.set_states_happy(
+                    orders,
+                    OrderState::Complete,
+                    "Order completed automatically after hold period.".to_string(),
+                )
+            })
+    }
+
+    // Declines paid orders the seller did not confirm in time, set_state does the refund and restock.
+    // Pre-orders are cancelled by `auto_cancel_pre_orders` after their ship deadline instead
+    fn auto_decline_orders_happy(self) -> impl Future<Item = (Self, Vec<Option<Order>>), Error = (Self, FailureError)> {
+        let updated_to = Utc::now() - Duration::hours(self.config.scheduler.confirmation_sla_h);
+        let terms = OrderSearchTerms::awaiting_confirmation(updated_to);
+        self.reserve_scheduler_run(SchedulerJob::AutoDeclineOrders)
+            .and_then(move |(s, reserved)| {
+                if reserved {
+                    Either::A(s.search_orders_by_states(vec![OrderState::Paid], terms))
+                } else {
+                    Either::B(future::ok((s, vec![])))
+                }
+            })
+            .and_then(|(s, orders)| {
+                debug!("Declining {} orders not confirmed by seller", orders.len());
+                s.set_states_happy(
+                    orders,
+                    OrderState::Cancelled,
+                    "Order declined automatically, it was not confirmed by seller in time.".to_string(),
+                )
+            })
+    }
+
+    // Reminder is recorded in orders microservice before it is sent and dropped if sending fails,
+    // so store is reminded once after the order got into its state, whichever tick picks it up
+    fn remind_store_once<R, F>(
+        &self,
+        order: &Order,
+        kind: OrderReminderKind,
+        since: DateTime<Utc>,
+        remind: R,
+    ) -> impl Future<Item = (), Error = ()>
+    where
+        R: FnOnce() -> F + 'static,
+        F: Future<Item = (), Error = FailureError> + 'static,
+    {
+        let orders_microservice = self.orders_microservice.clone();
+        let order_id = order.id;
+        let order_slug = order.slug;
+        let payload = NewOrderReminder { kind, since };
+
+        self.orders_microservice
+            .reserve_order_reminder(Some(Initiator::Superadmin), order_id, payload)
+            .and_then(move |previous| match previous {
+                Some(_) => Either::A(future::ok(())),
+                None => Either::B(remind().or_else(move |e| {
+                    orders_microservice
+                        .release_order_reminder(Some(Initiator::Superadmin), order_id, kind)
+                        .then(move |res| {
+                            if let Err(release_error) = res {
+                                error!("Releasing {} reminder of order {} failed: {}", kind, order_slug, release_error);
+                            }
+                            Err::<(), _>(e)
+                        })
+                })),
+            })
+            .then(move |res| {
+                if let Err(e) = res {
+                    error!("Sending {} reminder for order {} failed: {}", kind, order_slug, e);
+                }
+                Ok::<_, ()>(())
+            })
+    }
+
+    // Orders past SLA are declined instead of being reminded about
+    fn remind_stores_to_confirm_orders_happy(self) -> impl Future<Item = (Self, ()), Error = (Self, FailureError)> {
+        let now = Utc::now();
+        let sla = Duration::hours(self.config.scheduler.confirmation_sla_h);
+        let remind_after = sla - Duration::hours(self.config.scheduler.confirmation_reminder_before_h);
+
+        let terms = OrderSearchTerms::awaiting_confirmation(now - remind_after);
+        self.search_orders_by_states(vec![OrderState::Paid], terms)
+            .and_then(move |(s, orders)| {
+                let reminders = orders
+                    .into_iter()
+                    .filter(|order| order.updated_at + sla > now)
+                    .map(|order| {
+                        let service = s.fork();
+                        let store_id = order.store;
+                        let order_slug = order.slug;
+                        let deadline = order.updated_at + sla;
+                        s.remind_store_once(&order, OrderReminderKind::ConfirmOrder, order.updated_at, move || {
+                            service.notify_store_confirmation_reminder(store_id, order_slug, deadline)
+                        })
+                    })
+                    .collect::<Vec<_>>();
+                debug!("Reminding stores to confirm {} orders", reminders.len());
+
+                join_all(reminders).then(|_: Result<Vec<()>, ()>| Ok((s, ())))
+            })
+    }
+
+    fn search_unsent_pre_orders(self) -> impl Future<Item = (Self, Vec<Order>), Error = (Self, FailureError)> {
+        let terms = OrderSearchTerms {
+            pre_order: Some(true),
+            ..Default::default()
+        };
+        self.search_orders_by_states(vec![OrderState::Paid, OrderState::InProcessing], terms)
+    }
+
+    // Overdue pre-orders are cancelled instead of being reminded about, the deadline is counted from order creation
+    fn remind_stores_to_ship_pre_orders_happy(self) -> impl Future<Item = (Self, ()), Error = (Self, FailureError)> {
+        let now = Utc::now();
+        let remind_before = Duration::hours(self.config.scheduler.pre_order_reminder_before_h);
+
+        self.search_unsent_pre_orders().and_then(move |(s, orders)| {
+            let reminders = orders
+                .into_iter()
+                .filter_map(|order| {
+                    let deadline = pre_order_ship_deadline(&order)?;
+                    if deadline - remind_before <= now && now < deadline {
+                        Some((order, deadline))
+                    } else {
+                        None
+                    }
+                })
+                .map(|(order, deadline)| {
+                    let service = s.fork();
+                    let store_id = order.store;
+                    let order_slug = order.slug;
+                    s.remind_store_once(&order, OrderReminderKind::ShipPreOrder, order.created_at, move || {
+                        service.notify_store_pre_order_ship_reminder(store_id, order_slug, deadline)
+                    })
+                })
+                .collect::<Vec<_>>();
+            debug!("Reminding stores to ship {} pre-orders", reminders.len());
+
+            join_all(reminders).then(|_: Result<Vec<()>, ()>| Ok((s, ())))
+        })
+    }
+
+    // Cancels pre-orders which were not sent in promised time, set_state does the refund
+    fn auto_cancel_pre_orders_happy(self) -> impl Future<Item = (Self, Vec<Option<Order>>), Error = (Self, FailureError)> {
+        let now = Utc::now();
+        self.search_unsent_pre_orders().and_then(move |(s, orders)| {
+            let overdue_orders = orders
+                .into_iter()
+                .filter(|order| pre_order_ship_deadline(order).map(|deadline| deadline <= now).unwrap_or(false))
+                .collect::<Vec<_>>();
+            debug!("Cancelling {} pre-orders not sent in time", overdue_orders.len());
+            s.set_states_happy(
+                overdue_orders,
+                OrderState::Cancelled,
+                "Pre-order cancelled automatically, it was not sent in promised time.".to_string(),
+            )
+        })
+    }
+
+    fn notify_pre_orders_paid(self, orders: &[Option<Order>]) -> impl Future<Item = (Self, ()), Error = (Self, FailureError)> {
+        let notifications = orders
+            .iter()
+            .filter_map(|order| order.as_ref())
+            .filter(|order| order.state == OrderState::Paid)
+            .filter_map(|order| pre_order_ship_deadline(order).map(|deadline| (order, deadline)))
+            .map(|(order, deadline)| {
+                let order_slug = order.slug;
+                self.notify_user_pre_order_expected_date(order.customer, order_slug, deadline)
+                    .then(move |res| {
+                        if let Err(e) = res {
+                            error!("Notifying user on pre-order {} expected date failed: {}", order_slug, e);
+                        }
+                        Ok(())
+                    })
+            })
+            .collect::<Vec<_>>();
+
+        join_all(notifications).then(|res: Result<Vec<()>, FailureError>| match res {
+            Ok(_) => Ok((self, ())),
+            Err(e) => Err((self, e)),
+        })
+    }
+
+    // Sellers are paid once per payout interval by the instance which reserved the run first.
+    // Orders are claimed as `PaymentToSellerInProgress` and paid out, orders of a failed payout are returned
+    // to `PaymentToSellerNeeded` for the next run. Orders set to `PaidToSeller` by hand are never claimed
+    fn pay_sellers_happy(self) -> impl Future<Item = (Self, Vec<Payout>), Error = (Self, FailureError)> {
+        let run = NewPayoutRun::containing(Utc::now(), self.config.scheduler.payout_interval_h * 3600);
+        let period_start = run.period_start;
+
+        self.billing_microservice
+            .reserve_payout_run(Some(Initiator::Superadmin), run)
+            .then(|res| match res {
+                Ok(reserved) => Ok((self, reserved)),
+                Err(e) => Err((self, e)),
+            })
+            .and_then(move |(s, reserved)| {
+                if reserved {
+                    Either::A(
+                        s.settle_paid_out_orders()
+                            .and_then(|(s, _)| s.claim_orders_for_payout())
+                            .and_then(|(s, _)| s.pay_claimed_orders()),
+                    )
+                } else {
+                    debug!("Payout run of period starting {} is already reserved", period_start);
+                    Either::B(future::ok((s, vec![])))
+                }
+            })
+    }
+
+    // Orders already included in a payout, e.g. one that timed out but was created by billing,
+    // are marked as paid instead of being claimed for another payout
+    fn settle_paid_out_orders(self) -> impl Future<Item = (Self, ()), Error = (Self, FailureError)> {
+        let billing_microservice = self.billing_microservice.clone();
+        let searches = [PaymentState::PaymentToSellerNeeded, PaymentState::PaymentToSellerInProgress]
+            .iter()
+            .map(|payment_state| {
+                self.billing_microservice.search_orders(
+                    Some(Initiator::Superadmin),
+                    BillingOrderSearchTerms {
+                        payment_state: *payment_state,
+                        paid_out: Some(true),
+                    },
+                )
+            })
+            .collect::<Vec<_>>();
+
+        join_all(searches)
+            .and_then(move |orders| {
+                let order_ids = orders.into_iter().flatten().map(|order| order.id).collect();
+                Self::set_payment_states(billing_microservice, order_ids, PaymentState::PaidToSeller)
+            })
+            .then(|res| match res {
+                Ok(_) => Ok((self, ())),
+                Err(e) => Err((self, e)),
+            })
+    }
+
+    // Orders that could not be claimed are left to the next run, so are orders in dispute
+    fn claim_orders_for_payout(self) -> impl Future<Item = (Self, ()), Error = (Self, FailureError)> {
+        let terms = BillingOrderSearchTerms {
+            payment_state: PaymentState::PaymentToSellerNeeded,
+            paid_out: Some(false),
+        };
+        let disputed_terms = OrderSearchTerms {
+            state: Some(OrderState::Dispute),
+            ..Default::default()
+        };
+        let billing_microservice = self.billing_microservice.clone();
+        let disputed = self.orders_microservice.search_orders(Some(Initiator::Superadmin), disputed_terms);
 
-        join_all(orders_futures)
-            .map_err(|e: FailureError| e.context("Notifying on update orders error.".to_string()).into())
+        self.billing_microservice
+            .search_orders(Some(Initiator::Superadmin), terms)
+            .join(disputed)
+            .and_then(move |(orders, disputed)| {
+                let disputed = disputed.into_iter().map(|order| order.id).collect::<Vec<_>>();
+                let order_ids = claimable_orders(orders, &disputed).into_iter().map(|order| order.id).collect();
+                Self::set_payment_states(billing_microservice, order_ids, PaymentState::PaymentToSellerInProgress)
+            })
             .then(|res| match res {
                 Ok(_) => Ok((self, ())),
                 Err(e) => Err((self, e)),
             })
     }
 
-    // Contains happy path for Order creation
-    fn create_happy(self, input: ConvertCart) -> impl Future<Item = (Self, Invoice), Error = (Self, FailureError)> {
-        self.convert_cart(input.clone()).and_then(move |(s, orders)| {
-            let create_invoice = CreateInvoice {
-                customer_id: input.customer_id,
-                orders: orders.clone(),
-                currency: input.currency,
-                saga_id: SagaId::new(),
-            };
-            s.create_invoice(&create_invoice).and_then(move |(s, invoice)| {
-                s.commit_coupons(orders.clone()).and_then(move |(s, _)| {
-                    s.notify(&orders.into_iter().map(Some).collect::<Vec<Option<Order>>>())
-                        .then(|res| match res {
-                            Ok((s, _)) => Ok((s, invoice)),
-                            Err((s, _)) => Ok((s, invoice)),
+    // One payout per store and currency, failed payout of one store does not stop the others.
+    // Claimed orders left by an interrupted run are paid out as well
+    fn pay_claimed_orders(self) -> impl Future<Item = (Self, Vec<Payout>), Error = (Self, FailureError)> {
+        let terms = BillingOrderSearchTerms {
+            payment_state: PaymentState::PaymentToSellerInProgress,
+            paid_out: Some(false),
+        };
+        let service = self.fork();
+
+        self.billing_microservice
+            .search_orders(Some(Initiator::Superadmin), terms)
+            .and_then(move |orders| {
+                let groups = PayoutGroup::from_orders(orders);
+                debug!("Paying sellers, {} payouts to make", groups.len());
+                iter_ok::<_, FailureError>(groups)
+                    .and_then(move |group| {
+                        let store_id = group.store_id;
+                        let order_ids = group.order_ids();
+                        let billing_microservice = service.billing_microservice.clone();
+                        service.fork().pay_seller(group).then(move |res| {
+                            let payment_state = settled_payment_state(res.is_ok());
+                            let payout = match res {
+                                Ok((_, payout)) => Some(payout),
+                                Err((_, e)) => {
+                                    error!(
+                                        "Paying seller of store {} failed, returning its orders to the next run: {}",
+                                        store_id, e
+                                    );
+                                    None
+                                }
+                            };
+                            Self::set_payment_states(billing_microservice, order_ids, payment_state).map(move |_| payout)
                         })
-                })
+                    })
+                    .filter_map(|payout| payout)
+                    .collect()
+            })
+            .then(|res| match res {
+                Ok(payouts) => Ok((self, payouts)),
+                Err(e) => Err((self, e)),
             })
-        })
     }
 
-    fn create_from_buy_now(self, input: BuyNow) -> impl Future<Item = (Self, Invoice), Error = (Self, FailureError)> {
-        self.buy_now(input.clone()).and_then(move |(s, orders)| {
-            let create_invoice = CreateInvoice {
-                customer_id: input.customer_id,
-                orders: orders.clone(),
-                currency: input.currency,
-                saga_id: SagaId::new(),
-            };
-            s.create_invoice(&create_invoice).and_then(move |(s, invoice)| {
-                s.notify(&orders.into_iter().map(Some).collect::<Vec<Option<Order>>>())
-                    .then(|res| match res {
-                        Ok((s, _)) => Ok((s, invoice)),
-                        Err((s, _)) => Ok((s, invoice)),
+    // Failure of one order is logged and does not stop the others
+    fn set_payment_states(
+        billing_microservice: Arc<BillingMicroservice>,
+        order_ids: Vec<OrderId>,
+        payment_state: PaymentState,
+    ) -> impl Future<Item = (), Error = FailureError> {
+        let updates = order_ids
+            .into_iter()
+            .map(|order_id| {
+                let payload = OrderPaymentStateRequest { state: payment_state };
+                billing_microservice
+                    .set_payment_state(Some(Initiator::Superadmin), order_id, payload)
+                    .then(move |res| {
+                        if let Err(e) = res {
+                            error!("Setting payment state {:?} for order {} failed: {}", payment_state, order_id, e);
+                        }
+                        Ok::<_, FailureError>(())
                     })
             })
-        })
+            .collect::<Vec<_>>();
+
+        join_all(updates).map(|_| ())
     }
 
-    // Contains happy path for Order creation
-    fn update_orders_happy(self, orders_info: BillingOrdersVec) -> impl Future<Item = (Self, ()), Error = (Self, FailureError)> {
-        self.update_orders(orders_info)
-            .and_then(move |(s, orders)| {
-                s.update_warehouse(&orders).then(|res| match res {
-                    Ok((s, _)) => Ok((s, orders)),
-                    Err((s, _)) => Ok((s, orders)),
-                })
+    // Billing creates payout with the same id only once, so payout with unknown outcome is safely requested again
+    fn pay_seller(self, group: PayoutGroup) -> impl Future<Item = (Self, Payout), Error = (Self, FailureError)> {
+        let store_id = group.store_id;
+
+        self.billing_microservice
+            .get_store_merchant(Some(Initiator::Superadmin), store_id)
+            .then(|res| match res {
+                Ok(merchant) => Ok((self, merchant)),
+                Err(e) => Err((self, e)),
             })
-            .and_then(move |(s, orders)| {
-                s.notify(&orders).then(|res| match res {
-                    Ok((s, _)) => Ok((s, ())),
-                    Err((s, _)) => Ok((s, ())),
+            .and_then(move |(s, merchant)| {
+                let payload = CreatePayout {
+                    id: group.payout_id(),
+                    merchant_id: merchant.merchant_id,
+                    currency: group.currency,
+                    order_ids: group.order_ids(),
+                    amount: group.total_amount(),
+                };
+                s.create_payout(payload).map(move |(s, payout)| (s, group, payout))
+            })
+            .and_then(move |(s, group, payout)| {
+                s.notify_store_payout(store_id, &group, &payout).then(move |res| {
+                    if let Err(e) = res {
+                        error!("Sending payout statement to store {} failed: {}", store_id, e);
+                    }
+                    Ok((s, payout))
                 })
             })
     }
 
-    // Contains happy path for Order set state
-    fn set_state_happy(
+    fn create_payout(self, payload: CreatePayout) -> impl Future<Item = (Self, Payout), Error = (Self, FailureError)> {
+        self.billing_microservice
+            .create_payout(Some(Initiator::Superadmin), payload)
+            .then(|res| match res {
+                Ok(payout) => Ok((self, payout)),
+                Err(e) => Err((self, e)),
+            })
+    }
+
+    fn set_user_notification_opt_outs_happy(
         self,
-        order_slug: OrderSlug,
-        order_state: OrderState,
-        track_id: Option<String>,
-        comment: Option<String>,
-        committer_role: CommitterRole,
-    ) -> impl Future<Item = (Self, Option<Order>), Error = (Self, FailureError)> {
-        self.set_state(order_slug, order_state, track_id, comment, committer_role)
-            .and_then(move |(s, order)| {
-                s.notify(&[order.clone()]).then(|res| match res {
-                    Ok((s, _)) => Ok((s, order)),
-                    Err((s, _)) => Ok((s, order)),
-                })
+        user_id: UserId,
+        opt_outs: NotificationOptOuts,
+    ) -> impl Future<Item = (Self, NotificationOptOuts), Error = (Self, FailureError)> {
+        let users_microservice = self.users_microservice.clone();
+        let initiator = self.user_id.map(Initiator::from);
+        let validation = validate_opt_outs(&self.notification_rules().customer, &opt_outs);
+        validation
+            .into_future()
+            .and_then(move |_| users_microservice.set_notification_opt_outs(initiator, user_id, opt_outs))
+            .then(|res| match res {
+                Ok(opt_outs) => Ok((self, opt_outs)),
+                Err(e) => Err((self, e)),
+            })
+    }
+
+    fn set_store_notification_opt_outs_happy(
+        self,
+        store_id: StoreId,
+        opt_outs: NotificationOptOuts,
+    ) -> impl Future<Item = (Self, NotificationOptOuts), Error = (Self, FailureError)> {
+        let stores_microservice = self.stores_microservice.clone();
+        let initiator = self.user_id.map(Initiator::from);
+        let validation = validate_opt_outs(&self.notification_rules().store, &opt_outs);
+        validation
+            .into_future()
+            .and_then(move |_| stores_microservice.set_notification_opt_outs(initiator, store_id, opt_outs))
+            .then(|res| match res {
+                Ok(opt_outs) => Ok((self, opt_outs)),
+                Err(e) => Err((self, e)),
             })
     }
 
+    // Sets payment state only if it follows the current one and matches order state,
+    // customer and store are notified when money is refunded or paid to seller
     fn set_payment_state_happy(
         self,
         order_id: OrderId,
         payload: OrderPaymentStateRequest,
     ) -> impl Future<Item = (Self, ()), Error = (Self, FailureError)> {
-        self.set_payment_state(order_id, payload)
+        let payment_state = payload.state;
+        let order = self
+            .orders_microservice
+            .get_order(None, OrderIdentifier::Id(order_id))
+            .and_then(move |order| {
+                order.ok_or_else(|| {
+                    format_err!("Order is not found in orders microservice! id: {}", order_id)
+                        .context(Error::NotFound)
+                        .into()
+                })
+            });
+        let billing_order = self.billing_microservice.get_order(None, order_id).and_then(move |billing_order| {
+            billing_order.ok_or_else(|| {
+                format_err!("Order is not found in billing microservice! id: {}", order_id)
+                    .context(Error::NotFound)
+                    .into()
+            })
+        });
+
+        order
+            .join(billing_order)
+            .and_then(move |(order, billing_order)| validate_payment_state(&order, billing_order.state, payment_state).map(|_| order))
+            .then(|res| match res {
+                Ok(order) => Ok((self, order)),
+                Err(e) => Err((self, e)),
+            })
+            .and_then(move |(s, order)| s.set_payment_state(order_id, payload).map(|(s, _)| (s, order)))
+            .and_then(move |(s, order)| {
+                s.notify_payment_state(&order, payment_state).then(|res| match res {
+                    Ok((s, _)) => Ok((s, ())),
+                    Err((s, _)) => Ok((s, ())),
+                })
+            })
+    }
+
+    fn notify_payment_state(
+        self,
+        order: &Order,
+        payment_state: PaymentState,
+    ) -> impl Future<Item = (Self, ()), Error = (Self, FailureError)> {
+        match payment_state {
+            PaymentState::Refunded | PaymentState::PaidToSeller => {
+                let order_slug = order.slug;
+                let send_to_client = self.notify_user_payment_state(order.customer, order.slug, payment_state);
+                let send_to_store = self.notify_store_payment_state(order.store, order.slug, payment_state);
+                Either::A(
+                    send_to_client
+                        .then(move |res| {
+                            if let Err(e) = res {
+                                error!("Notifying customer on payment state of order {} failed: {}", order_slug, e);
+                            }
+                            send_to_store
+                        })
+                        .then(move |res| {
+                            if let Err(e) = res {
+                                error!("Notifying store on payment state of order {} failed: {}", order_slug, e);
+                            }
+                            Ok((self, ()))
+                        }),
+                )
+            }
+            _ => Either::B(future::ok((self, ()))),
+        }
     }
 
     fn update_orders(self, orders_info: BillingOrdersVec) -> impl Future<Item = (Self, Vec<Option<Order>>), Error = (Self, FailureError)> {
         debug!("Updating orders status: {}", orders_info);
 
+        let (orders_info, skipped) = latest_billing_events(orders_info.0);
+        for (order_info, event_status) in skipped {
+            log_skipped_billing_event(&order_info, event_status);
+        }
+
         let mut orders_futures = vec![];
-        for order_info in orders_info.0 {
+        for order_info in orders_info {
             match &order_info.status {
                 OrderState::TransactionPending => continue, // do not set these invoice statuses to orders
                 _ => {}
             }
 
+            let order_id = order_info.order_id;
+            let sequence_number = order_info.sequence_number;
+
+            // sequence number is reserved before the event is applied, so its copies handled concurrently are skipped
+            let reservation = match sequence_number {
+                Some(sequence_number) => {
+                    self.orders_microservice
+                        .reserve_billing_event(Some(Initiator::Superadmin), order_id, sequence_number)
+                }
+                None => Box::new(future::ok(BillingEventStatus::New)) as ApiFuture<BillingEventStatus>,
+            };
+
             let orders_microservice = self.orders_microservice.clone();
+            let release_orders_microservice = self.orders_microservice.clone();
 
-            let order_id = order_info.order_id;
+            let res = reservation.and_then(move |event_status| {
+                if event_status != BillingEventStatus::New {
+                    log_skipped_billing_event(&order_info, event_status);
+                    return Either::A(future::ok(None));
+                }
 
-            let res = self
-                .orders_microservice
-                .get_order(Some(order_info.customer_id.into()), OrderIdentifier::Id(order_info.order_id))
-                .and_then(move |order| {
-                    order
-                        .ok_or(
-                            format_err!("Order is not found in orders microservice! id: {}", order_id)
-                                .context(Error::NotFound)
-                                .into(),
-                        )
-                        .into_future()
-                })
-                .and_then(move |order| {
-                    let states_from_paid = vec![
-                        OrderState::New,
-                        OrderState::PaymentAwaited,
-                        OrderState::TransactionPending,
-                        OrderState::AmountExpired,
-                    ];
-
-                    if order.state == order_info.status {
-                        // if this status already set, do not update
-                        Either::A(future::ok(None))
-                    } else if order_info.status == OrderState::Paid && !states_from_paid.contains(&order.state) {
-                        Either::A(future::ok(None))
-                    } else {
-                        let payload: UpdateStatePayload = order_info.clone().into();
-                        Either::B(orders_microservice.set_order_state(Some(Initiator::Superadmin), OrderIdentifier::Id(order.id), payload))
-                    }
-                });
+                Either::B(
+                    orders_microservice
+                        .get_order(Some(order_info.customer_id.into()), OrderIdentifier::Id(order_info.order_id))
+                        .and_then(move |order| {
+                            order
+                                .ok_or(
+                                    format_err!("Order is not found in orders microservice! id: {}", order_id)
+                                        .context(Error::NotFound)
+                                        .into(),
+                                )
+                                .into_future()
+                        })
+                        .and_then(move |order| {
+                            let states_from_paid = vec![
+                                OrderState::New,
+                                OrderState::PaymentAwaited,
+                                OrderState::TransactionPending,
+                                OrderState::AmountExpired,
+                            ];
+
+                            if order.state == order_info.status {
+                                // if this status already set, do not update
+                                Either::A(future::ok(None))
+                            } else if order_info.status == OrderState::Paid && !states_from_paid.contains(&order.state) {
+                                Either::A(future::ok(None))
+                            } else if is_billing_state_regression(order.state, order_info.status) {
+                                warn!(
+                                    "Billing event for order {} tries to move it from {} back to {}, skipping it",
+                                    order_id, order.state, order_info.status
+                                );
+                                metrics::BILLING_EVENTS_OUT_OF_ORDER
+                                    .with_label_values(&[order.state.to_string().as_str(), order_info.status.to_string().as_str()])
+                                    .inc();
+                                Either::A(future::ok(None))
+                            } else {
+                                let payload: SetOrderStatePayload = order_info.clone().into();
+                                let history_entry = NewOrderHistoryEntry {
+                                    order_id: order.id,
+                                    order_slug: order.slug,
+                                    previous_state: order.state,
+                                    state: payload.state,
+                                    committer: None,
+                                    committer_role: HistoryCommitterRole::System,
+                                    comment: payload.comment.clone(),
+                                    track_id: payload.track_id.clone(),
+                                    source: StateChangeSource::BillingCallback,
+                                    billing_side_effect: None,
+                                };
+                                Either::B(
+                                    orders_microservice
+                                        .set_order_state(Some(Initiator::Superadmin), OrderIdentifier::Id(order.id), payload)
+                                        .and_then(move |updated_order| {
+                                            orders_microservice
+                                                .add_order_history_entry(Some(Initiator::Superadmin), history_entry)
+                                                .then(move |res| {
+                                                    if let Err(e) = res {
+                                                        error!("Recording state change of order {} to history failed: {}", order_id, e);
+                                                    }
+                                                    Ok(updated_order)
+                                                })
+                                        }),
+                                )
+                            }
+                        })
+                        .or_else(move |e| match sequence_number {
+                            Some(sequence_number) => Either::A(
+                                release_orders_microservice
+                                    .release_billing_event(Some(Initiator::Superadmin), order_id, sequence_number)
+                                    .then(move |res| {
+                                        if let Err(release_error) = res {
+                                            error!(
+                                                "Releasing billing event {} of order {} failed: {}",
+                                                sequence_number, order_id, release_error
+                                            );
+                                        }
+                                        Err::<Option<Order>, _>(e)
+                                    }),
+                            ),
+                            None => Either::B(future::err(e)),
+                        }),
+                )
+            });
             orders_futures.push(res);
         }
 
@@ -512,12 +1836,23 @@ impl OrderServiceImpl {
         new_order_state: OrderState,
         track_id: Option<String>,
         comment: Option<String>,
-        committer_role: CommitterRole,
+        committer_role: Option<CommitterRole>,
+        source: StateChangeSource,
     ) -> impl Future<Item = (Self, Option<Order>), Error = (Self, FailureError)> {
+        // without committer role saga coordinator changes the state on its own, not on behalf of the caller
+        let initiator = match committer_role {
+            Some(_) => None,
+            None => Some(Initiator::Superadmin),
+        };
         let orders_microservice = self.orders_microservice.clone();
+        let history_orders_microservice = self.orders_microservice.clone();
         let billing_microservice = self.billing_microservice.clone();
+        let warehouses_microservice = self.warehouses_microservice.clone();
+        let committer = self.user_id;
+        let carriers = self.carriers.clone();
+        let validated_track_id = track_id.clone();
         self.orders_microservice
-            .get_order(None, OrderIdentifier::Slug(order_slug))
+            .get_order(initiator, OrderIdentifier::Slug(order_slug))
             .and_then(move |order| {
                 order
                     .ok_or(
@@ -527,9 +1862,25 @@ impl OrderServiceImpl {
                     )
                     .into_future()
             })
+            .and_then(move |order| {
+                if new_order_state == OrderState::Sent {
+                    if let (Some(carrier), Some(track_id)) = (carriers.for_order(&order), validated_track_id) {
+                        if !carrier.is_valid_tracking_number(&track_id) {
+                            return Err(Error::Validate(
+                                validation_errors!({"track_id": ["track_id" => format!("Invalid {} tracking number", carrier.name)]}),
+                            )
+                            .into());
+                        }
+                    }
+                }
+                Ok(order)
+            })
             .and_then(move |order| {
                 let old_order_state = order.state;
                 let order_id = order.id;
+                let product_id = order.product;
+                let quantity = order.quantity;
+                let pre_order = order.pre_order;
                 if old_order_state == new_order_state {
                     // if this status already set, do not update
                     info!(
@@ -542,36 +1893,69 @@ impl OrderServiceImpl {
                         "order slug: {:?} status: {:?} start request update on orders",
                         order_slug, new_order_state
                     );
+                    let billing_side_effect = billing_side_effect(old_order_state, new_order_state, pre_order);
+                    let billing_call: Box<Future<Item = (), Error = FailureError>> = match billing_side_effect {
+                        Some(BillingSideEffect::DeclineOrder) => billing_microservice.decline_order(Initiator::Superadmin, order_id),
+                        Some(BillingSideEffect::CaptureOrder) => billing_microservice.capture_order(Initiator::Superadmin, order_id),
+                        Some(BillingSideEffect::SetPaymentState(state)) => billing_microservice.set_payment_state(
+                            Some(Initiator::Superadmin),
+                            order_id,
+                            OrderPaymentStateRequest { state },
+                        ),
+                        None => Box::new(future::ok(())),
+                    };
+                    let history_entry = NewOrderHistoryEntry {
+                        order_id,
+                        order_slug,
+                        previous_state: old_order_state,
+                        state: new_order_state,
+                        committer,
+                        committer_role: source.committer_role(committer_role.clone()),
+                        comment: comment.clone(),
+                        track_id: track_id.clone(),
+                        source,
+                        billing_side_effect,
+                    };
                     Either::B(
-                        {
-                            if new_order_state == OrderState::Cancelled && old_order_state == OrderState::Paid {
-                                // order canceled by seller - we need to do refund on billing
-                                Either::A(billing_microservice.decline_order(Initiator::Superadmin, order_id))
-                            } else if new_order_state == OrderState::InProcessing && old_order_state == OrderState::Paid {
-                                // order confirmed by seller - we need to do capture on billing
-                                Either::A(billing_microservice.capture_order(Initiator::Superadmin, order_id))
-                            } else if new_order_state == OrderState::Complete {
-                                // order completed by seller or buyer - we need to send money to seller on billing
-                                let payload = OrderPaymentStateRequest {
-                                    state: PaymentState::PaymentToSellerNeeded,
-                                };
-                                Either::A(billing_microservice.set_payment_state(Some(Initiator::Superadmin), order_id, payload))
-                            } else {
-                                Either::B(future::ok(()))
-                            }
-                        }
-                        .and_then(move |_| {
-                            orders_microservice.set_order_state(
-                                None,
-                                OrderIdentifier::Slug(order_slug),
-                                UpdateStatePayload {
-                                    state: new_order_state,
-                                    comment,
-                                    track_id,
-                                    committer_role,
-                                },
-                            )
-                        }),
+                        billing_call
+                            .and_then(move |_| {
+                                orders_microservice.set_order_state(
+                                    initiator,
+                                    OrderIdentifier::Slug(order_slug),
+                                    SetOrderStatePayload {
+                                        state: new_order_state,
+                                        comment,
+                                        track_id,
+                                        committer_role,
+                                    },
+                                )
+                            })
+                            .and_then(move |updated_order| {
+                                let stock_taken = old_order_state == OrderState::Paid || old_order_state == OrderState::InProcessing;
+                                if new_order_state == OrderState::Cancelled && stock_taken && !pre_order {
+                                    // stock was taken when order was paid - we need to return it to warehouse
+                                    Either::A(
+                                        Self::restock_product(warehouses_microservice, product_id, quantity).then(move |res| {
+                                            if let Err(e) = res {
+                                                error!("Restocking product {} for order {} failed: {}", product_id, order_slug, e);
+                                            }
+                                            Ok(updated_order)
+                                        }),
+                                    )
+                                } else {
+                                    Either::B(future::ok(updated_order))
+                                }
+                            })
+                            .and_then(move |updated_order| {
+                                history_orders_microservice
+                                    .add_order_history_entry(Some(Initiator::Superadmin), history_entry)
+                                    .then(move |res| {
+                                        if let Err(e) = res {
+                                            error!("Recording state change of order {} to history failed: {}", order_slug, e);
+                                        }
+                                        Ok(updated_order)
+                                    })
+                            }),
                     )
                 }
             })
@@ -601,7 +1985,8 @@ impl OrderServiceImpl {
         for order in orders {
             let warehouses_microservice = self.warehouses_microservice.clone();
             if let Some(order) = order {
-                if order.state == OrderState::Paid {
+                // pre-ordered items are not in stock yet, nothing to reserve
+                if order.state == OrderState::Paid && !order.pre_order {
                     debug!("Updating warehouses stock with product id {}", order.product);
                     let order_quantity = order.quantity;
                     let res = warehouses_microservice
@@ -651,6 +2036,36 @@ impl OrderServiceImpl {
         })
     }
 
+    fn restock_product(
+        warehouses_microservice: Arc<WarehousesMicroservice>,
+        product_id: ProductId,
+        quantity: Quantity,
+    ) -> impl Future<Item = (), Error = FailureError> {
+        debug!("Restocking product id {} with quantity {}", product_id, quantity.0);
+        warehouses_microservice
+            .find_by_product_id(Initiator::Superadmin, product_id)
+            .and_then(move |stocks| match stocks.into_iter().next() {
+                Some(stock) => {
+                    let new_quantity = stock.quantity.0 + quantity.0;
+                    debug!(
+                        "New warehouses {} product {} quantity {}",
+                        stock.warehouse_id, stock.product_id, new_quantity
+                    );
+                    Either::A(
+                        warehouses_microservice
+                            .set_product_in_warehouse(Initiator::Superadmin, stock.warehouse_id, stock.product_id, Quantity(new_quantity))
+                            .map(|_| ()),
+                    )
+                }
+                None => Either::B(future::ok(())),
+            })
+            .map_err(|e| {
+                e.context("incrementing quantity in warehouses microservice failed.")
+                    .context(Error::HttpClient)
+                    .into()
+            })
+    }
+
     // Contains reversal of Order creation
     fn create_revert(self) -> impl Future<Item = (Self, ()), Error = (Self, FailureError)> {
         let log = self.log.lock().unwrap().clone();
@@ -703,6 +2118,15 @@ impl OrderService for OrderServiceImpl {
         )
     }
 
+    fn quote(self, input: ConvertCart) -> ServiceFuture<Box<OrderService>, Quote> {
+        Box::new(
+            self.quote_cart(input)
+                .map(|(s, quote)| (Box::new(s) as Box<OrderService>, quote))
+                .or_else(|(s, e)| future::err((Box::new(s) as Box<OrderService>, e)))
+                .map_err(|(s, e): (Box<OrderService>, FailureError)| (s, parse_validation_errors(e, &["phone"]))),
+        )
+    }
+
     fn create_buy_now(self, input: BuyNow) -> ServiceFuture<Box<OrderService>, Invoice> {
         Box::new(
             self.create_from_buy_now(input)
@@ -734,9 +2158,17 @@ impl OrderService for OrderServiceImpl {
             order_slug, order_state, track_id, comment, committer_role
         );
         Box::new(
-            self.set_state_happy(order_slug, order_state, track_id, comment, committer_role)
-                .map(|(s, o)| (Box::new(s) as Box<OrderService>, o))
-                .or_else(|(s, e)| future::err((Box::new(s) as Box<OrderService>, e))),
+            self.set_state_happy(
+                order_slug,
+                order_state,
+                track_id,
+                comment,
+                Some(committer_role),
+                StateChangeSource::Manual,
+                true,
+            )
+            .map(|(s, o)| (Box::new(s) as Box<OrderService>, o))
+            .or_else(|(s, e)| future::err((Box::new(s) as Box<OrderService>, e))),
         )
     }
 
@@ -748,4 +2180,179 @@ impl OrderService for OrderServiceImpl {
                 .or_else(|(s, e)| future::err((Box::new(s) as Box<OrderService>, e))),
         )
     }
+
+    fn auto_complete_orders(self) -> ServiceFuture<Box<OrderService>, Vec<Option<Order>>> {
+        info!("Completing orders after hold period");
+        Box::new(
+            self.auto_complete_orders_happy()
+                .map(|(s, orders)| (Box::new(s) as Box<OrderService>, orders))
+                .or_else(|(s, e)| future::err((Box::new(s) as Box<OrderService>, e))),
+        )
+    }
+
+    fn auto_decline_orders(self) -> ServiceFuture<Box<OrderService>, Vec<Option<Order>>> {
+        info!("Declining orders not confirmed by seller");
+        Box::new(
+            self.auto_decline_orders_happy()
+                .map(|(s, orders)| (Box::new(s) as Box<OrderService>, orders))
+                .or_else(|(s, e)| future::err((Box::new(s) as Box<OrderService>, e))),
+        )
+    }
+
+    fn remind_stores_to_confirm_orders(self) -> ServiceFuture<Box<OrderService>, ()> {
+        info!("Reminding stores to confirm paid orders");
+        Box::new(
+            self.remind_stores_to_confirm_orders_happy()
+                .map(|(s, _)| (Box::new(s) as Box<OrderService>, ()))
+                .or_else(|(s, e)| future::err((Box::new(s) as Box<OrderService>, e))),
+        )
+    }
+
+    fn resolve_dispute(
+        self,
+        order_slug: OrderSlug,
+        resolution: DisputeResolution,
+        payload: ResolveDisputePayload,
+    ) -> ServiceFuture<Box<OrderService>, Option<Order>> {
+        info!(
+            "resolve dispute on order {} in favour of {:?}, comment {:?}, user: {:?}",
+            order_slug, resolution, payload.comment, self.user_id
+        );
+        Box::new(
+            self.resolve_dispute_happy(order_slug, resolution, payload)
+                .map(|(s, o)| (Box::new(s) as Box<OrderService>, o))
+                .or_else(|(s, e)| future::err((Box::new(s) as Box<OrderService>, e))),
+        )
+    }
+
+    fn get_order_history(self, order_slug: OrderSlug) -> ServiceFuture<Box<OrderService>, Vec<OrderHistoryEntry>> {
+        Box::new(
+            self.orders_microservice
+                .get_order_history(None, OrderIdentifier::Slug(order_slug))
+                .then(|res| match res {
+                    Ok(history) => Ok((Box::new(self) as Box<OrderService>, history)),
+                    Err(e) => Err((Box::new(self) as Box<OrderService>, e)),
+                }),
+        )
+    }
+
+    fn bulk_set_state(self, payload: BulkUpdateStatePayload) -> ServiceFuture<Box<OrderService>, Vec<BulkUpdateStateResult>> {
+        info!(
+            "bulk set state of {} orders, committer: {}",
+            payload.items.len(),
+            payload.committer_role
+        );
+        let max_items = self.config.service.bulk_max_items;
+        if payload.items.len() > max_items {
+            let e =
+                Error::Validate(validation_errors!({"items": ["items" => format!("At most {} orders can be updated at once", max_items)]}));
+            return Box::new(future::err((Box::new(self) as Box<OrderService>, e.into())));
+        }
+        Box::new(
+            self.bulk_set_state_happy(payload)
+                .map(|(s, results)| (Box::new(s) as Box<OrderService>, results))
+                .or_else(|(s, e)| future::err((Box::new(s) as Box<OrderService>, e))),
+        )
+    }
+
+    fn remind_stores_to_ship_pre_orders(self) -> ServiceFuture<Box<OrderService>, ()> {
+        info!("Reminding stores to ship pre-orders");
+        Box::new(
+            self.remind_stores_to_ship_pre_orders_happy()
+                .map(|(s, _)| (Box::new(s) as Box<OrderService>, ()))
+                .or_else(|(s, e)| future::err((Box::new(s) as Box<OrderService>, e))),
+        )
+    }
+
+    fn auto_cancel_pre_orders(self) -> ServiceFuture<Box<OrderService>, Vec<Option<Order>>> {
+        info!("Cancelling pre-orders not sent in time");
+        Box::new(
+            self.auto_cancel_pre_orders_happy()
+                .map(|(s, orders)| (Box::new(s) as Box<OrderService>, orders))
+                .or_else(|(s, e)| future::err((Box::new(s) as Box<OrderService>, e))),
+        )
+    }
+
+    fn set_user_notification_opt_outs(
+        self,
+        user_id: UserId,
+        opt_outs: NotificationOptOuts,
+    ) -> ServiceFuture<Box<OrderService>, NotificationOptOuts> {
+        debug!("Setting notification opt-outs of user {}: {:?}", user_id, opt_outs);
+        Box::new(
+            self.set_user_notification_opt_outs_happy(user_id, opt_outs)
+                .map(|(s, opt_outs)| (Box::new(s) as Box<OrderService>, opt_outs))
+                .or_else(|(s, e)| future::err((Box::new(s) as Box<OrderService>, e))),
+        )
+    }
+
+    fn set_store_notification_opt_outs(
+        self,
+        store_id: StoreId,
+        opt_outs: NotificationOptOuts,
+    ) -> ServiceFuture<Box<OrderService>, NotificationOptOuts> {
+        debug!("Setting notification opt-outs of store {}: {:?}", store_id, opt_outs);
+        Box::new(
+            self.set_store_notification_opt_outs_happy(store_id, opt_outs)
+                .map(|(s, opt_outs)| (Box::new(s) as Box<OrderService>, opt_outs))
+                .or_else(|(s, e)| future::err((Box::new(s) as Box<OrderService>, e))),
+        )
+    }
+
+    fn pay_sellers(self) -> ServiceFuture<Box<OrderService>, Vec<Payout>> {
+        info!("Paying sellers for completed orders");
+        Box::new(
+            self.pay_sellers_happy()
+                .map(|(s, payouts)| (Box::new(s) as Box<OrderService>, payouts))
+                .or_else(|(s, e)| future::err((Box::new(s) as Box<OrderService>, e))),
+        )
+    }
+}
+
+/// Skipped billing callbacks are counted by reason of skipping
+fn log_skipped_billing_event(order_info: &BillingOrderInfo, event_status: BillingEventStatus) {
+    info!(
+        "Billing event {:?} for order {} is {}, skipping it",
+        order_info.sequence_number,
+        order_info.order_id,
+        event_status.as_label()
+    );
+    metrics::BILLING_EVENTS_SKIPPED.with_label_values(&[event_status.as_label()]).inc();
+}
+
+/// Payment state may only move along payment states graph and must match order state
+fn validate_payment_state(order: &Order, current_state: PaymentState, new_state: PaymentState) -> Result<(), FailureError> {
+    if current_state == PaymentState::PaymentToSellerInProgress || new_state == PaymentState::PaymentToSellerInProgress {
+        let message = "Payment state of order being paid out is changed by payout run only".to_string();
+        return Err(Error::Validate(validation_errors!({"state": ["state" => message]})).into());
+    }
+    if !current_state.can_move_to(new_state) {
+        let message = format!("Payment state can not be changed from {:?} to {:?}", current_state, new_state);
+        return Err(Error::Validate(validation_errors!({"state": ["state" => message]})).into());
+    }
+    if !new_state.is_consistent_with(order.state) {
+        let message = format!("Payment state {:?} does not match order state {}", new_state, order.state);
+        return Err(Error::Validate(validation_errors!({"state": ["state" => message]})).into());
+    }
+    Ok(())
+}
+
+/// Recipient may opt out only of states allowed by notification rules
+fn validate_opt_outs(rules: &config::RecipientNotificationRules, opt_outs: &NotificationOptOuts) -> Result<(), FailureError> {
+    match opt_outs.order_states.iter().find(|state| !rules.opt_out_allowed.contains(state)) {
+        Some(state) => Err(Error::Validate(
+            validation_errors!({"order_states": ["order_states" => format!("Opting out of {} notifications is not allowed", state)]}),
+        )
+        .into()),
+        None => Ok(()),
+    }
+}
+
+/// Date the pre-ordered item was promised to be sent at
+fn pre_order_ship_deadline(order: &Order) -> Option<DateTime<Utc>> {
+    if order.pre_order {
+        Some(order.created_at + Duration::days(i64::from(order.pre_order_days)))
+    } else {
+        None
+    }
 }
@@ -1,10 +1,11 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::{Arc, Mutex};
 
+use chrono::{Duration, Utc};
 use failure::Error as FailureError;
 use failure::Fail;
 use futures;
-use futures::future::{self, Either};
+use futures::future::{self, join_all, Either};
 use futures::prelude::*;
 use futures::stream::iter_ok;
 use hyper::header::Authorization;
@@ -16,14 +17,13 @@ use stq_types::{
     WarehouseRole,
 };
 
-use stq_static_resources::{
-    BaseProductModerationStatusForModerator, BaseProductModerationStatusForUser, EmailUser, ModerationStatus,
-    StoreModerationStatusForModerator, StoreModerationStatusForUser,
-};
+use stq_api::orders::Order;
+use stq_static_resources::{BaseProductModerationStatusForModerator, EmailUser, ModerationStatus, StoreModerationStatusForModerator};
 
 use super::parse_validation_errors;
-use config;
+use config::{self, OpenOrdersPolicy};
 use errors::Error;
+use metrics;
 use microservice::*;
 use models::*;
 use services::types::ServiceFuture;
@@ -40,8 +40,12 @@ pub trait StoreService {
     fn send_to_moderation_base_product(self, base_product_id: BaseProductId) -> ServiceFuture<Box<StoreService>, ()>;
     /// Deactivate base product
     fn deactivate_base_product(self, base_product_id: BaseProductId) -> ServiceFuture<Box<StoreService>, BaseProduct>;
-    /// Deactivate store
+    /// Deactivate store together with its base products, shipping and stock
     fn deactivate_store(self, store: StoreId) -> ServiceFuture<Box<StoreService>, Store>;
+    /// Reactivate store together with base products, stock and shipping taken away by its deactivation
+    fn reactivate_store(self, store_id: StoreId) -> ServiceFuture<Box<StoreService>, Store>;
+    /// Reactivate base product restoring warehouse and shipping state it had before store deactivation
+    fn reactivate_base_product(self, base_product_id: BaseProductId) -> ServiceFuture<Box<StoreService>, BaseProduct>;
     /// Deactivate product
     fn deactivate_product(self, product_id: ProductId) -> ServiceFuture<Box<StoreService>, Product>;
     /// Update base product
@@ -51,6 +55,52 @@ pub trait StoreService {
         payload: UpdateBaseProduct,
     ) -> ServiceFuture<Box<StoreService>, BaseProduct>;
     fn create_base_product_with_variants(self, payload: NewBaseProductWithVariants) -> ServiceFuture<Box<StoreService>, BaseProduct>;
+    /// Delete store together with its roles, merchant, stock, shipping and cart entries
+    fn delete_store(self, store_id: StoreId) -> ServiceFuture<Box<StoreService>, Store>;
+    /// Move store to another owner together with its roles in other microservices
+    fn transfer_ownership(self, store_id: StoreId, payload: TransferStoreOwnership) -> ServiceFuture<Box<StoreService>, Store>;
+    /// Add user to store staff, only store owner and superadmin may do it
+    fn add_staff(self, store_id: StoreId, payload: NewStoreStaff) -> ServiceFuture<Box<StoreService>, StoreStaffMember>;
+    /// Add user found by email to store staff or invite them to sign up, and tell them about it.
+    /// Only store owner and superadmin may do it
+    fn invite_staff(self, store_id: StoreId, payload: StoreStaffInvite) -> ServiceFuture<Box<StoreService>, StoreStaffInviteResult>;
+    /// Add user to staff of the stores they were invited to before they had an account
+    fn accept_staff_invitations(self, user_id: UserId, email: String) -> ServiceFuture<Box<StoreService>, Vec<StoreStaffMember>>;
+    /// Remove user from store staff, only store owner and superadmin may do it
+    fn remove_staff(self, store_id: StoreId, user_id: UserId) -> ServiceFuture<Box<StoreService>, ()>;
+    /// Staff members of the store, store owner is not included. Only store owner and superadmin may list them
+    fn get_staff(self, store_id: StoreId) -> ServiceFuture<Box<StoreService>, Vec<StoreStaffMember>>;
+    /// Stores and base products waiting for the moderator
+    fn get_moderator_assignments(self, moderator_id: UserId) -> ServiceFuture<Box<StoreService>, Vec<ModerationAssignment>>;
+    /// Stores and base products waiting for moderation
+    fn get_waiting_moderations(self) -> ServiceFuture<Box<StoreService>, Vec<ModerationAssignment>>;
+    /// Changes of published base product waiting for moderator's approval
+    fn get_base_product_pending_changes(
+        self,
+        base_product_id: BaseProductId,
+    ) -> ServiceFuture<Box<StoreService>, Option<UpdateBaseProduct>>;
+    /// Move subjects the assigned moderator did not handle in time to other moderators
+    fn reassign_expired_moderations(self) -> ServiceFuture<Box<StoreService>, Vec<ModerationAssignment>>;
+    /// Remind moderators about subjects waiting for them longer than SLA
+    fn remind_overdue_moderations(self) -> ServiceFuture<Box<StoreService>, Vec<ModerationAssignment>>;
+    /// Tell all moderators about subjects waiting for moderation too long
+    fn escalate_overdue_moderations(self) -> ServiceFuture<Box<StoreService>, Vec<ModerationAssignment>>;
+    /// Set moderation status of many stores and base products, managers get one digest each
+    fn bulk_moderate(self, payload: BulkModeratePayload) -> ServiceFuture<Box<StoreService>, Vec<BulkModerateResult>>;
+}
+
+/// Store or base product moderated in bulk moderation
+#[derive(Clone, Debug)]
+struct ModerationOutcome {
+    subject: ModerationSubject,
+    store_id: StoreId,
+    /// Known only when the store itself was moderated
+    manager_id: Option<UserId>,
+    status: ModerationStatus,
+    reason: Option<ModerationRejectionReason>,
+    comment: Option<String>,
+    /// Products that have to be removed from carts after the status change
+    product_ids: Vec<ProductId>,
 }
 
 pub struct StoreServiceImpl {
@@ -63,6 +113,8 @@ pub struct StoreServiceImpl {
     pub users_microservice: Arc<UsersMicroservice>,
     pub config: config::Config,
     pub log: Arc<Mutex<CreateStoreOperationLog>>,
+    pub delete_log: Arc<Mutex<DeleteStoreOperationLog>>,
+    pub reactivate_log: Arc<Mutex<ReactivateStoreOperationLog>>,
 }
 
 impl StoreServiceImpl {
@@ -77,9 +129,13 @@ impl StoreServiceImpl {
         delivery_microservice: Arc<DeliveryMicroservice>,
     ) -> Self {
         let log = Arc::new(Mutex::new(CreateStoreOperationLog::new()));
+        let delete_log = Arc::new(Mutex::new(DeleteStoreOperationLog::new()));
+        let reactivate_log = Arc::new(Mutex::new(ReactivateStoreOperationLog::new()));
         Self {
             config,
             log,
+            delete_log,
+            reactivate_log,
             orders_microservice,
             stores_microservice,
             notifications_microservice,
@@ -90,6 +146,19 @@ impl StoreServiceImpl {
         }
     }
 
+    fn fork(&self) -> Self {
+        Self::new(
+            self.config.clone(),
+            self.orders_microservice.clone(),
+            self.stores_microservice.clone(),
+            self.notifications_microservice.clone(),
+            self.billing_microservice.clone(),
+            self.warehouses_microservice.clone(),
+            self.users_microservice.clone(),
+            self.delivery_microservice.clone(),
+        )
+    }
+
     fn create_store(self, input: &NewStore, saga_id: SagaId) -> ServiceFuture<Self, Store> {
         // Create Store
         debug!("Creating store, input: {:?}", input);
@@ -385,267 +454,2438 @@ impl StoreServiceImpl {
         })
     }
 
-    fn set_store_moderation_status(self, payload: StoreModerate) -> ServiceFuture<Self, Store> {
-        let res = self.stores_microservice.set_store_moderation_status(payload).then(|res| match res {
-            Ok(store) => Ok((self, store)),
-            Err(_) => Err((self, format_err!("Store service set_moderation_status error occurred."))),
-        });
-
-        Box::new(res)
-    }
-
-    fn send_to_moderation(self, store_id: StoreId) -> ServiceFuture<Self, Store> {
-        let res = self.stores_microservice.send_to_moderation(store_id).then(|res| match res {
-            Ok(store) => Ok((self, store)),
-            Err(_) => Err((self, format_err!("Store service send_to_moderation error occurred."))),
-        });
+    fn get_store(self, store_id: StoreId) -> ServiceFuture<Self, Store> {
+        let res = self
+            .stores_microservice
+            .get(store_id, Visibility::Active)
+            .then(move |res| match res {
+                Ok(Some(store)) => Ok((self, store)),
+                Ok(None) => Err((
+                    self,
+                    format_err!("Store {} is not found in stores microservice.", store_id)
+                        .context(Error::NotFound)
+                        .into(),
+                )),
+                Err(e) => Err((self, e)),
+            });
 
         Box::new(res)
     }
 
-    fn set_moderation_status_base_product(self, payload: BaseProductModerate) -> ServiceFuture<Self, BaseProduct> {
+    // Stores microservice lets only the store owner and superadmin update the store, so an update
+    // that changes nothing, made as the caller, checks they may manage it before anything is changed as superadmin
+    fn authorize_store_management(self, store_id: StoreId) -> ServiceFuture<Self, ()> {
+        debug!("Checking that the caller may manage store {}", store_id);
         let res = self
             .stores_microservice
-            .set_moderation_status_base_product(payload)
+            .update_store(None, store_id, UpdateStore::default())
             .then(|res| match res {
-                Ok(base) => Ok((self, base)),
-                Err(_) => Err((
-                    self,
-                    format_err!("Store service set_moderation_status_base_product error occurred."),
-                )),
+                Ok(_) => Ok((self, ())),
+                Err(e) => Err((self, e)),
             });
 
         Box::new(res)
     }
 
-    fn send_to_moderation_base_product(self, base_product_id: BaseProductId) -> ServiceFuture<Self, BaseProduct> {
+    fn get_store_with_products(self, store_id: StoreId) -> ServiceFuture<Self, (Store, Vec<Product>)> {
+        let products = self.stores_microservice.get_products_by_store(store_id);
+
+        Box::new(self.get_store(store_id).and_then(|(s, store)| {
+            products.then(|res| match res {
+                Ok(products) => Ok((s, (store, products))),
+                Err(e) => Err((s, e)),
+            })
+        }))
+    }
+
+    fn search_open_orders(self, store_id: StoreId) -> ServiceFuture<Self, Vec<Order>> {
+        let terms = OrderSearchTerms {
+            store_id: Some(store_id),
+            ..Default::default()
+        };
+
         let res = self
-            .stores_microservice
-            .send_to_moderation_base_product(base_product_id)
+            .orders_microservice
+            .search_orders(Some(Initiator::Superadmin), terms)
+            .map(|orders| orders.into_iter().filter(|order| is_open_order_state(order.state)).collect())
             .then(|res| match res {
-                Ok(base) => Ok((self, base)),
-                Err(_) => Err((self, format_err!("Store service send_to_moderation_base_product error occurred."))),
+                Ok(orders) => Ok((self, orders)),
+                Err(e) => Err((self, e)),
             });
 
         Box::new(res)
     }
 
-    fn notify_moderators_base_product_update_moderation_status(
-        self,
-        store_id: StoreId,
-        base_product_id: BaseProductId,
-        status: ModerationStatus,
-    ) -> impl Future<Item = (Self, ()), Error = (Self, FailureError)> {
-        info!("get moderators from stores microservice");
+    // Store can not be deleted while its customers are still waiting for their orders
+    fn check_no_open_orders(self, store_id: StoreId) -> ServiceFuture<Self, ()> {
+        Box::new(self.search_open_orders(store_id).and_then(move |(s, orders)| {
+            if orders.is_empty() {
+                Ok((s, ()))
+            } else {
+                Err((s, open_orders_error(store_id, orders.len())))
+            }
+        }))
+    }
 
-        let stores_microservice = self.stores_microservice.clone();
-        let notifications_microservice = self.notifications_microservice.clone();
-        let users_microservice = self.users_microservice.clone();
-        let cluster_url = self.config.cluster.url.clone();
+    // Sellers are paid out to the merchant of the store, so the store can not be deleted
+    // while some of its orders are waiting for their payout or being paid out
+    fn check_no_pending_payouts(self, store_id: StoreId) -> ServiceFuture<Self, ()> {
+        let searches = [PaymentState::PaymentToSellerNeeded, PaymentState::PaymentToSellerInProgress]
+            .iter()
+            .map(|payment_state| {
+                self.billing_microservice.search_orders(
+                    Some(Initiator::Superadmin),
+                    BillingOrderSearchTerms {
+                        payment_state: *payment_state,
+                        paid_out: None,
+                    },
+                )
+            })
+            .collect::<Vec<_>>();
 
-        stores_microservice
-            .get_moderators(Initiator::Superadmin)
-            .map_err(FailureError::from)
-            .and_then(move |results| {
-                let fut = iter_ok::<_, FailureError>(results).for_each(move |moderator_id| {
-                    let notif = notifications_microservice.clone();
-                    let cluster_url = cluster_url.clone();
+        let res = join_all(searches).then(move |res| match res {
+            Ok(orders) => {
+                let pending_payouts = orders.into_iter().flatten().filter(|order| order.store_id == store_id).count();
+                if pending_payouts == 0 {
+                    Ok((self, ()))
+                } else {
+                    let message = format!(
+                        "Store {} has {} orders waiting for payout, they must be paid out first",
+                        store_id, pending_payouts
+                    );
+                    Err((
+                        self,
+                        Error::Validate(validation_errors!({"store": ["pending_payouts" => message]})).into(),
+                    ))
+                }
+            }
+            Err(e) => Err((self, e)),
+        });
 
-                    Box::new(
-                        users_microservice
-                            .clone()
-                            .get(Some(Initiator::Superadmin), moderator_id)
-                            .and_then(move |moderator| {
-                                if let Some(user) = moderator {
-                                    let email_user = EmailUser {
-                                        email: user.email.clone(),
-                                        first_name: user.first_name.unwrap_or_else(|| "user".to_string()),
-                                        last_name: user.last_name.unwrap_or_else(|| "".to_string()),
-                                    };
-                                    let email = BaseProductModerationStatusForModerator {
-                                        user: email_user,
-                                        store_id: store_id.to_string(),
-                                        base_product_id: base_product_id.to_string(),
-                                        cluster_url,
-                                        status,
-                                    };
-                                    Either::A(
-                                        notif
-                                            .base_product_moderation_status_for_moderator(Initiator::Superadmin, email)
-                                            .then(|_| Ok(())),
-                                    )
-                                } else {
-                                    Either::B(future::ok(()))
-                                }
-                            }),
-                    ) as Box<Future<Item = (), Error = FailureError>>
-                });
+        Box::new(res)
+    }
+
+    // Depending on config open orders either block store deactivation or their buyers are notified about it
+    fn check_open_orders_before_deactivation(self, store_id: StoreId) -> ServiceFuture<Self, Vec<Order>> {
+        let policy = self.config.store_deactivation.open_orders;
+
+        Box::new(self.search_open_orders(store_id).and_then(move |(s, orders)| match policy {
+            OpenOrdersPolicy::Block if !orders.is_empty() => Err((s, open_orders_error(store_id, orders.len()))),
+            _ => Ok((s, orders)),
+        }))
+    }
+
+    fn delete_warehouses_roles(self, user_id: UserId, store_id: StoreId) -> ServiceFuture<Self, ()> {
+        debug!("Deleting warehouses roles, user id: {}, store id: {}", user_id, store_id);
+        let log = self.delete_log.clone();
+        let warehouses_microservice = self.warehouses_microservice.clone();
 
-                fut
+        let res = self
+            .warehouses_microservice
+            .get_warehouse_roles_for_user(Some(Initiator::Superadmin), user_id)
+            .and_then(move |roles| {
+                let store_roles = roles.into_iter().filter(move |role| role.role.data == store_id);
+                iter_ok::<_, FailureError>(store_roles).for_each(move |role| {
+                    let log = log.clone();
+                    warehouses_microservice
+                        .delete_warehouse_role(Some(Initiator::Superadmin), role.id)
+                        .map(move |_| log.lock().unwrap().push(DeleteStoreOperationStage::WarehousesRoleDeleted(role)))
+                })
             })
             .then(|res| match res {
                 Ok(_) => Ok((self, ())),
                 Err(e) => Err((self, e)),
-            })
-    }
+            });
 
-    fn notify_manager_store_update_moderation_status(
-        self,
-        store_id: StoreId,
-        store_manager_id: UserId,
-        status: ModerationStatus,
-    ) -> impl Future<Item = (Self, ()), Error = (Self, FailureError)> {
-        let cluster_url = self.config.cluster.url.clone();
-        let notifications_microservice = self.notifications_microservice.clone();
-        let users_microservice = self.users_microservice.clone();
+        Box::new(res)
+    }
 
-        let fut = Box::new(
-            users_microservice
-                .get(Some(Initiator::Superadmin), store_manager_id)
-                .and_then(move |store_manager| {
-                    if let Some(user) = store_manager {
-                        let email = StoreModerationStatusForUser {
-                            store_email: user.email.to_string(),
-                            store_id: store_id.to_string(),
-                            cluster_url,
-                            status,
-                        };
+    fn delete_orders_roles(self, user_id: UserId, store_id: StoreId) -> ServiceFuture<Self, ()> {
+        debug!("Deleting orders roles, user id: {}, store id: {}", user_id, store_id);
+        let log = self.delete_log.clone();
+        let orders_microservice = self.orders_microservice.clone();
 
-                        Either::A(
-                            notifications_microservice
-                                .store_moderation_status_for_user(Initiator::Superadmin, email)
-                                .then(|_| Ok(())),
-                        )
-                    } else {
-                        Either::B(future::ok(()))
-                    }
-                }),
-        ) as Box<Future<Item = (), Error = FailureError>>;
+        let res = self
+            .orders_microservice
+            .get_roles_for_user(Some(Initiator::Superadmin), user_id)
+            .and_then(move |roles| {
+                let store_roles = roles.into_iter().filter(move |role| role.role.data == store_id);
+                iter_ok::<_, FailureError>(store_roles).for_each(move |role| {
+                    let log = log.clone();
+                    orders_microservice
+                        .delete_role(Some(Initiator::Superadmin), role.id)
+                        .map(move |_| log.lock().unwrap().push(DeleteStoreOperationStage::OrdersRoleDeleted(role)))
+                })
+            })
+            .then(|res| match res {
+                Ok(_) => Ok((self, ())),
+                Err(e) => Err((self, e)),
+            });
 
-        fut.then(|res| match res {
-            Ok(_) => Ok((self, ())),
-            Err(e) => Err((self, e)),
-        })
+        Box::new(res)
     }
 
-    fn notify_manager_base_product_update_moderation_status(
-        self,
-        store_id: StoreId,
-        base_product_id: BaseProductId,
-        status: ModerationStatus,
-    ) -> impl Future<Item = (Self, ()), Error = (Self, FailureError)> {
-        let cluster_url = self.config.cluster.url.clone();
-        let notifications_microservice = self.notifications_microservice.clone();
-        let users_microservice = self.users_microservice.clone();
-        let stores_microservice = self.stores_microservice.clone();
+    fn delete_billing_roles(self, user_id: UserId, store_id: StoreId) -> ServiceFuture<Self, ()> {
+        debug!("Deleting billing roles, user id: {}, store id: {}", user_id, store_id);
+        let log = self.delete_log.clone();
+        let billing_microservice = self.billing_microservice.clone();
 
-        let fut = Box::new(
-            stores_microservice
-                .get(store_id, Visibility::Active)
-                .and_then(move |store| {
-                    store
-                        .ok_or_else(|| {
-                            error!(
-                                "Sending notification to store can not be done. Store with id: {} is not found.",
-                                store_id
-                            );
-                            format_err!("Store is not found in stores microservice.")
-                                .context(Error::NotFound)
-                                .into()
-                        })
-                        .into_future()
+        let res = self
+            .billing_microservice
+            .get_roles_for_user(Some(Initiator::Superadmin), user_id)
+            .and_then(move |roles| {
+                let store_roles = roles.into_iter().filter(move |role| role.data == Some(store_id));
+                iter_ok::<_, FailureError>(store_roles).for_each(move |role| {
+                    let log = log.clone();
+                    billing_microservice
+                        .delete_role(Some(Initiator::Superadmin), role.id)
+                        .map(move |_| log.lock().unwrap().push(DeleteStoreOperationStage::BillingRoleDeleted(role)))
                 })
-                .and_then(move |store| {
-                    users_microservice
-                        .get(Some(Initiator::Superadmin), store.user_id)
-                        .and_then(move |store_manager| {
-                            if let Some(user) = store_manager {
-                                let email = BaseProductModerationStatusForUser {
-                                    store_email: user.email.to_string(),
-                                    store_id: store_id.to_string(),
-                                    base_product_id: base_product_id.to_string(),
-                                    cluster_url,
-                                    status,
-                                };
-
-                                Either::A(
-                                    notifications_microservice
-                                        .base_product_moderation_status_for_user(Initiator::Superadmin, email)
-                                        .then(|_| Ok(())),
-                                )
-                            } else {
-                                Either::B(future::ok(()))
-                            }
-                        })
-                }),
-        ) as Box<Future<Item = (), Error = FailureError>>;
+            })
+            .then(|res| match res {
+                Ok(_) => Ok((self, ())),
+                Err(e) => Err((self, e)),
+            });
 
-        fut.then(|res| match res {
-            Ok(_) => Ok((self, ())),
-            Err(e) => Err((self, e)),
-        })
+        Box::new(res)
     }
 
-    fn notify_moderators_store_update_moderation_status(
-        self,
-        store_id: StoreId,
-        status: ModerationStatus,
-    ) -> impl Future<Item = (Self, ()), Error = (Self, FailureError)> {
-        info!("get moderators from stores microservice");
+    fn delete_delivery_roles(self, user_id: UserId, store_id: StoreId) -> ServiceFuture<Self, ()> {
+        debug!("Deleting delivery roles, user id: {}, store id: {}", user_id, store_id);
+        let log = self.delete_log.clone();
+        let delivery_microservice = self.delivery_microservice.clone();
 
-        let stores_microservice = self.stores_microservice.clone();
-        let notifications_microservice = self.notifications_microservice.clone();
-        let users_microservice = self.users_microservice.clone();
-        let cluster_url = self.config.cluster.url.clone();
+        let res = self
+            .delivery_microservice
+            .get_delivery_roles_for_user(Some(Initiator::Superadmin), user_id)
+            .and_then(move |roles| {
+                let store_roles = roles.into_iter().filter(move |role| role.data == Some(store_id));
+                iter_ok::<_, FailureError>(store_roles).for_each(move |role| {
+                    let log = log.clone();
+                    delivery_microservice
+                        .delete_delivery_role(Some(Initiator::Superadmin), role.id)
+                        .map(move |_| log.lock().unwrap().push(DeleteStoreOperationStage::DeliveryRoleDeleted(role)))
+                })
+            })
+            .then(|res| match res {
+                Ok(_) => Ok((self, ())),
+                Err(e) => Err((self, e)),
+            });
 
-        stores_microservice
-            .get_moderators(Initiator::Superadmin)
-            .map_err(FailureError::from)
-            .and_then(move |results| {
-                let fut = iter_ok::<_, FailureError>(results).for_each(move |moderator_id| {
-                    let notif = notifications_microservice.clone();
-                    let cluster_url = cluster_url.clone();
+        Box::new(res)
+    }
 
-                    Box::new(
-                        users_microservice
-                            .clone()
-                            .get(Some(Initiator::Superadmin), moderator_id)
-                            .and_then(move |moderator| {
-                                if let Some(user) = moderator {
-                                    let email_user = EmailUser {
-                                        email: user.email.clone(),
-                                        first_name: user.first_name.unwrap_or_else(|| "user".to_string()),
-                                        last_name: user.last_name.unwrap_or_else(|| "".to_string()),
-                                    };
-                                    let email = StoreModerationStatusForModerator {
-                                        user: email_user,
-                                        store_id: store_id.to_string(),
-                                        cluster_url,
-                                        status,
-                                    };
-                                    Either::A(
-                                        notif
-                                            .store_moderation_status_for_moderator(Initiator::Superadmin, email)
-                                            .then(|_| Ok(())),
-                                    )
-                                } else {
-                                    Either::B(future::ok(()))
-                                }
-                            }),
-                    ) as Box<Future<Item = (), Error = FailureError>>
-                });
+    fn delete_merchant(self, store_id: StoreId, store_country_code: Option<String>) -> ServiceFuture<Self, ()> {
+        debug!("Deleting merchant for store_id: {}", store_id);
+        let log = self.delete_log.clone();
+        let payload = CreateStoreMerchantPayload {
+            id: store_id,
+            country_code: store_country_code,
+        };
 
-                fut
+        let res = self
+            .billing_microservice
+            .delete_store_merchant(Some(Initiator::Superadmin), store_id)
+            .map(move |_| {
+                log.lock()
+                    .unwrap()
+                    .push(DeleteStoreOperationStage::BillingDeleteMerchantComplete(payload))
             })
             .then(|res| match res {
                 Ok(_) => Ok((self, ())),
                 Err(e) => Err((self, e)),
+            });
+
+        Box::new(res)
+    }
+
+    fn clear_warehouses_stock(self, product_ids: Vec<ProductId>) -> ServiceFuture<Self, ()> {
+        debug!("Clearing warehouses stock of {} products", product_ids.len());
+        let log = self.delete_log.clone();
+        let warehouses_microservice = self.warehouses_microservice.clone();
+
+        let stocks = product_ids
+            .into_iter()
+            .map(|product_id| self.warehouses_microservice.find_by_product_id(Initiator::Superadmin, product_id))
+            .collect::<Vec<_>>();
+
+        let res = join_all(stocks)
+            .map(|stocks| {
+                stocks
+                    .into_iter()
+                    .flatten()
+                    .filter(|stock| stock.quantity.0 > 0)
+                    .collect::<Vec<_>>()
+            })
+            .and_then(move |stocks| {
+                iter_ok::<_, FailureError>(stocks).for_each(move |stock| {
+                    let log = log.clone();
+                    let warehouse_id = stock.warehouse_id;
+                    let product_id = stock.product_id;
+                    let quantity = stock.quantity;
+                    warehouses_microservice
+                        .set_product_in_warehouse(Initiator::Superadmin, warehouse_id, product_id, Quantity(0))
+                        .map(move |_| {
+                            log.lock().unwrap().push(DeleteStoreOperationStage::WarehouseStockCleared {
+                                warehouse_id,
+                                product_id,
+                                quantity,
+                            })
+                        })
+                })
             })
+            .then(|res| match res {
+                Ok(_) => Ok((self, ())),
+                Err(e) => Err((self, e)),
+            });
+
+        Box::new(res)
     }
 
-    fn remove_products_from_cart_after_base_product_status_change(
+    fn delete_store_record(self, store_id: StoreId) -> ServiceFuture<Self, Store> {
+        debug!("Deleting store, store id: {}", store_id);
+        let res = self
+            .stores_microservice
+            .delete_store(Some(Initiator::Superadmin), store_id)
+            .then(|res| match res {
+                Ok(store) => Ok((self, store)),
+                Err(e) => Err((self, e)),
+            });
+
+        Box::new(res)
+    }
+
+    // Runs after the store itself is deleted, there is nothing to restore by then, so failures are only logged
+    fn delete_store_leftovers(
         self,
-        base_product_id: BaseProductId,
+        store_id: StoreId,
+        products: Vec<Product>,
+    ) -> impl Future<Item = (Self, ()), Error = (Self, FailureError)> {
+        let warehouses_microservice = self.warehouses_microservice.clone();
+
+        let shipping = self.delete_products_shipping(&products);
+
+        let warehouses = self
+            .warehouses_microservice
+            .find_by_store_id(Some(Initiator::Superadmin), store_id)
+            .and_then(move |warehouses| {
+                join_all(
+                    warehouses
+                        .into_iter()
+                        .map(|warehouse| warehouses_microservice.delete_warehouse(Some(Initiator::Superadmin), warehouse.id))
+                        .collect::<Vec<_>>(),
+                )
+            })
+            .map(|_| ());
+
+        let carts = self.orders_microservice.delete_products_from_all_carts(
+            Some(Initiator::Superadmin),
+            DeleteProductsFromCartsPayload {
+                product_ids: products.into_iter().map(|product| product.id).collect(),
+            },
+        );
+
+        let steps: Vec<(&'static str, Box<Future<Item = (), Error = FailureError>>)> = vec![
+            ("shipping", Box::new(shipping)),
+            ("warehouses", Box::new(warehouses)),
+            ("cart entries", Box::new(carts)),
+        ];
+        let cleanup = steps
+            .into_iter()
+            .map(move |(resource, step)| {
+                step.then(move |res| {
+                    if let Err(e) = res {
+                        error!("Deleting {} of deleted store {} failed: {}", resource, store_id, e);
+                    }
+                    Ok::<_, ()>(())
+                })
+            })
+            .collect::<Vec<_>>();
+
+        join_all(cleanup).then(|_| Ok::<_, (Self, FailureError)>((self, ())))
+    }
+
+    fn delete_products_shipping(&self, products: &[Product]) -> impl Future<Item = (), Error = FailureError> {
+        let base_product_ids = products.iter().map(|product| product.base_product_id).collect::<HashSet<_>>();
+        let deletions = base_product_ids
+            .into_iter()
+            .map(|base_product_id| {
+                self.delivery_microservice
+                    .delete_shipping_by_base_product(Some(Initiator::Superadmin), base_product_id)
+            })
+            .collect::<Vec<_>>();
+
+        join_all(deletions).map(|_| ())
+    }
+
+    // Base products lose their stock and shipping on store deactivation, what they had is kept in stores microservice for reactivation
+    fn save_base_product_deactivations(self, store_id: StoreId, products: &[Product]) -> ServiceFuture<Self, ()> {
+        let log = self.delete_log.clone();
+        let stores_microservice = self.stores_microservice.clone();
+        let warehouses_microservice = self.warehouses_microservice.clone();
+        let delivery_microservice = self.delivery_microservice.clone();
+
+        let mut product_ids_by_base_product: HashMap<BaseProductId, Vec<ProductId>> = HashMap::new();
+        for product in products {
+            product_ids_by_base_product
+                .entry(product.base_product_id)
+                .or_insert_with(Vec::new)
+                .push(product.id);
+        }
+
+        let res = iter_ok::<_, FailureError>(product_ids_by_base_product)
+            .for_each(move |(base_product_id, product_ids)| {
+                debug!("Saving deactivation of base product {}", base_product_id);
+                let log = log.clone();
+                let stores_microservice = stores_microservice.clone();
+
+                let stocks = join_all(
+                    product_ids
+                        .into_iter()
+                        .map(|product_id| warehouses_microservice.find_by_product_id(Initiator::Superadmin, product_id))
+                        .collect::<Vec<_>>(),
+                )
+                .map(|stocks| {
+                    stocks
+                        .into_iter()
+                        .flatten()
+                        .filter(|stock| stock.quantity.0 > 0)
+                        .map(|stock| DeactivatedStock {
+                            warehouse_id: stock.warehouse_id,
+                            product_id: stock.product_id,
+                            quantity: stock.quantity,
+                        })
+                        .collect::<Vec<_>>()
+                });
+                let shipping = delivery_microservice
+                    .get_shipping(Some(Initiator::Superadmin), base_product_id)
+                    .map(|shipping| if shipping.is_empty() { None } else { Some(shipping) });
+
+                stocks
+                    .join(shipping)
+                    .and_then(move |(stocks, shipping)| {
+                        stores_microservice.save_base_product_deactivation(
+                            Some(Initiator::Superadmin),
+                            BaseProductDeactivation {
+                                base_product_id,
+                                store_id,
+                                stocks,
+                                shipping,
+                            },
+                        )
+                    })
+                    .map(move |_| {
+                        log.lock()
+                            .unwrap()
+                            .push(DeleteStoreOperationStage::BaseProductDeactivationSaved(base_product_id))
+                    })
+            })
+            .then(|res| match res {
+                Ok(_) => Ok((self, ())),
+                Err(e) => Err((self, e)),
+            });
+
+        Box::new(res)
+    }
+
+    fn deactivate_store_record(self, store_id: StoreId) -> ServiceFuture<Self, Store> {
+        debug!("Deactivating store, store id: {}", store_id);
+        let log = self.delete_log.clone();
+
+        let res = self
+            .stores_microservice
+            .deactivate_store(None, store_id)
+            .map(move |store| {
+                log.lock().unwrap().push(DeleteStoreOperationStage::StoreDeactivated(store_id));
+                store
+            })
+            .then(|res| match res {
+                Ok(store) => Ok((self, store)),
+                Err(e) => Err((self, e)),
+            });
+
+        Box::new(res)
+    }
+
+    fn deactivate_base_products(self, base_product_ids: HashSet<BaseProductId>) -> ServiceFuture<Self, ()> {
+        debug!("Deactivating base products: {:?}", base_product_ids);
+        let log = self.delete_log.clone();
+        let stores_microservice = self.stores_microservice.clone();
+
+        let res = iter_ok::<_, FailureError>(base_product_ids)
+            .for_each(move |base_product_id| {
+                let log = log.clone();
+                stores_microservice
+                    .deactivate_base_product(Some(Initiator::Superadmin), base_product_id)
+                    .map(move |_| {
+                        log.lock()
+                            .unwrap()
+                            .push(DeleteStoreOperationStage::BaseProductDeactivated(base_product_id))
+                    })
+            })
+            .then(|res| match res {
+                Ok(_) => Ok((self, ())),
+                Err(e) => Err((self, e)),
+            });
+
+        Box::new(res)
+    }
+
+    // Every buyer gets one email listing all of their open orders, failures are only logged
+    fn notify_buyers_store_deactivated(
+        self,
+        store_id: StoreId,
+        orders: Vec<Order>,
+    ) -> impl Future<Item = (Self, ()), Error = (Self, FailureError)> {
+        let mut order_slugs_by_buyer: HashMap<UserId, Vec<String>> = HashMap::new();
+        for order in orders {
+            order_slugs_by_buyer
+                .entry(order.customer)
+                .or_insert_with(Vec::new)
+                .push(order.slug.to_string());
+        }
+
+        let notifications = order_slugs_by_buyer
+            .into_iter()
+            .map(|(buyer_id, order_slugs)| {
+                let notifications_microservice = self.notifications_microservice.clone();
+                let cluster_url = self.config.cluster.url.clone();
+                self.users_microservice
+                    .get(Some(Initiator::Superadmin), buyer_id)
+                    .and_then(move |user| {
+                        user.ok_or_else(|| {
+                            format_err!("User {} is not found in users microservice.", buyer_id)
+                                .context(Error::NotFound)
+                                .into()
+                        })
+                    })
+                    .and_then(move |user| {
+                        let email = StoreDeactivatedForUser {
+                            user: EmailUser {
+                                email: user.email.clone(),
+                                first_name: user.first_name.unwrap_or_else(|| "user".to_string()),
+                                last_name: user.last_name.unwrap_or_else(|| "".to_string()),
+                            },
+                            store_id: store_id.to_string(),
+                            order_slugs,
+                            cluster_url,
+                        };
+                        notifications_microservice.store_deactivated_for_user(Initiator::Superadmin, email)
+                    })
+                    .then(move |res| {
+                        if let Err(e) = res {
+                            error!(
+                                "Notifying buyer {} about deactivation of store {} failed: {}",
+                                buyer_id, store_id, e
+                            );
+                        }
+                        Ok::<_, ()>(())
+                    })
+            })
+            .collect::<Vec<_>>();
+
+        join_all(notifications).then(|_| Ok::<_, (Self, FailureError)>((self, ())))
+    }
+
+    // Runs after everything that can be restored is done, failures are only logged
+    fn delete_shipping_after_deactivation(
+        self,
+        store_id: StoreId,
+        products: Vec<Product>,
+    ) -> impl Future<Item = (Self, ()), Error = (Self, FailureError)> {
+        self.delete_products_shipping(&products).then(move |res| {
+            if let Err(e) = res {
+                error!("Deleting shipping of deactivated store {} failed: {}", store_id, e);
+            }
+            Ok::<_, (Self, FailureError)>((self, ()))
+        })
+    }
+
+    // Contains happy path for Store deactivation, carts are cleared before buyers are notified.
+    // Store record is deactivated as the caller before anything is changed as superadmin,
+    // so users that may not deactivate the store are stopped before the saga touches their products
+    fn deactivate_store_happy(self, store_id: StoreId) -> ServiceFuture<Self, Store> {
+        Box::new(
+            self.get_store_with_products(store_id)
+                .and_then(move |(s, (_, products))| {
+                    s.check_open_orders_before_deactivation(store_id)
+                        .map(|(s, open_orders)| (s, products, open_orders))
+                })
+                .and_then(move |(s, products, open_orders)| {
+                    s.deactivate_store_record(store_id)
+                        .map(|(s, store)| (s, store, products, open_orders))
+                })
+                .and_then(move |(s, store, products, open_orders)| {
+                    let saved = s.save_base_product_deactivations(store_id, &products);
+                    saved.map(|(s, _)| (s, store, products, open_orders))
+                })
+                .and_then(|(s, store, products, open_orders)| {
+                    let product_ids = products.iter().map(|product| product.id).collect();
+                    s.clear_warehouses_stock(product_ids)
+                        .map(|(s, _)| (s, store, products, open_orders))
+                })
+                .and_then(|(s, store, products, open_orders)| {
+                    let base_product_ids = products.iter().map(|product| product.base_product_id).collect();
+                    s.deactivate_base_products(base_product_ids)
+                        .map(|(s, _)| (s, store, products, open_orders))
+                })
+                .and_then(|(s, store, products, open_orders)| {
+                    let product_ids = products.iter().map(|product| product.id).collect();
+                    s.remove_products_from_carts(product_ids)
+                        .map(|(s, _)| (s, store, products, open_orders))
+                })
+                .and_then(move |(s, store, products, open_orders)| {
+                    s.notify_buyers_store_deactivated(store_id, open_orders)
+                        .map(|(s, _)| (s, store, products))
+                })
+                .and_then(move |(s, store, products)| s.delete_shipping_after_deactivation(store_id, products).map(|(s, _)| (s, store))),
+        )
+    }
+
+    // Revokes store roles of the owner and of every staff member, so nobody keeps access to the deleted store
+    fn revoke_store_roles(self, store: &Store) -> ServiceFuture<Self, ()> {
+        let store_id = store.id;
+        let owner_id = store.user_id;
+        let staff = self.stores_microservice.get_store_staff(Some(Initiator::Superadmin), store_id);
+
+        Box::new(
+            staff
+                .then(|res| match res {
+                    Ok(staff) => Ok((self, staff)),
+                    Err(e) => Err((self, e)),
+                })
+                .and_then(move |(s, staff)| {
+                    let user_ids = staff
+                        .into_iter()
+                        .map(|staff_member| staff_member.user_id)
+                        .filter(|user_id| *user_id != owner_id)
+                        .collect::<Vec<_>>();
+                    iter_ok::<_, (Self, FailureError)>(Some(owner_id).into_iter().chain(user_ids))
+                        .fold(s, move |s, user_id| s.revoke_staff_roles(user_id, store_id).map(|(s, _)| s))
+                        .map(|s| (s, ()))
+                }),
+        )
+    }
+
+    // Contains happy path for Store deletion, the caller is authorized before anything is changed as superadmin
+    fn delete_happy(self, store_id: StoreId) -> ServiceFuture<Self, Store> {
+        Box::new(
+            self.authorize_store_management(store_id)
+                .and_then(move |(s, _)| s.get_store_with_products(store_id))
+                .and_then(move |(s, store_with_products)| s.check_no_open_orders(store_id).map(|(s, _)| (s, store_with_products)))
+                .and_then(move |(s, store_with_products)| s.check_no_pending_payouts(store_id).map(|(s, _)| (s, store_with_products)))
+                .and_then(move |(s, (store, products))| {
+                    let revoked = s.revoke_store_roles(&store);
+                    revoked.map(|(s, _)| (s, store, products))
+                })
+                .and_then(move |(s, store, products)| s.delete_merchant(store_id, store.country_code).map(|(s, _)| (s, products)))
+                .and_then(|(s, products)| {
+                    let product_ids = products.iter().map(|product| product.id).collect();
+                    s.clear_warehouses_stock(product_ids).map(|(s, _)| (s, products))
+                })
+                .and_then(move |(s, products)| s.delete_store_record(store_id).map(|(s, store)| (s, store, products)))
+                .and_then(move |(s, store, products)| s.delete_store_leftovers(store_id, products).map(|(s, _)| (s, store))),
+        )
+    }
+
+    fn check_new_owner(self, old_owner_id: UserId, new_owner_id: UserId) -> ServiceFuture<Self, ()> {
+        if old_owner_id == new_owner_id {
+            let message = format!("User {} already owns the store", new_owner_id);
+            return Box::new(future::err((
+                self,
+                Error::Validate(validation_errors!({"user_id": ["user_id" => message]})).into(),
+            )));
+        }
+
+        let res = self
+            .users_microservice
+            .get(Some(Initiator::Superadmin), new_owner_id)
+            .then(move |res| match res {
+                Ok(Some(_)) => Ok((self, ())),
+                Ok(None) => Err((
+                    self,
+                    format_err!("User {} is not found in users microservice.", new_owner_id)
+                        .context(Error::NotFound)
+                        .into(),
+                )),
+                Err(e) => Err((self, e)),
+            });
+
+        Box::new(res)
+    }
+
+    fn update_store_owner(self, store_id: StoreId, user_id: UserId) -> ServiceFuture<Self, Store> {
+        debug!("Updating owner of store {} to user {}", store_id, user_id);
+        let payload = UpdateStore { user_id: Some(user_id) };
+
+        let res = self
+            .stores_microservice
+            .update_store(Some(Initiator::Superadmin), store_id, payload)
+            .then(|res| match res {
+                Ok(store) => Ok((self, store)),
+                Err(e) => Err((self, e)),
+            });
+
+        Box::new(res)
+    }
+
+    // Contains happy path for Store ownership transfer, store record is updated last,
+    // so it never has to be reverted. The caller is authorized before any role is changed
+    fn transfer_ownership_happy(self, store_id: StoreId, new_owner_id: UserId) -> ServiceFuture<Self, Store> {
+        Box::new(
+            self.authorize_store_management(store_id)
+                .and_then(move |(s, _)| s.get_store(store_id))
+                .and_then(move |(s, store)| {
+                    let old_owner_id = store.user_id;
+                    s.check_new_owner(old_owner_id, new_owner_id).map(move |(s, _)| (s, old_owner_id))
+                })
+                .and_then(move |(s, old_owner_id)| {
+                    s.create_warehouses_role(new_owner_id, store_id)
+                        .map(move |(s, _)| (s, old_owner_id))
+                })
+                .and_then(move |(s, old_owner_id)| s.create_orders_role(new_owner_id, store_id).map(move |(s, _)| (s, old_owner_id)))
+                .and_then(move |(s, old_owner_id)| s.create_billing_role(new_owner_id, store_id).map(move |(s, _)| (s, old_owner_id)))
+                .and_then(move |(s, old_owner_id)| s.create_delivery_role(new_owner_id, store_id).map(move |(s, _)| (s, old_owner_id)))
+                .and_then(move |(s, old_owner_id)| {
+                    s.delete_warehouses_roles(old_owner_id, store_id)
+                        .map(move |(s, _)| (s, old_owner_id))
+                })
+                .and_then(move |(s, old_owner_id)| s.delete_orders_roles(old_owner_id, store_id).map(move |(s, _)| (s, old_owner_id)))
+                .and_then(move |(s, old_owner_id)| s.delete_billing_roles(old_owner_id, store_id).map(move |(s, _)| (s, old_owner_id)))
+                .and_then(move |(s, old_owner_id)| s.delete_delivery_roles(old_owner_id, store_id))
+                .and_then(move |(s, _)| s.update_store_owner(store_id, new_owner_id)),
+        )
+    }
+
+    // Contains reversal of Store ownership transfer: old owner roles are restored
+    // before roles of the new owner are removed
+    fn transfer_ownership_revert(self) -> impl Future<Item = (Self, ()), Error = (Self, FailureError)> {
+        self.delete_revert().then(|res| {
+            let s = match res {
+                Ok((s, _)) => s,
+                Err((s, _)) => s,
+            };
+            s.create_revert()
+        })
+    }
+
+    fn check_staff_member(self, store: &Store, payload: &NewStoreStaff) -> ServiceFuture<Self, ()> {
+        let user_id = payload.user_id;
+        let store_id = store.id;
+        if user_id == store.user_id {
+            let message = format!("User {} owns the store and already has access to it", user_id);
+            return Box::new(future::err((
+                self,
+                Error::Validate(validation_errors!({"user_id": ["user_id" => message]})).into(),
+            )));
+        }
+        if payload.profile.is_empty() {
+            return Box::new(future::err((
+                self,
+                Error::Validate(validation_errors!({"profile": ["profile" => "Profile must give access to at least one service"]})).into(),
+            )));
+        }
+
+        let res = self
+            .users_microservice
+            .get(Some(Initiator::Superadmin), user_id)
+            .then(move |res| match res {
+                Ok(Some(_)) => Ok((self, ())),
+                Ok(None) => Err((
+                    self,
+                    format_err!("User {} is not found in users microservice.", user_id)
+                        .context(Error::NotFound)
+                        .into(),
+                )),
+                Err(e) => Err((self, e)),
+            })
+            .and_then(move |(s, _)| {
+                s.stores_microservice
+                    .get_store_staff(Some(Initiator::Superadmin), store_id)
+                    .then(move |res| match res {
+                        Ok(ref staff) if staff.iter().any(|staff_member| staff_member.user_id == user_id) => {
+                            let message = format!("User {} is already a staff member of the store", user_id);
+                            Err((s, Error::Validate(validation_errors!({"user_id": ["user_id" => message]})).into()))
+                        }
+                        Ok(_) => Ok((s, ())),
+                        Err(e) => Err((s, e)),
+                    })
+            });
+
+        Box::new(res)
+    }
+
+    // Grants the same roles store owner gets on store creation, except the ones profile leaves out.
+    // Roles the user already has in the store are not granted again
+    fn grant_staff_roles(self, user_id: UserId, store_id: StoreId, profile: StaffProfile) -> ServiceFuture<Self, ()> {
+        let has_warehouses_role = self
+            .warehouses_microservice
+            .get_warehouse_roles_for_user(Some(Initiator::Superadmin), user_id)
+            .map(move |roles| roles.iter().any(|role| role.role.data == store_id));
+        let has_orders_role = self
+            .orders_microservice
+            .get_roles_for_user(Some(Initiator::Superadmin), user_id)
+            .map(move |roles| roles.iter().any(|role| role.role.data == store_id));
+        let has_billing_role = self
+            .billing_microservice
+            .get_roles_for_user(Some(Initiator::Superadmin), user_id)
+            .map(move |roles| roles.iter().any(|role| role.data == Some(store_id)));
+        let has_delivery_role = self
+            .delivery_microservice
+            .get_delivery_roles_for_user(Some(Initiator::Superadmin), user_id)
+            .map(move |roles| roles.iter().any(|role| role.data == Some(store_id)));
+
+        Box::new(
+            has_warehouses_role
+                .join4(has_orders_role, has_billing_role, has_delivery_role)
+                .then(|res| match res {
+                    Ok(existing_roles) => Ok((self, existing_roles)),
+                    Err(e) => Err((self, e)),
+                })
+                .and_then(
+                    move |(s, (has_warehouses_role, has_orders_role, has_billing_role, has_delivery_role))| {
+                        future::ok(s)
+                            .and_then(move |s| {
+                                if profile.warehouses && !has_warehouses_role {
+                                    Either::A(s.create_warehouses_role(user_id, store_id).map(|(s, _)| s))
+                                } else {
+                                    Either::B(future::ok(s))
+                                }
+                            })
+                            .and_then(move |s| {
+                                if profile.orders && !has_orders_role {
+                                    Either::A(s.create_orders_role(user_id, store_id).map(|(s, _)| s))
+                                } else {
+                                    Either::B(future::ok(s))
+                                }
+                            })
+                            .and_then(move |s| {
+                                if profile.billing && !has_billing_role {
+                                    Either::A(s.create_billing_role(user_id, store_id).map(|(s, _)| s))
+                                } else {
+                                    Either::B(future::ok(s))
+                                }
+                            })
+                            .and_then(move |s| {
+                                if profile.delivery && !has_delivery_role {
+                                    Either::A(s.create_delivery_role(user_id, store_id).map(|(s, _)| s))
+                                } else {
+                                    Either::B(future::ok(s))
+                                }
+                            })
+                            .map(|s| (s, ()))
+                    },
+                ),
+        )
+    }
+
+    // Revokes every store role of the user, whatever profile they were added with
+    fn revoke_staff_roles(self, user_id: UserId, store_id: StoreId) -> ServiceFuture<Self, ()> {
+        Box::new(
+            self.delete_warehouses_roles(user_id, store_id)
+                .and_then(move |(s, _)| s.delete_orders_roles(user_id, store_id))
+                .and_then(move |(s, _)| s.delete_billing_roles(user_id, store_id))
+                .and_then(move |(s, _)| s.delete_delivery_roles(user_id, store_id)),
+        )
+    }
+
+    fn notify_user_staff_invitation(
+        self,
+        user: EmailUser,
+        store_id: StoreId,
+        has_account: bool,
+    ) -> impl Future<Item = (Self, ()), Error = (Self, FailureError)> {
+        let email = StoreStaffInvitationForUser {
+            user,
+            store_id: store_id.to_string(),
+            has_account,
+            cluster_url: self.config.cluster.url.clone(),
+        };
+
+        self.notifications_microservice
+            .store_staff_invitation_for_user(Initiator::Superadmin, email)
+            .then(|res| match res {
+                Ok(_) => Ok((self, ())),
+                Err(e) => Err((self, e)),
+            })
+    }
+
+    // Membership is stored after the roles are granted, roles granted by this saga are revoked if it can not be stored
+    fn add_staff_happy(self, store_id: StoreId, payload: NewStoreStaff) -> ServiceFuture<Self, StoreStaffMember> {
+        let staff_member = StoreStaffMember {
+            store_id,
+            user_id: payload.user_id,
+            profile: payload.profile,
+        };
+
+        Box::new(
+            self.get_store(store_id)
+                .and_then(move |(s, store)| s.check_staff_member(&store, &payload))
+                .and_then(move |(s, _)| {
+                    s.grant_staff_roles(staff_member.user_id, store_id, staff_member.profile)
+                        .map(|(s, _)| (s, staff_member))
+                })
+                .and_then(|(s, staff_member)| {
+                    s.stores_microservice
+                        .add_store_staff_member(Some(Initiator::Superadmin), staff_member)
+                        .then(|res| match res {
+                            Ok(staff_member) => Ok((s, staff_member)),
+                            Err(e) => Err((s, e)),
+                        })
+                }),
+        )
+    }
+
+    // Invitation is sent after the user is added to the staff, failing to send it does not take the roles back.
+    // Users without an account are invited by email and join the staff when they verify it
+    fn invite_staff_happy(self, store_id: StoreId, payload: StoreStaffInvite) -> ServiceFuture<Self, StoreStaffInviteResult> {
+        let profile = payload.profile;
+        let email = payload.email.clone();
+
+        Box::new(
+            self.users_microservice
+                .get_by_email(Some(Initiator::Superadmin), &payload.email)
+                .then(|res| match res {
+                    Ok(user) => Ok((self, user)),
+                    Err(e) => Err((self, e)),
+                })
+                .and_then(move |(s, user)| match user {
+                    Some(user) => Either::A(s.invite_user(store_id, user, profile)),
+                    None => Either::B(s.invite_without_account(store_id, email, profile)),
+                }),
+        )
+    }
+
+    fn invite_user(self, store_id: StoreId, user: User, profile: StaffProfile) -> ServiceFuture<Self, StoreStaffInviteResult> {
+        let new_staff = NewStoreStaff { user_id: user.id, profile };
+        let email_user = EmailUser {
+            email: user.email.clone(),
+            first_name: user.first_name.unwrap_or_else(|| "user".to_string()),
+            last_name: user.last_name.unwrap_or_else(|| "".to_string()),
+        };
+
+        Box::new(
+            self.add_staff_happy(store_id, new_staff)
+                .and_then(move |(s, staff_member)| {
+                    s.notify_user_staff_invitation(email_user, store_id, true)
+                        .then(move |res| match res {
+                            Ok((s, _)) => Ok((s, staff_member)),
+                            Err((s, e)) => {
+                                warn!("Sending store {} staff invitation failed: {}", store_id, e);
+                                Ok((s, staff_member))
+                            }
+                        })
+                })
+                .map(|(s, staff_member)| (s, StoreStaffInviteResult::Added(staff_member))),
+        )
+    }
+
+    fn invite_without_account(
+        self,
+        store_id: StoreId,
+        email: String,
+        profile: StaffProfile,
+    ) -> ServiceFuture<Self, StoreStaffInviteResult> {
+        let invitation = StoreStaffInvitation {
+            id: Uuid::new_v4(),
+            store_id,
+            email: email.clone(),
+            profile,
+        };
+        let email_user = EmailUser {
+            email,
+            first_name: "user".to_string(),
+            last_name: "".to_string(),
+        };
+
+        Box::new(
+            self.get_store(store_id)
+                .and_then(move |(s, _)| {
+                    s.stores_microservice
+                        .create_store_staff_invitation(Some(Initiator::Superadmin), invitation)
+                        .then(|res| match res {
+                            Ok(invitation) => Ok((s, invitation)),
+                            Err(e) => Err((s, e)),
+                        })
+                })
+                .and_then(move |(s, invitation)| {
+                    s.notify_user_staff_invitation(email_user, store_id, false)
+                        .then(move |res| match res {
+                            Ok((s, _)) => Ok((s, invitation)),
+                            Err((s, e)) => {
+                                warn!("Sending store {} staff invitation failed: {}", store_id, e);
+                                Ok((s, invitation))
+                            }
+                        })
+                })
+                .map(|(s, invitation)| (s, StoreStaffInviteResult::Invited(invitation))),
+        )
+    }
+
+    // Invitations are accepted one by one, an invitation that could not be accepted is kept for the next try
+    fn accept_staff_invitations_happy(self, user_id: UserId, email: String) -> ServiceFuture<Self, Vec<StoreStaffMember>> {
+        let service = self.fork();
+
+        Box::new(
+            self.stores_microservice
+                .search_store_staff_invitations(Some(Initiator::Superadmin), StoreStaffInvitationSearch { email })
+                .and_then(move |invitations| {
+                    iter_ok::<_, FailureError>(invitations)
+                        .and_then(move |invitation| {
+                            let store_id = invitation.store_id;
+                            let invitation_id = invitation.id;
+                            let new_staff = NewStoreStaff {
+                                user_id,
+                                profile: invitation.profile,
+                            };
+                            service
+                                .fork()
+                                .add_staff_happy(store_id, new_staff)
+                                .and_then(move |(s, staff_member)| {
+                                    s.stores_microservice
+                                        .delete_store_staff_invitation(Some(Initiator::Superadmin), store_id, invitation_id)
+                                        .then(move |res| {
+                                            if let Err(e) = res {
+                                                error!("Deleting accepted staff invitation {} failed: {}", invitation_id, e);
+                                            }
+                                            Ok((s, staff_member))
+                                        })
+                                })
+                                .then(move |res| match res {
+                                    Ok((_, staff_member)) => Either::A(future::ok(Some(staff_member))),
+                                    Err((s, e)) => {
+                                        error!("Accepting staff invitation {} of store {} failed: {}", invitation_id, store_id, e);
+                                        Either::B(s.create_revert().then(|_| Ok::<_, FailureError>(None)))
+                                    }
+                                })
+                        })
+                        .filter_map(|staff_member| staff_member)
+                        .collect()
+                })
+                .then(|res| match res {
+                    Ok(staff) => Ok((self, staff)),
+                    Err(e) => Err((self, e)),
+                }),
+        )
+    }
+
+    // Membership is deleted after the roles are revoked, so a failed removal can be repeated
+    fn remove_staff_happy(self, store_id: StoreId, user_id: UserId) -> ServiceFuture<Self, ()> {
+        Box::new(
+            self.get_store(store_id)
+                .and_then(move |(s, store)| {
+                    if store.user_id == user_id {
+                        let message = format!("User {} owns the store, ownership must be transferred first", user_id);
+                        return Either::A(future::err((
+                            s,
+                            Error::Validate(validation_errors!({"user_id": ["user_id" => message]})).into(),
+                        )));
+                    }
+                    Either::B(s.revoke_staff_roles(user_id, store_id))
+                })
+                .and_then(move |(s, _)| {
+                    s.stores_microservice
+                        .delete_store_staff_member(Some(Initiator::Superadmin), store_id, user_id)
+                        .then(|res| match res {
+                            Ok(_) => Ok((s, ())),
+                            Err(e) => Err((s, e)),
+                        })
+                }),
+        )
+    }
+
+    fn get_staff_happy(self, store_id: StoreId) -> ServiceFuture<Self, Vec<StoreStaffMember>> {
+        Box::new(
+            self.stores_microservice
+                .get_store_staff(Some(Initiator::Superadmin), store_id)
+                .then(|res| match res {
+                    Ok(staff) => Ok((self, staff)),
+                    Err(e) => Err((self, e)),
+                }),
+        )
+    }
+
+    // Store that is already active is left as it is, so failed reactivation does not deactivate it
+    fn activate_store(self, store_id: StoreId) -> ServiceFuture<Self, Store> {
+        let log = self.reactivate_log.clone();
+        let stores_microservice = self.stores_microservice.clone();
+
+        let res = self
+            .stores_microservice
+            .get(store_id, Visibility::Active)
+            .and_then(move |store| match store {
+                Some(ref store) if store.is_active => Either::A(future::ok(store.clone())),
+                _ => Either::B(
+                    stores_microservice
+                        .activate_store(Some(Initiator::Superadmin), store_id)
+                        .map(move |store| {
+                            log.lock().unwrap().push(ReactivateStoreOperationStage::StoreActivated(store_id));
+                            store
+                        }),
+                ),
+            })
+            .then(|res| match res {
+                Ok(store) => Ok((self, store)),
+                Err(e) => Err((self, e)),
+            });
+
+        Box::new(res)
+    }
+
+    // Base product that is already active is left as it is, so failed reactivation does not deactivate it
+    fn activate_base_product(self, base_product_id: BaseProductId) -> ServiceFuture<Self, BaseProduct> {
+        let log = self.reactivate_log.clone();
+        let stores_microservice = self.stores_microservice.clone();
+
+        let res = self
+            .stores_microservice
+            .get_base_product(base_product_id, Visibility::Active)
+            .and_then(move |base_product| match base_product {
+                Some(ref base_product) if base_product.is_active => Either::A(future::ok(base_product.clone())),
+                _ => Either::B(
+                    stores_microservice
+                        .activate_base_product(Some(Initiator::Superadmin), base_product_id)
+                        .map(move |base_product| {
+                            log.lock()
+                                .unwrap()
+                                .push(ReactivateStoreOperationStage::BaseProductActivated(base_product_id));
+                            base_product
+                        }),
+                ),
+            })
+            .then(|res| match res {
+                Ok(base_product) => Ok((self, base_product)),
+                Err(e) => Err((self, e)),
+            });
+
+        Box::new(res)
+    }
+
+    fn restore_base_product_stock(self, base_product_id: BaseProductId, stocks: Vec<DeactivatedStock>) -> ServiceFuture<Self, ()> {
+        debug!("Restoring stock of base product {}, stocks: {:?}", base_product_id, stocks);
+        let warehouses_microservice = self.warehouses_microservice.clone();
+
+        let res = iter_ok::<_, FailureError>(stocks)
+            .for_each(move |stock| {
+                warehouses_microservice
+                    .set_product_in_warehouse(Initiator::Superadmin, stock.warehouse_id, stock.product_id, stock.quantity)
+                    .map(|_| ())
+            })
+            .then(|res| match res {
+                Ok(_) => Ok((self, ())),
+                Err(e) => Err((self, e)),
+            });
+
+        Box::new(res)
+    }
+
+    fn restore_base_product_shipping(self, base_product: BaseProduct, shipping: Option<Shipping>) -> ServiceFuture<Self, BaseProduct> {
+        let shipping = match shipping {
+            Some(shipping) => shipping,
+            None => return Box::new(future::ok((self, base_product))),
+        };
+        let base_product_id = base_product.id;
+        let measurements = base_product.measurements();
+        debug!("Restoring shipping of base product {}", base_product_id);
+
+        let delivery_microservice = self.delivery_microservice.clone();
+        let res = self
+            .warehouses_microservice
+            .find_by_store_id(Some(Initiator::Superadmin), base_product.store_id)
+            .and_then(move |warehouses| {
+                let delivery_from = warehouses.into_iter().filter_map(|warehouse| warehouse.country_code).next();
+                let items_count = shipping.items.len();
+                let shipping = shipping.into_new_shipping(measurements, delivery_from);
+                if shipping.items.len() < items_count {
+                    warn!(
+                        "Shipping items of base product {} are not restored, its measurements or warehouse country are not known",
+                        base_product_id
+                    );
+                }
+                delivery_microservice.upsert_shipping(Some(Initiator::Superadmin), base_product_id, shipping)
+            })
+            .then(|res| match res {
+                Ok(_) => Ok((self, base_product)),
+                Err(e) => Err((self, e)),
+            });
+
+        Box::new(res)
+    }
+
+    fn replay_base_product_deactivation(
+        self,
+        base_product: BaseProduct,
+        deactivation: BaseProductDeactivation,
+    ) -> ServiceFuture<Self, BaseProduct> {
+        let BaseProductDeactivation {
+            base_product_id,
+            stocks,
+            shipping,
+            ..
+        } = deactivation;
+
+        Box::new(
+            self.restore_base_product_stock(base_product_id, stocks)
+                .and_then(move |(s, _)| s.restore_base_product_shipping(base_product, shipping)),
+        )
+    }
+
+    // Deactivations are deleted only after the whole reactivation succeeded, so failed one can be retried.
+    // Failures are only logged, replaying deactivation again restores the same state
+    fn delete_base_product_deactivations(
+        self,
+        base_product_ids: Vec<BaseProductId>,
+    ) -> impl Future<Item = (Self, ()), Error = (Self, FailureError)> {
+        let deletions = base_product_ids
+            .into_iter()
+            .map(|base_product_id| {
+                self.stores_microservice
+                    .delete_base_product_deactivation(Some(Initiator::Superadmin), base_product_id)
+                    .then(move |res| {
+                        if let Err(e) = res {
+                            error!("Deleting deactivation of base product {} failed: {}", base_product_id, e);
+                        }
+                        Ok::<_, ()>(())
+                    })
+            })
+            .collect::<Vec<_>>();
+
+        join_all(deletions).then(|_| Ok::<_, (Self, FailureError)>((self, ())))
+    }
+
+    // Base products deactivated together with the store are activated back with their stock and shipping
+    fn reactivate_store_base_products(self, store_id: StoreId) -> ServiceFuture<Self, Vec<BaseProductId>> {
+        let res = self
+            .stores_microservice
+            .get_base_product_deactivations_by_store(Some(Initiator::Superadmin), store_id)
+            .then(|res| match res {
+                Ok(deactivations) => Ok((self, deactivations)),
+                Err(e) => Err((self, e)),
+            })
+            .and_then(|(s, deactivations)| {
+                let base_product_ids = deactivations
+                    .iter()
+                    .map(|deactivation| deactivation.base_product_id)
+                    .collect::<Vec<_>>();
+                iter_ok::<_, (Self, FailureError)>(deactivations)
+                    .fold(s, |s, deactivation| {
+                        s.activate_base_product(deactivation.base_product_id)
+                            .and_then(move |(s, base_product)| s.replay_base_product_deactivation(base_product, deactivation))
+                            .map(|(s, _)| s)
+                    })
+                    .map(|s| (s, base_product_ids))
+            });
+
+        Box::new(res)
+    }
+
+    fn send_store_to_remoderation(self, store: Store) -> ServiceFuture<Self, Store> {
+        if !is_moderation_required_after_reactivation(store.status) {
+            return Box::new(future::ok((self, store)));
+        }
+
+        Box::new(self.send_to_moderation(store.id).and_then(|(s, store)| {
+            s.send_to_moderator(ModerationSubject::Store(store.id), store.id)
+                .map(|(s, _)| (s, store))
+        }))
+    }
+
+    fn send_base_product_to_remoderation(self, base_product: BaseProduct) -> ServiceFuture<Self, BaseProduct> {
+        if !is_moderation_required_after_reactivation(base_product.status) {
+            return Box::new(future::ok((self, base_product)));
+        }
+
+        Box::new(self.send_to_moderation_base_product(base_product.id).and_then(|(s, base)| {
+            s.send_to_moderator(ModerationSubject::BaseProduct(base.id), base.store_id)
+                .map(|(s, _)| (s, base))
+        }))
+    }
+
+    // Contains happy path for Store reactivation, manager is told about the status the store ended up in
+    fn reactivate_store_happy(self, store_id: StoreId) -> ServiceFuture<Self, Store> {
+        Box::new(
+            self.activate_store(store_id)
+                .and_then(move |(s, store)| {
+                    s.reactivate_store_base_products(store_id)
+                        .map(|(s, base_product_ids)| (s, store, base_product_ids))
+                })
+                .and_then(|(s, store, base_product_ids)| s.send_store_to_remoderation(store).map(|(s, store)| (s, store, base_product_ids)))
+                .and_then(|(s, store, base_product_ids)| s.delete_base_product_deactivations(base_product_ids).map(|(s, _)| (s, store)))
+                .and_then(|(s, store)| {
+                    s.notify_manager_store_update_moderation_status(store.id, store.user_id, store.status, None, None)
+                        .then(move |res| match res {
+                            Ok((s, _)) => Ok((s, store)),
+                            Err((s, e)) => {
+                                warn!("Notifying manager about reactivation of store {} failed: {}", store.id, e);
+                                Ok((s, store))
+                            }
+                        })
+                }),
+        )
+    }
+
+    // Contains happy path for Base product reactivation, manager is told about the status the base product ended up in
+    fn reactivate_base_product_happy(self, base_product_id: BaseProductId) -> ServiceFuture<Self, BaseProduct> {
+        Box::new(
+            self.activate_base_product(base_product_id)
+                .and_then(move |(s, base)| {
+                    s.stores_microservice
+                        .get_base_product_deactivation(Some(Initiator::Superadmin), base_product_id)
+                        .then(move |res| match res {
+                            Ok(deactivation) => Ok((s, base, deactivation)),
+                            Err(e) => Err((s, e)),
+                        })
+                })
+                .and_then(|(s, base, deactivation)| match deactivation {
+                    Some(deactivation) => Either::A(s.replay_base_product_deactivation(base, deactivation)),
+                    None => Either::B(future::ok((s, base))),
+                })
+                .and_then(|(s, base)| s.send_base_product_to_remoderation(base))
+                .and_then(move |(s, base)| s.delete_base_product_deactivations(vec![base_product_id]).map(|(s, _)| (s, base)))
+                .and_then(|(s, base)| {
+                    s.notify_manager_base_product_update_moderation_status(base.store_id, base.id, base.status, None, None)
+                        .then(move |res| match res {
+                            Ok((s, _)) => Ok((s, base)),
+                            Err((s, e)) => {
+                                warn!("Notifying manager about reactivation of base product {} failed: {}", base.id, e);
+                                Ok((s, base))
+                            }
+                        })
+                }),
+        )
+    }
+
+    // Contains reversal of Store and Base product reactivation, deactivates back only what was activated by it.
+    // Restored stock and shipping of inactive entities are not used, so they are left in place
+    fn reactivate_revert(self) -> impl Future<Item = (Self, ()), Error = (Self, FailureError)> {
+        let log = self.reactivate_log.lock().unwrap().clone();
+
+        let stores_microservice = self.stores_microservice.clone();
+        let fut = iter_ok::<_, ()>(log.into_iter().rev()).for_each(move |e| match e {
+            ReactivateStoreOperationStage::StoreActivated(store_id) => {
+                debug!("Deactivating store back, store_id: {}", store_id);
+                Box::new(
+                    stores_microservice
+                        .deactivate_store(Some(Initiator::Superadmin), store_id)
+                        .then(|_| Ok(())),
+                ) as Box<Future<Item = (), Error = ()>>
+            }
+
+            ReactivateStoreOperationStage::BaseProductActivated(base_product_id) => {
+                debug!("Deactivating base product back, base_product_id: {}", base_product_id);
+                Box::new(
+                    stores_microservice
+                        .deactivate_base_product(Some(Initiator::Superadmin), base_product_id)
+                        .then(|_| Ok(())),
+                ) as Box<Future<Item = (), Error = ()>>
+            }
+        });
+
+        fut.then(|res| match res {
+            Ok(_) => Ok((self, ())),
+            Err(_) => Err((self, format_err!("Store service reactivate_revert error occurred."))),
+        })
+    }
+
+    // Contains reversal of Store deletion, restores resources in the reverse order of their deletion
+    fn delete_revert(self) -> impl Future<Item = (Self, ()), Error = (Self, FailureError)> {
+        let log = self.delete_log.lock().unwrap().clone();
+
+        let orders_microservice = self.orders_microservice.clone();
+        let stores_microservice = self.stores_microservice.clone();
+        let billing_microservice = self.billing_microservice.clone();
+        let warehouses_microservice = self.warehouses_microservice.clone();
+        let delivery_microservice = self.delivery_microservice.clone();
+        let fut = iter_ok::<_, ()>(log.into_iter().rev()).for_each(move |e| match e {
+            DeleteStoreOperationStage::WarehousesRoleDeleted(role) => {
+                debug!("Restoring warehouses role, role_id: {}", role.id);
+                Box::new(
+                    warehouses_microservice
+                        .create_warehouse_role(Some(Initiator::Superadmin), role)
+                        .then(|_| Ok(())),
+                ) as Box<Future<Item = (), Error = ()>>
+            }
+
+            DeleteStoreOperationStage::OrdersRoleDeleted(role) => {
+                debug!("Restoring orders role, role_id: {}", role.id);
+                Box::new(orders_microservice.create_role(Some(Initiator::Superadmin), role).then(|_| Ok(())))
+                    as Box<Future<Item = (), Error = ()>>
+            }
+
+            DeleteStoreOperationStage::BillingRoleDeleted(role) => {
+                debug!("Restoring billing role, role_id: {}", role.id);
+                Box::new(billing_microservice.create_role(Some(Initiator::Superadmin), role).then(|_| Ok(())))
+                    as Box<Future<Item = (), Error = ()>>
+            }
+
+            DeleteStoreOperationStage::DeliveryRoleDeleted(role) => {
+                debug!("Restoring delivery role, role_id: {}", role.id);
+                Box::new(
+                    delivery_microservice
+                        .create_delivery_role(Some(Initiator::Superadmin), role)
+                        .then(|_| Ok(())),
+                ) as Box<Future<Item = (), Error = ()>>
+            }
+
+            DeleteStoreOperationStage::BillingDeleteMerchantComplete(payload) => {
+                debug!("Restoring merchant, store_id: {}", payload.id);
+                Box::new(
+                    billing_microservice
+                        .create_store_merchant(Some(Initiator::Superadmin), payload)
+                        .then(|_| Ok(())),
+                ) as Box<Future<Item = (), Error = ()>>
+            }
+
+            DeleteStoreOperationStage::StoreDeactivated(store_id) => {
+                debug!("Reactivating store, store_id: {}", store_id);
+                Box::new(
+                    stores_microservice
+                        .activate_store(Some(Initiator::Superadmin), store_id)
+                        .then(|_| Ok(())),
+                ) as Box<Future<Item = (), Error = ()>>
+            }
+
+            DeleteStoreOperationStage::BaseProductDeactivated(base_product_id) => {
+                debug!("Reactivating base product, base_product_id: {}", base_product_id);
+                Box::new(
+                    stores_microservice
+                        .activate_base_product(Some(Initiator::Superadmin), base_product_id)
+                        .then(|_| Ok(())),
+                ) as Box<Future<Item = (), Error = ()>>
+            }
+
+            DeleteStoreOperationStage::BaseProductDeactivationSaved(base_product_id) => {
+                debug!("Deleting deactivation of base product, base_product_id: {}", base_product_id);
+                Box::new(
+                    stores_microservice
+                        .delete_base_product_deactivation(Some(Initiator::Superadmin), base_product_id)
+                        .then(|_| Ok(())),
+                ) as Box<Future<Item = (), Error = ()>>
+            }
+
+            DeleteStoreOperationStage::WarehouseStockCleared {
+                warehouse_id,
+                product_id,
+                quantity,
+            } => {
+                debug!("Restoring stock of product {} in warehouse {}", product_id, warehouse_id);
+                Box::new(
+                    warehouses_microservice
+                        .set_product_in_warehouse(Initiator::Superadmin, warehouse_id, product_id, quantity)
+                        .then(|_| Ok(())),
+                ) as Box<Future<Item = (), Error = ()>>
+            }
+        });
+
+        fut.then(|res| match res {
+            Ok(_) => Ok((self, ())),
+            Err(_) => Err((self, format_err!("Store service delete_revert error occurred."))),
+        })
+    }
+
+    fn set_store_moderation_status(self, payload: StoreModerate) -> ServiceFuture<Self, Store> {
+        let history_entry = payload.history_entry();
+        let res = self
+            .stores_microservice
+            .set_store_moderation_status(payload)
+            .then(|res| match res {
+                Ok(store) => Ok((self, store)),
+                Err(_) => Err((self, format_err!("Store service set_moderation_status error occurred."))),
+            })
+            .and_then(|(s, store)| {
+                s.finish_moderation(ModerationSubject::Store(store.id), store.status)
+                    .map(|(s, _)| (s, store))
+            })
+            .and_then(|(s, store)| s.record_moderation_history(history_entry).map(|(s, _)| (s, store)));
+
+        Box::new(res)
+    }
+
+    fn send_to_moderation(self, store_id: StoreId) -> ServiceFuture<Self, Store> {
+        let res = self.stores_microservice.send_to_moderation(store_id).then(|res| match res {
+            Ok(store) => Ok((self, store)),
+            Err(_) => Err((self, format_err!("Store service send_to_moderation error occurred."))),
+        });
+
+        Box::new(res)
+    }
+
+    fn set_moderation_status_base_product(self, payload: BaseProductModerate) -> ServiceFuture<Self, BaseProduct> {
+        // Moderator's decision on held changes of published base product
+        let base_product_id = payload.base_product_id;
+        let status = payload.status;
+        let history_entry = payload.history_entry();
+        let changes: ApiFuture<Option<UpdateBaseProduct>> = match status {
+            ModerationStatus::Published | ModerationStatus::Decline => self
+                .stores_microservice
+                .get_base_product_pending_changes(Some(Initiator::Superadmin), base_product_id),
+            _ => Box::new(future::ok(None)),
+        };
+
+        let res = changes
+            .then(|res| match res {
+                Ok(changes) => Ok((self, changes)),
+                Err(e) => Err((self, e)),
+            })
+            .and_then(move |(s, changes)| match (changes, status) {
+                (Some(changes), ModerationStatus::Published) => s.approve_base_product_changes(base_product_id, changes),
+                (Some(_), _) => s.decline_base_product_changes(base_product_id),
+                (None, _) => s.set_base_product_moderation_status(payload),
+            })
+            .and_then(|(s, base)| s.record_moderation_history(history_entry).map(|(s, _)| (s, base)));
+
+        Box::new(res)
+    }
+
+    fn set_base_product_moderation_status(self, payload: BaseProductModerate) -> ServiceFuture<Self, BaseProduct> {
+        let res = self
+            .stores_microservice
+            .set_moderation_status_base_product(payload)
+            .then(|res| match res {
+                Ok(base) => Ok((self, base)),
+                Err(_) => Err((
+                    self,
+                    format_err!("Store service set_moderation_status_base_product error occurred."),
+                )),
+            })
+            .and_then(|(s, base)| {
+                s.finish_moderation(ModerationSubject::BaseProduct(base.id), base.status)
+                    .map(|(s, _)| (s, base))
+            });
+
+        Box::new(res)
+    }
+
+    // Held changes are deleted only after they are applied, so moderator can approve them again if update fails
+    fn approve_base_product_changes(self, base_product_id: BaseProductId, changes: UpdateBaseProduct) -> ServiceFuture<Self, BaseProduct> {
+        let stores_microservice = self.stores_microservice.clone();
+        let update = changes.clone();
+
+        let res = self
+            .stores_microservice
+            .get_base_product(base_product_id, Visibility::Active)
+            .and_then(move |old_base_product| {
+                old_base_product.ok_or_else(|| {
+                    format_err!("Base product {} is not found in stores microservice.", base_product_id)
+                        .context(Error::NotFound)
+                        .into()
+                })
+            })
+            .and_then(move |old_base_product| {
+                stores_microservice
+                    .update_base_product(Some(Initiator::Superadmin), base_product_id, update)
+                    .map(|base_product| (old_base_product, base_product))
+            })
+            .then(move |res| match res {
+                Ok((old_base_product, base_product)) => Ok((self, old_base_product, base_product)),
+                Err(e) => Err((self, e)),
+            })
+            .and_then(move |(s, old_base_product, base_product)| {
+                s.finish_moderation(ModerationSubject::BaseProduct(base_product_id), base_product.status)
+                    .map(|(s, _)| (s, old_base_product, base_product))
+            })
+            .and_then(move |(s, old_base_product, base_product)| {
+                s.stores_microservice
+                    .delete_base_product_pending_changes(Some(Initiator::Superadmin), base_product_id)
+                    .then(move |res| {
+                        if let Err(e) = res {
+                            error!("Deleting approved changes of base product {} failed: {}", base_product_id, e);
+                        }
+                        Ok::<_, (Self, FailureError)>((s, old_base_product, base_product))
+                    })
+            })
+            .and_then(move |(s, old_base_product, base_product)| {
+                s.after_base_product_update(old_base_product, changes, base_product_id)
+                    .map(|(s, _)| (s, base_product))
+            });
+
+        Box::new(res)
+    }
+
+    // Declined changes are dropped, base product stays as it was approved
+    fn decline_base_product_changes(self, base_product_id: BaseProductId) -> ServiceFuture<Self, BaseProduct> {
+        let stores_microservice = self.stores_microservice.clone();
+
+        let res = self
+            .stores_microservice
+            .delete_base_product_pending_changes(Some(Initiator::Superadmin), base_product_id)
+            .and_then(move |_| stores_microservice.get_base_product(base_product_id, Visibility::Active))
+            .then(move |res| match res {
+                Ok(Some(base_product)) => Ok((self, base_product)),
+                Ok(None) => Err((
+                    self,
+                    format_err!("Base product {} is not found in stores microservice.", base_product_id)
+                        .context(Error::NotFound)
+                        .into(),
+                )),
+                Err(e) => Err((self, e)),
+            })
+            .and_then(move |(s, base_product)| {
+                s.finish_moderation(ModerationSubject::BaseProduct(base_product_id), ModerationStatus::Decline)
+                    .map(|(s, _)| (s, base_product))
+            });
+
+        Box::new(res)
+    }
+
+    // Newer changes override older ones held for the same base product
+    fn hold_base_product_changes(self, base_product_id: BaseProductId, changes: UpdateBaseProduct) -> ServiceFuture<Self, ()> {
+        debug!("Holding changes of published base product {} for moderation", base_product_id);
+        let stores_microservice = self.stores_microservice.clone();
+
+        let res = self
+            .stores_microservice
+            .get_base_product_pending_changes(Some(Initiator::Superadmin), base_product_id)
+            .and_then(move |held| {
+                let changes = held.unwrap_or_default().merge(changes);
+                stores_microservice.set_base_product_pending_changes(Some(Initiator::Superadmin), base_product_id, changes)
+            })
+            .then(|res| match res {
+                Ok(_) => Ok((self, ())),
+                Err(e) => Err((self, e)),
+            });
+
+        Box::new(res)
+    }
+
+    fn apply_base_product_update(self, old_base_product: BaseProduct, payload: UpdateBaseProduct) -> ServiceFuture<Self, BaseProduct> {
+        let base_product_id = old_base_product.id;
+        let payload_clone = payload.clone();
+
+        let res = self
+            .stores_microservice
+            .update_base_product(None, base_product_id, payload)
+            .then(move |res| match res {
+                Ok(base_product) => Ok((self, base_product)),
+                Err(e) => Err((self, e)),
+            })
+            .and_then(move |(s, base_product)| {
+                s.after_base_product_update(old_base_product, payload_clone, base_product_id)
+                    .map(|(s, _)| (s, base_product))
+            });
+
+        Box::new(res)
+    }
+
+    // Changes of sensitive fields of published base product are held until moderator approves them,
+    // the rest of the update is applied right away
+    fn update_published_base_product(self, old_base_product: BaseProduct, payload: UpdateBaseProduct) -> ServiceFuture<Self, BaseProduct> {
+        let (payload, changes) = payload.split_off(&self.config.moderation.sensitive_fields);
+        if changes.is_empty() {
+            return self.apply_base_product_update(old_base_product, payload);
+        }
+
+        let base_product_id = old_base_product.id;
+        let store_id = old_base_product.store_id;
+        let update: ServiceFuture<Self, BaseProduct> = if payload.is_empty() {
+            Box::new(future::ok((self, old_base_product)))
+        } else {
+            self.apply_base_product_update(old_base_product, payload)
+        };
+
+        Box::new(
+            update
+                .and_then(move |(s, base_product)| {
+                    s.hold_base_product_changes(base_product_id, changes)
+                        .map(|(s, _)| (s, base_product))
+                })
+                .and_then(move |(s, base_product)| {
+                    s.send_to_moderator(ModerationSubject::BaseProduct(base_product_id), store_id)
+                        .map(|(s, _)| (s, base_product))
+                }),
+        )
+    }
+
+    fn send_to_moderation_base_product(self, base_product_id: BaseProductId) -> ServiceFuture<Self, BaseProduct> {
+        let res = self
+            .stores_microservice
+            .send_to_moderation_base_product(base_product_id)
+            .then(|res| match res {
+                Ok(base) => Ok((self, base)),
+                Err(_) => Err((self, format_err!("Store service send_to_moderation_base_product error occurred."))),
+            });
+
+        Box::new(res)
+    }
+
+    fn notify_moderator_base_product_update_moderation_status(
+        self,
+        moderator_id: UserId,
+        store_id: StoreId,
+        base_product_id: BaseProductId,
+        status: ModerationStatus,
+    ) -> impl Future<Item = (Self, ()), Error = (Self, FailureError)> {
+        let notifications_microservice = self.notifications_microservice.clone();
+        let cluster_url = self.config.cluster.url.clone();
+
+        self.users_microservice
+            .get(Some(Initiator::Superadmin), moderator_id)
+            .and_then(move |moderator| {
+                if let Some(user) = moderator {
+                    let email_user = EmailUser {
+                        email: user.email.clone(),
+                        first_name: user.first_name.unwrap_or_else(|| "user".to_string()),
+                        last_name: user.last_name.unwrap_or_else(|| "".to_string()),
+                    };
+                    let email = BaseProductModerationStatusForModerator {
+                        user: email_user,
+                        store_id: store_id.to_string(),
+                        base_product_id: base_product_id.to_string(),
+                        cluster_url,
+                        status,
+                    };
+                    Either::A(
+                        notifications_microservice
+                            .base_product_moderation_status_for_moderator(Initiator::Superadmin, email)
+                            .then(|_| Ok(())),
+                    )
+                } else {
+                    Either::B(future::ok(()))
+                }
+            })
+            .then(|res| match res {
+                Ok(_) => Ok((self, ())),
+                Err(e) => Err((self, e)),
+            })
+    }
+
+    fn notify_manager_store_update_moderation_status(
+        self,
+        store_id: StoreId,
+        store_manager_id: UserId,
+        status: ModerationStatus,
+        reason: Option<ModerationRejectionReason>,
+        comment: Option<String>,
+    ) -> impl Future<Item = (Self, ()), Error = (Self, FailureError)> {
+        let cluster_url = self.config.cluster.url.clone();
+        let notifications_microservice = self.notifications_microservice.clone();
+        let users_microservice = self.users_microservice.clone();
+
+        let fut = Box::new(
+            users_microservice
+                .get(Some(Initiator::Superadmin), store_manager_id)
+                .and_then(move |store_manager| {
+                    if let Some(user) = store_manager {
+                        let email = StoreModerationStatusWithReasonForUser {
+                            store_email: user.email.to_string(),
+                            store_id: store_id.to_string(),
+                            cluster_url,
+                            status,
+                            reason,
+                            comment,
+                        };
+
+                        Either::A(
+                            notifications_microservice
+                                .store_moderation_status_for_user(Initiator::Superadmin, email)
+                                .then(|_| Ok(())),
+                        )
+                    } else {
+                        Either::B(future::ok(()))
+                    }
+                }),
+        ) as Box<Future<Item = (), Error = FailureError>>;
+
+        fut.then(|res| match res {
+            Ok(_) => Ok((self, ())),
+            Err(e) => Err((self, e)),
+        })
+    }
+
+    fn notify_manager_base_product_update_moderation_status(
+        self,
+        store_id: StoreId,
+        base_product_id: BaseProductId,
+        status: ModerationStatus,
+        reason: Option<ModerationRejectionReason>,
+        comment: Option<String>,
+    ) -> impl Future<Item = (Self, ()), Error = (Self, FailureError)> {
+        let cluster_url = self.config.cluster.url.clone();
+        let notifications_microservice = self.notifications_microservice.clone();
+        let users_microservice = self.users_microservice.clone();
+        let stores_microservice = self.stores_microservice.clone();
+
+        let fut = Box::new(
+            stores_microservice
+                .get(store_id, Visibility::Active)
+                .and_then(move |store| {
+                    store
+                        .ok_or_else(|| {
+                            error!(
+                                "Sending notification to store can not be done. Store with id: {} is not found.",
+                                store_id
+                            );
+                            format_err!("Store is not found in stores microservice.")
+                                .context(Error::NotFound)
+                                .into()
+                        })
+                        .into_future()
+                })
+                .and_then(move |store| {
+                    users_microservice
+                        .get(Some(Initiator::Superadmin), store.user_id)
+                        .and_then(move |store_manager| {
+                            if let Some(user) = store_manager {
+                                let email = BaseProductModerationStatusWithReasonForUser {
+                                    store_email: user.email.to_string(),
+                                    store_id: store_id.to_string(),
+                                    base_product_id: base_product_id.to_string(),
+                                    cluster_url,
+                                    status,
+                                    reason,
+                                    comment,
+                                };
+
+                                Either::A(
+                                    notifications_microservice
+                                        .base_product_moderation_status_for_user(Initiator::Superadmin, email)
+                                        .then(|_| Ok(())),
+                                )
+                            } else {
+                                Either::B(future::ok(()))
+                            }
+                        })
+                }),
+        ) as Box<Future<Item = (), Error = FailureError>>;
+
+        fut.then(|res| match res {
+            Ok(_) => Ok((self, ())),
+            Err(e) => Err((self, e)),
+        })
+    }
+
+    fn notify_moderator_store_update_moderation_status(
+        self,
+        moderator_id: UserId,
+        store_id: StoreId,
+        status: ModerationStatus,
+    ) -> impl Future<Item = (Self, ()), Error = (Self, FailureError)> {
+        let notifications_microservice = self.notifications_microservice.clone();
+        let cluster_url = self.config.cluster.url.clone();
+
+        self.users_microservice
+            .get(Some(Initiator::Superadmin), moderator_id)
+            .and_then(move |moderator| {
+                if let Some(user) = moderator {
+                    let email_user = EmailUser {
+                        email: user.email.clone(),
+                        first_name: user.first_name.unwrap_or_else(|| "user".to_string()),
+                        last_name: user.last_name.unwrap_or_else(|| "".to_string()),
+                    };
+                    let email = StoreModerationStatusForModerator {
+                        user: email_user,
+                        store_id: store_id.to_string(),
+                        cluster_url,
+                        status,
+                    };
+                    Either::A(
+                        notifications_microservice
+                            .store_moderation_status_for_moderator(Initiator::Superadmin, email)
+                            .then(|_| Ok(())),
+                    )
+                } else {
+                    Either::B(future::ok(()))
+                }
+            })
+            .then(|res| match res {
+                Ok(_) => Ok((self, ())),
+                Err(e) => Err((self, e)),
+            })
+    }
+
+    // Assignments are kept in stores microservice, so every coordinator instance sees the same queue
+    fn moderation_queue(&self) -> impl Future<Item = ModerationQueue, Error = FailureError> {
+        self.stores_microservice
+            .get_moderation_assignments(Some(Initiator::Superadmin))
+            .map(ModerationQueue::new)
+    }
+
+    fn save_moderation_assignments(
+        &self,
+        assignments: Vec<ModerationAssignment>,
+    ) -> impl Future<Item = Vec<ModerationAssignment>, Error = FailureError> {
+        join_all(
+            assignments
+                .into_iter()
+                .map(|assignment| {
+                    self.stores_microservice
+                        .set_moderation_assignment(Some(Initiator::Superadmin), assignment)
+                })
+                .collect::<Vec<_>>(),
+        )
+    }
+
+    fn assign_moderator(self, subject: ModerationSubject, store_id: StoreId) -> ServiceFuture<Self, Option<ModerationAssignment>> {
+        let stores_microservice = self.stores_microservice.clone();
+
+        let res = self
+            .stores_microservice
+            .get_moderators(Initiator::Superadmin)
+            .join(self.moderation_queue())
+            .and_then(
+                move |(moderators, mut queue)| match queue.assign(subject, store_id, &moderators, Utc::now()) {
+                    Some(assignment) => Either::A(
+                        stores_microservice
+                            .set_moderation_assignment(Some(Initiator::Superadmin), assignment)
+                            .map(Some),
+                    ),
+                    None => Either::B(future::ok(None)),
+                },
+            )
+            .then(|res| match res {
+                Ok(assignment) => Ok((self, assignment)),
+                Err(e) => Err((self, e)),
+            });
+
+        Box::new(res)
+    }
+
+    fn notify_assigned_moderator(self, assignment: ModerationAssignment) -> ServiceFuture<Self, ()> {
+        match assignment.subject {
+            ModerationSubject::Store(store_id) => Box::new(self.notify_moderator_store_update_moderation_status(
+                assignment.moderator_id,
+                store_id,
+                ModerationStatus::Moderation,
+            )),
+            ModerationSubject::BaseProduct(base_product_id) => Box::new(self.notify_moderator_base_product_update_moderation_status(
+                assignment.moderator_id,
+                assignment.store_id,
+                base_product_id,
+                ModerationStatus::Moderation,
+            )),
+        }
+    }
+
+    // Subject sent to moderation is assigned to a single moderator, only they are notified about it
+    fn send_to_moderator(self, subject: ModerationSubject, store_id: StoreId) -> ServiceFuture<Self, ()> {
+        Box::new(
+            self.assign_moderator(subject, store_id)
+                .and_then(move |(s, assignment)| match assignment {
+                    Some(assignment) => Either::A(s.notify_assigned_moderator(assignment)),
+                    None => {
+                        warn!("There are no moderators to assign {:?} to", subject);
+                        Either::B(future::ok((s, ())))
+                    }
+                }),
+        )
+    }
+
+    // Runs after moderation status is set, failures are only logged
+    fn record_moderation_history(self, entry: NewModerationHistoryEntry) -> impl Future<Item = (Self, ()), Error = (Self, FailureError)> {
+        let subject = entry.subject;
+        self.stores_microservice
+            .add_moderation_history_entry(Some(Initiator::Superadmin), entry)
+            .then(move |res| {
+                if let Err(e) = res {
+                    error!("Recording moderation history of {:?} failed: {}", subject, e);
+                }
+                Ok::<_, (Self, FailureError)>((self, ()))
+            })
+    }
+
+    // Runs after moderation status is set, failures are only logged
+    fn finish_moderation(
+        self,
+        subject: ModerationSubject,
+        status: ModerationStatus,
+    ) -> impl Future<Item = (Self, ()), Error = (Self, FailureError)> {
+        let completed: ApiFuture<Option<ModerationAssignment>> = if status != ModerationStatus::Moderation {
+            self.stores_microservice
+                .delete_moderation_assignment(Some(Initiator::Superadmin), subject)
+        } else {
+            Box::new(future::ok(None))
+        };
+
+        completed.then(move |res| {
+            match res {
+                Ok(Some(assignment)) => {
+                    let waited = Utc::now().signed_duration_since(assignment.enqueued_at);
+                    metrics::MODERATION_WAIT_SECONDS
+                        .with_label_values(&[subject.as_label()])
+                        .observe(waited.num_seconds() as f64);
+                }
+                Ok(None) => {}
+                Err(e) => error!("Completing moderation of {:?} failed: {}", subject, e),
+            }
+            Ok::<_, (Self, FailureError)>((self, ()))
+        })
+    }
+
+    fn products_to_remove_from_carts(
+        self,
+        subject: ModerationSubject,
+        initial_status: ModerationStatus,
+        status: ModerationStatus,
+    ) -> ServiceFuture<Self, Vec<ProductId>> {
+        if !is_status_change_requires_to_delete_product(initial_status, status) {
+            return Box::new(future::ok((self, vec![])));
+        }
+
+        let products = match subject {
+            ModerationSubject::Store(store_id) => self.stores_microservice.get_products_by_store(store_id),
+            ModerationSubject::BaseProduct(base_product_id) => self.stores_microservice.get_products_by_base_product(base_product_id),
+        };
+
+        Box::new(products.then(|res| match res {
+            Ok(products) => Ok((self, products.into_iter().map(|product| product.id).collect())),
+            Err(e) => Err((self, e)),
+        }))
+    }
+
+    fn moderate_store(self, payload: StoreModerate) -> ServiceFuture<Self, ModerationOutcome> {
+        if let Err(e) = validate_moderation_reason(payload.status, payload.reason) {
+            return Box::new(future::err((self, e)));
+        }
+
+        let subject = ModerationSubject::Store(payload.store_id);
+        let reason = payload.reason;
+        let comment = payload.comment.clone();
+        Box::new(
+            self.get_store(payload.store_id)
+                .and_then(move |(s, store)| {
+                    let initial_status = store.status;
+                    s.set_store_moderation_status(payload)
+                        .map(move |(s, store)| (s, store, initial_status))
+                })
+                .and_then(move |(s, store, initial_status)| {
+                    s.products_to_remove_from_carts(subject, initial_status, store.status)
+                        .map(move |(s, product_ids)| {
+                            let outcome = ModerationOutcome {
+                                subject,
+                                store_id: store.id,
+                                manager_id: Some(store.user_id),
+                                status: store.status,
+                                reason,
+                                comment,
+                                product_ids,
+                            };
+                            (s, outcome)
+                        })
+                }),
+        )
+    }
+
+    fn moderate_base_product(self, payload: BaseProductModerate) -> ServiceFuture<Self, ModerationOutcome> {
+        if let Err(e) = validate_moderation_reason(payload.status, payload.reason) {
+            return Box::new(future::err((self, e)));
+        }
+
+        let base_product_id = payload.base_product_id;
+        let subject = ModerationSubject::BaseProduct(base_product_id);
+        let status = payload.status;
+        let reason = payload.reason;
+        let comment = payload.comment.clone();
+        Box::new(
+            self.stores_microservice
+                .get_base_product(base_product_id, Visibility::Active)
+                .then(move |res| match res {
+                    Ok(Some(base_product)) => Ok((self, base_product.status)),
+                    Ok(None) => Err((
+                        self,
+                        format_err!("Base product {} is not found in stores microservice.", base_product_id)
+                            .context(Error::NotFound)
+                            .into(),
+                    )),
+                    Err(e) => Err((self, e)),
+                })
+                .and_then(move |(s, initial_status)| {
+                    s.set_moderation_status_base_product(payload)
+                        .map(move |(s, base_product)| (s, base_product, initial_status))
+                })
+                .and_then(move |(s, base_product, initial_status)| {
+                    s.products_to_remove_from_carts(subject, initial_status, base_product.status)
+                        .map(move |(s, product_ids)| {
+                            let outcome = ModerationOutcome {
+                                subject,
+                                store_id: base_product.store_id,
+                                manager_id: None,
+                                status,
+                                reason,
+                                comment,
+                                product_ids,
+                            };
+                            (s, outcome)
+                        })
+                }),
+        )
+    }
+
+    // Every store manager gets one email listing all of their moderated stores and base products,
+    // failures are only logged
+    fn notify_managers_moderation_digest(
+        self,
+        outcomes: Vec<ModerationOutcome>,
+    ) -> impl Future<Item = (Self, ()), Error = (Self, FailureError)> {
+        let mut managers = outcomes
+            .iter()
+            .filter_map(|outcome| outcome.manager_id.map(|manager_id| (outcome.store_id, manager_id)))
+            .collect::<HashMap<StoreId, UserId>>();
+        let unknown_store_ids = outcomes
+            .iter()
+            .map(|outcome| outcome.store_id)
+            .filter(|store_id| !managers.contains_key(store_id))
+            .collect::<HashSet<_>>();
+
+        let stores_microservice = self.stores_microservice.clone();
+        let lookups = unknown_store_ids
+            .into_iter()
+            .map(move |store_id| {
+                stores_microservice.get(store_id, Visibility::Active).then(move |res| match res {
+                    Ok(Some(store)) => Ok::<_, ()>(Some((store_id, store.user_id))),
+                    Ok(None) => {
+                        error!("Store {} is not found, its manager will not get moderation digest", store_id);
+                        Ok(None)
+                    }
+                    Err(e) => {
+                        error!("Getting store {} for moderation digest failed: {}", store_id, e);
+                        Ok(None)
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let notifications_microservice = self.notifications_microservice.clone();
+        let users_microservice = self.users_microservice.clone();
+        let cluster_url = self.config.cluster.url.clone();
+
+        join_all(lookups)
+            .and_then(move |found_managers| {
+                managers.extend(found_managers.into_iter().filter_map(|manager| manager));
+
+                let mut items_by_manager = HashMap::<UserId, Vec<ModerationStatusDigestItem>>::new();
+                for outcome in outcomes {
+                    if let Some(manager_id) = managers.get(&outcome.store_id) {
+                        let base_product_id = match outcome.subject {
+                            ModerationSubject::Store(_) => None,
+                            ModerationSubject::BaseProduct(base_product_id) => Some(base_product_id.to_string()),
+                        };
+                        items_by_manager
+                            .entry(*manager_id)
+                            .or_insert_with(Vec::new)
+                            .push(ModerationStatusDigestItem {
+                                store_id: outcome.store_id.to_string(),
+                                base_product_id,
+                                status: outcome.status,
+                                reason: outcome.reason,
+                                comment: outcome.comment,
+                            });
+                    }
+                }
+
+                let notifications = items_by_manager
+                    .into_iter()
+                    .map(|(manager_id, items)| {
+                        let notifications_microservice = notifications_microservice.clone();
+                        let cluster_url = cluster_url.clone();
+                        users_microservice
+                            .get(Some(Initiator::Superadmin), manager_id)
+                            .and_then(move |manager| {
+                                manager.ok_or_else(|| {
+                                    format_err!("User {} is not found in users microservice.", manager_id)
+                                        .context(Error::NotFound)
+                                        .into()
+                                })
+                            })
+                            .and_then(move |manager| {
+                                let email = ModerationStatusDigestForUser {
+                                    store_email: manager.email.to_string(),
+                                    items,
+                                    cluster_url,
+                                };
+                                notifications_microservice.moderation_status_digest_for_user(Initiator::Superadmin, email)
+                            })
+                            .then(move |res| {
+                                if let Err(e) = res {
+                                    error!("Sending moderation digest to manager {} failed: {}", manager_id, e);
+                                }
+                                Ok::<_, ()>(())
+                            })
+                    })
+                    .collect::<Vec<_>>();
+
+                join_all(notifications)
+            })
+            .then(|_: Result<Vec<()>, ()>| Ok((self, ())))
+    }
+
+    // Items are moderated independently, products of all of them are removed from carts in one call
+    fn bulk_moderate_happy(self, payload: BulkModeratePayload) -> ServiceFuture<Self, Vec<BulkModerateResult>> {
+        let concurrency = self.config.service.bulk_concurrency;
+        let service = self.fork();
+
+        let res = iter_ok::<_, FailureError>(payload.into_items())
+            .map(move |item| {
+                let subject = item.subject();
+                let moderation = match item {
+                    ModerateItem::Store(payload) => service.fork().moderate_store(payload),
+                    ModerateItem::BaseProduct(payload) => service.fork().moderate_base_product(payload),
+                };
+                moderation.then(move |res| match res {
+                    Ok((_, outcome)) => Ok::<_, FailureError>(Ok(outcome)),
+                    Err((_, e)) => {
+                        error!("Bulk moderation of {:?} failed: {}", subject, e);
+                        Ok(Err((subject, e.to_string())))
+                    }
+                })
+            })
+            .buffered(concurrency)
+            .collect()
+            .then(|res| match res {
+                Ok(outcomes) => Ok((self, outcomes)),
+                Err(e) => Err((self, e)),
+            })
+            .and_then(
+                |(s, outcomes): (Self, Vec<Result<ModerationOutcome, (ModerationSubject, String)>>)| {
+                    let product_ids = outcomes
+                        .iter()
+                        .filter_map(|outcome| outcome.as_ref().ok())
+                        .flat_map(|outcome| outcome.product_ids.clone())
+                        .collect::<Vec<_>>();
+                    if product_ids.is_empty() {
+                        return Either::A(future::ok((s, outcomes)));
+                    }
+
+                    Either::B(s.remove_products_from_carts(product_ids).then(move |res| match res {
+                        Ok((s, _)) => Ok::<_, (Self, FailureError)>((s, outcomes)),
+                        Err((s, e)) => {
+                            error!("Removing products from carts after bulk moderation failed: {}", e);
+                            Ok((s, outcomes))
+                        }
+                    }))
+                },
+            )
+            .and_then(|(s, outcomes)| {
+                let moderated = outcomes
+                    .iter()
+                    .filter_map(|outcome| outcome.as_ref().ok())
+                    .cloned()
+                    .collect::<Vec<_>>();
+                s.notify_managers_moderation_digest(moderated).map(|(s, _)| (s, outcomes))
+            })
+            .map(|(s, outcomes)| {
+                let results = outcomes
+                    .into_iter()
+                    .map(|outcome| match outcome {
+                        Ok(outcome) => BulkModerateResult {
+                            subject: outcome.subject,
+                            status: Some(outcome.status),
+                            error: None,
+                        },
+                        Err((subject, error)) => BulkModerateResult {
+                            subject,
+                            status: None,
+                            error: Some(error),
+                        },
+                    })
+                    .collect();
+                (s, results)
+            });
+
+        Box::new(res)
+    }
+
+    fn reassign_expired_moderations(self) -> ServiceFuture<Self, Vec<ModerationAssignment>> {
+        let timeout = Duration::hours(self.config.moderation.assignment_timeout_h);
+        let stores_microservice = self.stores_microservice.clone();
+
+        let res = self
+            .moderation_queue()
+            .and_then(move |queue| {
+                let expired = queue.expired(Utc::now(), timeout);
+                if expired.is_empty() {
+                    return Either::A(future::ok(vec![]));
+                }
+                Either::B(stores_microservice.get_moderators(Initiator::Superadmin).map(move |moderators| {
+                    let mut queue = queue;
+                    let now = Utc::now();
+                    expired
+                        .into_iter()
+                        .filter_map(|subject| queue.reassign(subject, &moderators, now))
+                        .collect::<Vec<_>>()
+                }))
+            })
+            .then(|res| match res {
+                Ok(assignments) => Ok((self, assignments)),
+                Err(e) => Err((self, e)),
+            })
+            .and_then(|(s, assignments)| {
+                let saved = s.save_moderation_assignments(assignments);
+                saved.then(|res| match res {
+                    Ok(assignments) => Ok((s, assignments)),
+                    Err(e) => Err((s, e)),
+                })
+            })
+            .and_then(|(s, assignments)| {
+                iter_ok::<_, (Self, FailureError)>(assignments.clone())
+                    .fold(s, |s, assignment| {
+                        let subject = assignment.subject;
+                        s.notify_assigned_moderator(assignment).then(move |res| match res {
+                            Ok((s, _)) => Ok::<_, (Self, FailureError)>(s),
+                            Err((s, e)) => {
+                                warn!("Notifying moderator about reassigned {:?} failed: {}", subject, e);
+                                Ok(s)
+                            }
+                        })
+                    })
+                    .map(|s| (s, assignments))
+            });
+
+        Box::new(res)
+    }
+
+    fn notify_moderator_moderation_overdue(
+        &self,
+        moderator_id: UserId,
+        assignment: &ModerationAssignment,
+        escalated: bool,
+    ) -> impl Future<Item = (), Error = FailureError> {
+        let notifications_microservice = self.notifications_microservice.clone();
+        let cluster_url = self.config.cluster.url.clone();
+        let subject = assignment.subject;
+        let store_id = assignment.store_id;
+        let waiting_hours = Utc::now().signed_duration_since(assignment.enqueued_at).num_hours();
+
+        self.users_microservice
+            .get(Some(Initiator::Superadmin), moderator_id)
+            .and_then(move |moderator| {
+                moderator.ok_or_else(|| {
+                    format_err!("User {} is not found in users microservice.", moderator_id)
+                        .context(Error::NotFound)
+                        .into()
+                })
+            })
+            .and_then(move |moderator| {
+                let email = ModerationOverdueForModerator {
+                    user: EmailUser {
+                        email: moderator.email.clone(),
+                        first_name: moderator.first_name.unwrap_or_else(|| "user".to_string()),
+                        last_name: moderator.last_name.unwrap_or_else(|| "".to_string()),
+                    },
+                    subject,
+                    store_id: store_id.to_string(),
+                    waiting_hours,
+                    escalated,
+                    cluster_url,
+                };
+                notifications_microservice.moderation_overdue_for_moderator(Initiator::Superadmin, email)
+            })
+    }
+
+    // Reminder is marked in stores microservice only after it is delivered, undelivered one is sent again on the next run
+    fn remind_overdue_moderations(self) -> ServiceFuture<Self, Vec<ModerationAssignment>> {
+        let sla = Duration::hours(self.config.moderation.reminder_after_h);
+
+        let res = self
+            .moderation_queue()
+            .map(move |mut queue| queue.due_reminders(Utc::now(), sla))
+            .then(|res| match res {
+                Ok(due) => Ok((self, due)),
+                Err(e) => Err((self, e)),
+            })
+            .and_then(|(s, due)| {
+                let reminders = due
+                    .into_iter()
+                    .map(|assignment| {
+                        let stores_microservice = s.stores_microservice.clone();
+                        let subject = assignment.subject;
+                        let moderator_id = assignment.moderator_id;
+                        let notified = s.notify_moderator_moderation_overdue(moderator_id, &assignment, false);
+                        notified
+                            .and_then(move |_| stores_microservice.set_moderation_assignment(Some(Initiator::Superadmin), assignment))
+                            .then(move |res| match res {
+                                Ok(assignment) => Ok::<_, ()>(Some(assignment)),
+                                Err(e) => {
+                                    error!("Reminding moderator {} about overdue {:?} failed: {}", moderator_id, subject, e);
+                                    Ok(None)
+                                }
+                            })
+                    })
+                    .collect::<Vec<_>>();
+
+                join_all(reminders).then(move |res| {
+                    let reminded: Vec<ModerationAssignment> = res.unwrap_or_default().into_iter().flatten().collect();
+                    Ok::<_, (Self, FailureError)>((s, reminded))
+                })
+            });
+
+        Box::new(res)
+    }
+
+    // Every moderator hears about every escalated subject, not only the assigned one.
+    // Escalation is marked once at least one moderator got it, otherwise it is sent again on the next run
+    fn escalate_overdue_moderations(self) -> ServiceFuture<Self, Vec<ModerationAssignment>> {
+        let threshold = Duration::hours(self.config.moderation.escalation_after_h);
+        let stores_microservice = self.stores_microservice.clone();
+
+        let res = self
+            .moderation_queue()
+            .and_then(move |mut queue| {
+                let due = queue.due_escalations(Utc::now(), threshold);
+                if due.is_empty() {
+                    return Either::A(future::ok((vec![], vec![])));
+                }
+                Either::B(
+                    stores_microservice
+                        .get_moderators(Initiator::Superadmin)
+                        .map(move |moderators| (moderators, due)),
+                )
+            })
+            .then(|res| match res {
+                Ok((moderators, due)) => Ok((self, moderators, due)),
+                Err(e) => Err((self, e)),
+            })
+            .and_then(|(s, moderators, due)| {
+                let escalations = due
+                    .into_iter()
+                    .map(|assignment| {
+                        let stores_microservice = s.stores_microservice.clone();
+                        let subject = assignment.subject;
+                        let deliveries = moderators
+                            .iter()
+                            .map(|moderator_id| {
+                                let moderator_id = *moderator_id;
+                                s.notify_moderator_moderation_overdue(moderator_id, &assignment, true)
+                                    .then(move |res| match res {
+                                        Ok(_) => Ok::<_, ()>(true),
+                                        Err(e) => {
+                                            error!("Notifying moderator {} about escalated {:?} failed: {}", moderator_id, subject, e);
+                                            Ok(false)
+                                        }
+                                    })
+                            })
+                            .collect::<Vec<_>>();
+
+                        join_all(deliveries).and_then(move |delivered| {
+                            if !delivered.into_iter().any(|delivered| delivered) {
+                                return Either::A(future::ok(None));
+                            }
+                            Either::B(
+                                stores_microservice
+                                    .set_moderation_assignment(Some(Initiator::Superadmin), assignment)
+                                    .then(move |res| match res {
+                                        Ok(assignment) => Ok::<_, ()>(Some(assignment)),
+                                        Err(e) => {
+                                            error!("Marking {:?} as escalated failed: {}", subject, e);
+                                            Ok(None)
+                                        }
+                                    }),
+                            )
+                        })
+                    })
+                    .collect::<Vec<_>>();
+
+                join_all(escalations).then(move |res| {
+                    let escalated: Vec<ModerationAssignment> = res.unwrap_or_default().into_iter().flatten().collect();
+                    Ok::<_, (Self, FailureError)>((s, escalated))
+                })
+            });
+
+        Box::new(res)
+    }
+
+    fn remove_products_from_cart_after_base_product_status_change(
+        self,
+        base_product_id: BaseProductId,
         initial_status: ModerationStatus,
         status: ModerationStatus,
     ) -> impl Future<Item = (Self, ()), Error = (Self, FailureError)> {
@@ -713,18 +2953,9 @@ impl StoreServiceImpl {
             })
     }
 
-    fn remove_products_from_cart_after_store_deactivation(
-        self,
-        store_id: StoreId,
-    ) -> impl Future<Item = (Self, ()), Error = (Self, FailureError)> {
-        let stores_microservice = self.stores_microservice.clone();
-        let orders_microservice = self.orders_microservice.clone();
-        stores_microservice
-            .get_products_by_store(store_id)
-            .map(|products| DeleteProductsFromCartsPayload {
-                product_ids: products.into_iter().map(|p| p.id).collect(),
-            })
-            .and_then(move |payload| orders_microservice.delete_products_from_all_carts(Some(Initiator::Superadmin), payload))
+    fn remove_products_from_carts(self, product_ids: Vec<ProductId>) -> impl Future<Item = (Self, ()), Error = (Self, FailureError)> {
+        self.orders_microservice
+            .delete_products_from_all_carts(Some(Initiator::Superadmin), DeleteProductsFromCartsPayload { product_ids })
             .then(|res| match res {
                 Ok(_) => Ok((self, ())),
                 Err(err) => Err((self, err)),
@@ -823,6 +3054,23 @@ impl StoreServiceImpl {
     }
 }
 
+fn open_orders_error(store_id: StoreId, open_orders: usize) -> FailureError {
+    let message = format!(
+        "Store {} has {} open orders, they must be completed or cancelled first",
+        store_id, open_orders
+    );
+    Error::Validate(validation_errors!({"store": ["open_orders" => message]})).into()
+}
+
+/// Rejection reason is given only when store or base product is declined or blocked
+fn validate_moderation_reason(status: ModerationStatus, reason: Option<ModerationRejectionReason>) -> Result<(), FailureError> {
+    if reason.is_some() && !is_rejection_status(status) {
+        let message = format!("Rejection reason can not be given with moderation status {:?}", status);
+        return Err(Error::Validate(validation_errors!({"reason": ["reason" => message]})).into());
+    }
+    Ok(())
+}
+
 fn is_status_change_requires_to_delete_product(initial_status: ModerationStatus, status: ModerationStatus) -> bool {
     match (initial_status, status) {
         (ModerationStatus::Published, status) if status != ModerationStatus::Published => true,
@@ -866,6 +3114,12 @@ impl StoreService for StoreServiceImpl {
     }
 
     fn set_store_moderation_status(self, payload: StoreModerate) -> ServiceFuture<Box<StoreService>, Store> {
+        if let Err(e) = validate_moderation_reason(payload.status, payload.reason) {
+            return Box::new(future::err((Box::new(self) as Box<StoreService>, e)));
+        }
+
+        let reason = payload.reason;
+        let comment = payload.comment.clone();
         Box::new(
             self.stores_microservice
                 .get(payload.store_id, Visibility::Active)
@@ -887,8 +3141,8 @@ impl StoreService for StoreServiceImpl {
                     s.remove_products_from_cart_after_store_status_change(store.id, initial_status, store.status)
                         .map(|(s, _)| (s, store))
                 })
-                .and_then(|(s, store)| {
-                    s.notify_manager_store_update_moderation_status(store.id, store.user_id, store.status)
+                .and_then(move |(s, store)| {
+                    s.notify_manager_store_update_moderation_status(store.id, store.user_id, store.status, reason, comment)
                         .map(|(s, _)| (s, store))
                 })
                 .map(|(s, store)| (Box::new(s) as Box<StoreService>, store))
@@ -901,7 +3155,7 @@ impl StoreService for StoreServiceImpl {
         Box::new(
             self.send_to_moderation(store_id)
                 .and_then(|(s, store)| {
-                    s.notify_moderators_store_update_moderation_status(store.id, store.status)
+                    s.send_to_moderator(ModerationSubject::Store(store.id), store.id)
                         .map(|(s, _)| (s, store))
                 })
                 .map(|(s, store)| (Box::new(s) as Box<StoreService>, store))
@@ -911,6 +3165,13 @@ impl StoreService for StoreServiceImpl {
 
     /// Set moderation status for base_product_id
     fn set_moderation_status_base_product(self, payload: BaseProductModerate) -> ServiceFuture<Box<StoreService>, ()> {
+        if let Err(e) = validate_moderation_reason(payload.status, payload.reason) {
+            return Box::new(future::err((Box::new(self) as Box<StoreService>, e)));
+        }
+
+        let status = payload.status;
+        let reason = payload.reason;
+        let comment = payload.comment.clone();
         Box::new(
             self.stores_microservice
                 .get_base_product(payload.base_product_id, Visibility::Active)
@@ -932,8 +3193,8 @@ impl StoreService for StoreServiceImpl {
                     s.remove_products_from_cart_after_base_product_status_change(base_product.id, initial_status, base_product.status)
                         .map(|(s, _)| (s, base_product))
                 })
-                .and_then(|(s, base)| {
-                    s.notify_manager_base_product_update_moderation_status(base.store_id, base.id, base.status)
+                .and_then(move |(s, base)| {
+                    s.notify_manager_base_product_update_moderation_status(base.store_id, base.id, status, reason, comment)
                         .map(|(s, _)| (s, ()))
                 })
                 .map(|(s, _)| (Box::new(s) as Box<StoreService>, ()))
@@ -946,7 +3207,7 @@ impl StoreService for StoreServiceImpl {
         Box::new(
             self.send_to_moderation_base_product(base_product_id)
                 .and_then(|(s, base)| {
-                    s.notify_moderators_base_product_update_moderation_status(base.store_id, base.id, base.status)
+                    s.send_to_moderator(ModerationSubject::BaseProduct(base.id), base.store_id)
                         .map(|(s, _)| (s, ()))
                 })
                 .map(|(s, _)| (Box::new(s) as Box<StoreService>, ()))
@@ -972,21 +3233,54 @@ impl StoreService for StoreServiceImpl {
         )
     }
 
-    /// Deactivate store
+    /// Deactivate store together with its base products, shipping and stock
     fn deactivate_store(self, store_id: StoreId) -> ServiceFuture<Box<StoreService>, Store> {
         Box::new(
-            self.stores_microservice
-                .deactivate_store(None, store_id)
-                .then(move |res| match res {
-                    Ok(store) => Ok((self, store)),
-                    Err(err) => Err((self, err)),
-                })
-                .and_then(move |(s, store)| {
-                    s.remove_products_from_cart_after_store_deactivation(store_id)
-                        .map(move |(s, _)| (s, store))
-                })
+            self.deactivate_store_happy(store_id)
                 .map(|(s, store)| (Box::new(s) as Box<StoreService>, store))
-                .or_else(|(s, e)| future::err((Box::new(s) as Box<StoreService>, e))),
+                .or_else(move |(s, e)| {
+                    s.delete_revert().then(move |res| {
+                        let s = match res {
+                            Ok((s, _)) => s,
+                            Err((s, _)) => s,
+                        };
+                        futures::future::err((Box::new(s) as Box<StoreService>, e))
+                    })
+                }),
+        )
+    }
+
+    /// Reactivate store together with base products, stock and shipping taken away by its deactivation
+    fn reactivate_store(self, store_id: StoreId) -> ServiceFuture<Box<StoreService>, Store> {
+        Box::new(
+            self.reactivate_store_happy(store_id)
+                .map(|(s, store)| (Box::new(s) as Box<StoreService>, store))
+                .or_else(move |(s, e)| {
+                    s.reactivate_revert().then(move |res| {
+                        let s = match res {
+                            Ok((s, _)) => s,
+                            Err((s, _)) => s,
+                        };
+                        futures::future::err((Box::new(s) as Box<StoreService>, e))
+                    })
+                }),
+        )
+    }
+
+    /// Reactivate base product restoring warehouse and shipping state it had before store deactivation
+    fn reactivate_base_product(self, base_product_id: BaseProductId) -> ServiceFuture<Box<StoreService>, BaseProduct> {
+        Box::new(
+            self.reactivate_base_product_happy(base_product_id)
+                .map(|(s, base_product)| (Box::new(s) as Box<StoreService>, base_product))
+                .or_else(move |(s, e)| {
+                    s.reactivate_revert().then(move |res| {
+                        let s = match res {
+                            Ok((s, _)) => s,
+                            Err((s, _)) => s,
+                        };
+                        futures::future::err((Box::new(s) as Box<StoreService>, e))
+                    })
+                }),
         )
     }
 
@@ -1024,24 +3318,20 @@ impl StoreService for StoreServiceImpl {
         base_product_id: BaseProductId,
         payload: UpdateBaseProduct,
     ) -> ServiceFuture<Box<StoreService>, BaseProduct> {
-        let stores_microservice = self.stores_microservice.clone();
-        let payload_clone = payload.clone();
         Box::new(
             self.stores_microservice
                 .get_base_product(base_product_id, Visibility::Active)
                 .and_then(move |old_base_product| old_base_product.ok_or(format_err!("Could not find base product {}", base_product_id)))
-                .and_then(move |old_base_product| {
-                    stores_microservice
-                        .update_base_product(None, base_product_id, payload)
-                        .map(move |base_product| (old_base_product, base_product))
-                })
                 .then(move |res| match res {
-                    Ok((old_base_product, base_product)) => Ok((self, old_base_product, base_product)),
+                    Ok(old_base_product) => Ok((self, old_base_product)),
                     Err(err) => Err((self, err)),
                 })
-                .and_then(move |(s, old_base_product, base_product)| {
-                    s.after_base_product_update(old_base_product, payload_clone, base_product_id)
-                        .map(|(s, _)| (s, base_product))
+                .and_then(move |(s, old_base_product)| {
+                    if old_base_product.status == ModerationStatus::Published {
+                        s.update_published_base_product(old_base_product, payload)
+                    } else {
+                        s.apply_base_product_update(old_base_product, payload)
+                    }
                 })
                 .map(|(s, base_product)| (Box::new(s) as Box<StoreService>, base_product))
                 .or_else(|(s, e)| future::err((Box::new(s) as Box<StoreService>, e))),
@@ -1076,6 +3366,185 @@ impl StoreService for StoreServiceImpl {
                 .or_else(|(s, e)| future::err((Box::new(s) as Box<StoreService>, e))),
         )
     }
+
+    /// Move store to another owner together with its roles in other microservices
+    fn transfer_ownership(self, store_id: StoreId, payload: TransferStoreOwnership) -> ServiceFuture<Box<StoreService>, Store> {
+        Box::new(
+            self.transfer_ownership_happy(store_id, payload.user_id)
+                .map(|(s, store)| (Box::new(s) as Box<StoreService>, store))
+                .or_else(move |(s, e)| {
+                    s.transfer_ownership_revert().then(move |res| {
+                        let s = match res {
+                            Ok((s, _)) => s,
+                            Err((s, _)) => s,
+                        };
+                        futures::future::err((Box::new(s) as Box<StoreService>, e))
+                    })
+                }),
+        )
+    }
+
+    /// Add user to store staff, only store owner and superadmin may do it
+    fn add_staff(self, store_id: StoreId, payload: NewStoreStaff) -> ServiceFuture<Box<StoreService>, StoreStaffMember> {
+        Box::new(
+            self.authorize_store_management(store_id)
+                .and_then(move |(s, _)| s.add_staff_happy(store_id, payload))
+                .map(|(s, staff_member)| (Box::new(s) as Box<StoreService>, staff_member))
+                .or_else(move |(s, e)| {
+                    s.create_revert().then(move |res| {
+                        let s = match res {
+                            Ok((s, _)) => s,
+                            Err((s, _)) => s,
+                        };
+                        futures::future::err((Box::new(s) as Box<StoreService>, e))
+                    })
+                }),
+        )
+    }
+
+    /// Add user found by email to store staff or invite them to sign up, and tell them about it.
+    /// Only store owner and superadmin may do it
+    fn invite_staff(self, store_id: StoreId, payload: StoreStaffInvite) -> ServiceFuture<Box<StoreService>, StoreStaffInviteResult> {
+        Box::new(
+            self.authorize_store_management(store_id)
+                .and_then(move |(s, _)| s.invite_staff_happy(store_id, payload))
+                .map(|(s, result)| (Box::new(s) as Box<StoreService>, result))
+                .or_else(move |(s, e)| {
+                    s.create_revert().then(move |res| {
+                        let s = match res {
+                            Ok((s, _)) => s,
+                            Err((s, _)) => s,
+                        };
+                        futures::future::err((Box::new(s) as Box<StoreService>, e))
+                    })
+                }),
+        )
+    }
+
+    /// Add user to staff of the stores they were invited to before they had an account
+    fn accept_staff_invitations(self, user_id: UserId, email: String) -> ServiceFuture<Box<StoreService>, Vec<StoreStaffMember>> {
+        Box::new(
+            self.accept_staff_invitations_happy(user_id, email)
+                .map(|(s, staff)| (Box::new(s) as Box<StoreService>, staff))
+                .or_else(|(s, e)| future::err((Box::new(s) as Box<StoreService>, e))),
+        )
+    }
+
+    /// Remove user from store staff, only store owner and superadmin may do it
+    fn remove_staff(self, store_id: StoreId, user_id: UserId) -> ServiceFuture<Box<StoreService>, ()> {
+        Box::new(
+            self.authorize_store_management(store_id)
+                .and_then(move |(s, _)| s.remove_staff_happy(store_id, user_id))
+                .map(|(s, _)| (Box::new(s) as Box<StoreService>, ()))
+                .or_else(move |(s, e)| {
+                    s.delete_revert().then(move |res| {
+                        let s = match res {
+                            Ok((s, _)) => s,
+                            Err((s, _)) => s,
+                        };
+                        futures::future::err((Box::new(s) as Box<StoreService>, e))
+                    })
+                }),
+        )
+    }
+
+    /// Staff members of the store, store owner is not included. Only store owner and superadmin may list them
+    fn get_staff(self, store_id: StoreId) -> ServiceFuture<Box<StoreService>, Vec<StoreStaffMember>> {
+        Box::new(
+            self.authorize_store_management(store_id)
+                .and_then(move |(s, _)| s.get_staff_happy(store_id))
+                .map(|(s, staff)| (Box::new(s) as Box<StoreService>, staff))
+                .or_else(|(s, e)| future::err((Box::new(s) as Box<StoreService>, e))),
+        )
+    }
+
+    /// Delete store together with its roles, merchant, stock, shipping and cart entries
+    fn delete_store(self, store_id: StoreId) -> ServiceFuture<Box<StoreService>, Store> {
+        Box::new(
+            self.delete_happy(store_id)
+                .map(|(s, store)| (Box::new(s) as Box<StoreService>, store))
+                .or_else(move |(s, e)| {
+                    s.delete_revert().then(move |res| {
+                        let s = match res {
+                            Ok((s, _)) => s,
+                            Err((s, _)) => s,
+                        };
+                        futures::future::err((Box::new(s) as Box<StoreService>, e))
+                    })
+                }),
+        )
+    }
+
+    /// Stores and base products waiting for the moderator
+    fn get_moderator_assignments(self, moderator_id: UserId) -> ServiceFuture<Box<StoreService>, Vec<ModerationAssignment>> {
+        Box::new(
+            self.moderation_queue()
+                .map(move |queue| queue.assigned_to(moderator_id))
+                .then(|res| match res {
+                    Ok(assignments) => Ok((Box::new(self) as Box<StoreService>, assignments)),
+                    Err(e) => Err((Box::new(self) as Box<StoreService>, e)),
+                }),
+        )
+    }
+
+    /// Stores and base products waiting for moderation
+    fn get_waiting_moderations(self) -> ServiceFuture<Box<StoreService>, Vec<ModerationAssignment>> {
+        Box::new(self.moderation_queue().map(|queue| queue.waiting()).then(|res| match res {
+            Ok(assignments) => Ok((Box::new(self) as Box<StoreService>, assignments)),
+            Err(e) => Err((Box::new(self) as Box<StoreService>, e)),
+        }))
+    }
+
+    /// Changes of published base product waiting for moderator's approval
+    fn get_base_product_pending_changes(
+        self,
+        base_product_id: BaseProductId,
+    ) -> ServiceFuture<Box<StoreService>, Option<UpdateBaseProduct>> {
+        Box::new(
+            self.stores_microservice
+                .get_base_product_pending_changes(Some(Initiator::Superadmin), base_product_id)
+                .then(|res| match res {
+                    Ok(changes) => Ok((Box::new(self) as Box<StoreService>, changes)),
+                    Err(e) => Err((Box::new(self) as Box<StoreService>, e)),
+                }),
+        )
+    }
+
+    /// Move subjects the assigned moderator did not handle in time to other moderators
+    fn reassign_expired_moderations(self) -> ServiceFuture<Box<StoreService>, Vec<ModerationAssignment>> {
+        Box::new(
+            self.reassign_expired_moderations()
+                .map(|(s, assignments)| (Box::new(s) as Box<StoreService>, assignments))
+                .or_else(|(s, e)| future::err((Box::new(s) as Box<StoreService>, e))),
+        )
+    }
+
+    /// Remind moderators about subjects waiting for them longer than SLA
+    fn remind_overdue_moderations(self) -> ServiceFuture<Box<StoreService>, Vec<ModerationAssignment>> {
+        Box::new(
+            self.remind_overdue_moderations()
+                .map(|(s, assignments)| (Box::new(s) as Box<StoreService>, assignments))
+                .or_else(|(s, e)| future::err((Box::new(s) as Box<StoreService>, e))),
+        )
+    }
+
+    /// Tell all moderators about subjects waiting for moderation too long
+    fn escalate_overdue_moderations(self) -> ServiceFuture<Box<StoreService>, Vec<ModerationAssignment>> {
+        Box::new(
+            self.escalate_overdue_moderations()
+                .map(|(s, assignments)| (Box::new(s) as Box<StoreService>, assignments))
+                .or_else(|(s, e)| future::err((Box::new(s) as Box<StoreService>, e))),
+        )
+    }
+
+    /// Set moderation status of many stores and base products, managers get one digest each
+    fn bulk_moderate(self, payload: BulkModeratePayload) -> ServiceFuture<Box<StoreService>, Vec<BulkModerateResult>> {
+        Box::new(
+            self.bulk_moderate_happy(payload)
+                .map(|(s, results)| (Box::new(s) as Box<StoreService>, results))
+                .or_else(|(s, e)| future::err((Box::new(s) as Box<StoreService>, e))),
+        )
+    }
 }
 
 fn fill_uids(mut payload: NewBaseProductWithVariants) -> Result<NewBaseProductWithVariants, FailureError> {